@@ -201,6 +201,10 @@ pub struct Command {
     #[clap(long = "api-request-timeout", default_value = "30m", env)]
     pub api_request_timeout: humantime::Duration,
 
+    /// Verify and repair the block id indexes of the database on startup.
+    #[arg(long = "verify-block-index", env)]
+    pub verify_block_index: bool,
+
     #[clap(flatten)]
     pub profiling: profiling::ProfilingArgs,
 }
@@ -239,6 +243,7 @@ impl Command {
             time_until_synced,
             query_log_threshold_time,
             api_request_timeout,
+            verify_block_index,
             profiling: _,
         } = self;
 
@@ -338,6 +343,7 @@ impl Command {
             min_connected_reserved_peers,
             time_until_synced: time_until_synced.into(),
             query_log_threshold_time: query_log_threshold_time.into(),
+            verify_block_index,
         };
         Ok(config)
     }
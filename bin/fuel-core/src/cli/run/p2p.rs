@@ -191,6 +191,16 @@ pub struct SyncArgs {
     /// The maximum number of headers to request in a single batch.
     #[clap(long = "sync-header-batch-size", default_value = "10", env)]
     pub header_batch_size: u32,
+    /// Don't wait for the DA layer to sync the `da_height` of the headers.
+    /// Should be used only for chains without a DA layer.
+    #[clap(long = "sync-skip-da-height", env)]
+    pub skip_da_height: bool,
+    /// Store the time spent to fetch and execute each imported block.
+    #[clap(long = "sync-record-import-timings", env)]
+    pub record_import_timings: bool,
+    /// Persist the heights that failed to import, so they survive the restart.
+    #[clap(long = "sync-persist-failed-heights", env)]
+    pub persist_failed_heights: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -223,6 +233,10 @@ impl From<SyncArgs> for fuel_core::sync::Config {
         Self {
             block_stream_buffer_size: value.block_stream_buffer_size,
             header_batch_size: value.header_batch_size as usize,
+            require_da: !value.skip_da_height,
+            record_import_timings: value.record_import_timings,
+            persist_failed_heights: value.persist_failed_heights,
+            ..Default::default()
         }
     }
 }
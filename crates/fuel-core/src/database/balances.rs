@@ -3,16 +3,37 @@ use fuel_core_storage::{
     tables::ContractsAssets,
     ContractsAssetKey,
     Error as StorageError,
+    MerkleRootStorage,
+    StorageAsRef,
     StorageBatchMutate,
 };
 use fuel_core_types::{
     fuel_asm::Word,
+    fuel_merkle::sparse::{
+        in_memory,
+        MerkleTreeKey,
+    },
     fuel_types::{
         AssetId,
+        Bytes32,
         ContractId,
     },
 };
 use itertools::Itertools;
+use thiserror::Error;
+
+/// The violation of the invariants of the contract's assets.
+#[derive(Debug, Error)]
+pub enum ReconciliationError {
+    #[error("store error occurred: {0}")]
+    StorageError(StorageError),
+    #[error("the stored root {stored} of the assets doesn't match the computed root {computed}")]
+    RootMismatch {
+        contract_id: ContractId,
+        stored: Bytes32,
+        computed: Bytes32,
+    },
+}
 
 impl Database {
     /// Initialize the balances of the contract from the all leafs.
@@ -36,6 +57,42 @@ impl Database {
             &mut balances.iter().map(|(key, value)| (key, value)),
         )
     }
+
+    /// Checks that the balances stored for the contract are consistent with
+    /// the stored Merkle root of its assets. Returns the first violation.
+    ///
+    /// It scans all assets of the contract, so it is meant for debugging
+    /// and integrity checks rather than the regular block processing.
+    pub fn reconcile_contract_assets(
+        &self,
+        contract_id: &ContractId,
+    ) -> Result<(), ReconciliationError> {
+        let balances = self
+            .contract_balances(*contract_id, None, None)
+            .map(|res| {
+                res.map(|(asset, balance)| {
+                    (ContractsAssetKey::new(contract_id, &asset), balance)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ReconciliationError::StorageError)?;
+        let (computed, _) = in_memory::MerkleTree::nodes_from_set(balances.iter().map(
+            |(key, balance)| (MerkleTreeKey::new(key.as_ref()), balance.to_be_bytes()),
+        ));
+        let stored = self
+            .storage::<ContractsAssets>()
+            .root(contract_id)
+            .map_err(ReconciliationError::StorageError)?;
+
+        if stored != computed {
+            return Err(ReconciliationError::RootMismatch {
+                contract_id: *contract_id,
+                stored: stored.into(),
+                computed: computed.into(),
+            })
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -109,4 +166,60 @@ mod tests {
             assert_eq!(seq_value, value);
         }
     }
+
+    #[test]
+    fn reconcile_contract_assets_accepts_consistent_balances() {
+        use rand::SeedableRng;
+
+        let rng = &mut rand::rngs::StdRng::seed_from_u64(1234);
+        let contract_id = ContractId::from([1u8; 32]);
+        let database = &mut Database::<OnChain>::default();
+        for balance in 1..=10 {
+            let key = ContractsAssetKey::new(&contract_id, &random_asset_id(rng));
+            database
+                .storage::<ContractsAssets>()
+                .insert(&key, &balance)
+                .unwrap();
+        }
+
+        database
+            .reconcile_contract_assets(&contract_id)
+            .expect("The balances should be consistent");
+    }
+
+    #[test]
+    fn reconcile_contract_assets_reports_balance_written_past_the_merkle_tree() {
+        use fuel_core_storage::{
+            column::Column,
+            kv_store::KeyValueStore,
+        };
+        use rand::SeedableRng;
+
+        let rng = &mut rand::rngs::StdRng::seed_from_u64(1234);
+        let contract_id = ContractId::from([1u8; 32]);
+        let key = ContractsAssetKey::new(&contract_id, &random_asset_id(rng));
+        let database = &mut Database::<OnChain>::default();
+        database
+            .storage::<ContractsAssets>()
+            .insert(&key, &100)
+            .unwrap();
+
+        // Overwrite the balance without updating the Merkle tree.
+        let inconsistent: Word = 200;
+        database
+            .data
+            .as_ref()
+            .put(
+                key.as_ref(),
+                Column::ContractsAssets,
+                std::sync::Arc::new(inconsistent.to_be_bytes().to_vec()),
+            )
+            .unwrap();
+
+        let result = database.reconcile_contract_assets(&contract_id);
+        assert!(matches!(
+            result,
+            Err(ReconciliationError::RootMismatch { contract_id: id, .. }) if id == contract_id
+        ));
+    }
 }
@@ -23,6 +23,7 @@ use fuel_core_storage::{
             FuelBlockMerkleMetadata,
         },
         FuelBlocks,
+        SealedBlockConsensus,
         Transactions,
     },
     Error as StorageError,
@@ -44,12 +45,19 @@ use fuel_core_types::{
     },
     entities::message::MerkleProof,
     fuel_merkle::binary::MerkleTree,
-    fuel_types::BlockHeight,
+    fuel_types::{
+        BlockHeight,
+        Bytes32,
+    },
 };
 use itertools::Itertools;
-use std::borrow::{
-    BorrowMut,
-    Cow,
+use std::{
+    borrow::{
+        BorrowMut,
+        Cow,
+    },
+    collections::HashMap,
+    ops::Range,
 };
 
 /// The table of fuel block's secondary key - `BlockId`.
@@ -81,6 +89,93 @@ fuel_core_storage::basic_storage_tests!(
     <FuelBlockSecondaryKeyBlockHeights as Mappable>::Value::default()
 );
 
+/// The table of fuel block's ids.
+/// It links the `BlockHeight` to corresponding `BlockId`, the reverse of
+/// the [`FuelBlockSecondaryKeyBlockHeights`].
+pub struct FuelBlockIds;
+
+impl Mappable for FuelBlockIds {
+    /// Primary key - `BlockHeight`.
+    type Key = BlockHeight;
+    type OwnedKey = Self::Key;
+    /// The id of the block at the height.
+    type Value = BlockId;
+    type OwnedValue = Self::Value;
+}
+
+impl TableWithBlueprint for FuelBlockIds {
+    type Blueprint = Plain<Primitive<4>, Raw>;
+    type Column = fuel_core_storage::column::Column;
+
+    fn column() -> Self::Column {
+        Self::Column::FuelBlockIds
+    }
+}
+
+#[cfg(test)]
+fuel_core_storage::basic_storage_tests!(
+    FuelBlockIds,
+    <FuelBlockIds as Mappable>::Key::default(),
+    <FuelBlockIds as Mappable>::Value::default()
+);
+
+/// The inconsistency of the committed chain found by [`Database::verify_chain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainInconsistency {
+    /// The block at the height is missing.
+    MissingBlock(BlockHeight),
+    /// The parent of the block at the height is missing.
+    MissingParent(BlockHeight),
+    /// The `prev_root` of the block at the height doesn't match its parent.
+    ParentMismatch(BlockHeight),
+    /// The `BlockId -> BlockHeight` or `BlockHeight -> BlockId` index doesn't point to
+    /// the block at the height.
+    IndexMismatch(BlockHeight),
+    /// The consensus of the block at the height is missing.
+    MissingConsensus(BlockHeight),
+}
+
+/// The result of [`Database::verify_chain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainReport {
+    /// The number of blocks verified before the first inconsistency.
+    pub verified: usize,
+    /// The first inconsistency found, `None` if the chain is consistent.
+    pub inconsistency: Option<ChainInconsistency>,
+}
+
+/// The proof that a block is part of the committed chain,
+/// produced by [`Database::prove_block`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockProof {
+    /// The id of the proven block.
+    pub block_id: BlockId,
+    /// The root of the block commitments at the tip of the chain.
+    pub root: Bytes32,
+    /// The number of blocks committed by the `root`.
+    pub blocks_count: u64,
+    /// The proof of the inclusion of the `block_id` into the `root`.
+    pub proof: MerkleProof,
+}
+
+/// Returns `true` if the `proof` shows that its block is committed by its root.
+/// The caller is responsible for checking that the root is the trusted one.
+pub fn verify_block_proof(proof: &BlockProof) -> bool {
+    let proof_set = proof
+        .proof
+        .proof_set
+        .iter()
+        .map(|node| **node)
+        .collect::<Vec<_>>();
+    fuel_core_types::fuel_merkle::binary::verify(
+        &proof.root,
+        &proof.block_id.as_slice(),
+        &proof_set,
+        proof.proof.proof_index,
+        proof.blocks_count,
+    )
+}
+
 impl StorageInspect<FuelBlocks> for Database {
     type Error = StorageError;
 
@@ -114,6 +209,7 @@ impl StorageMutate<FuelBlocks> for Database {
         let block_id = value.id();
         self.storage::<FuelBlockSecondaryKeyBlockHeights>()
             .insert(&block_id, key)?;
+        self.storage::<FuelBlockIds>().insert(key, &block_id)?;
 
         // Get latest metadata entry
         let prev_metadata = self
@@ -162,6 +258,7 @@ impl StorageMutate<FuelBlocks> for Database {
             let _ = self
                 .storage::<FuelBlockSecondaryKeyBlockHeights>()
                 .remove(&block.id());
+            let _ = self.storage::<FuelBlockIds>().remove(height);
             // We can't clean up `MerkleTree<FuelBlockMerkleData>`.
             // But if we plan to insert a new block, it will override old values in the
             // `FuelBlockMerkleData` table.
@@ -193,6 +290,122 @@ impl Database {
             .map(|v| v.map(|v| v.into_owned()))
     }
 
+    /// Verifies that the `BlockId -> BlockHeight` and `BlockHeight -> BlockId` indexes
+    /// match the `FuelBlocks` table and repairs them in place. Missing or mismatched
+    /// entries are rewritten, and entries pointing to unknown blocks are removed.
+    ///
+    /// Returns the number of repaired entries.
+    pub fn verify_block_heights_index(&mut self) -> StorageResult<usize> {
+        let expected: HashMap<BlockId, BlockHeight> = self
+            .iter_all::<FuelBlocks>(None)
+            .map_ok(|(height, block)| (block.id(), height))
+            .try_collect()?;
+        let indexed_heights: Vec<(BlockId, BlockHeight)> = self
+            .iter_all::<FuelBlockSecondaryKeyBlockHeights>(None)
+            .try_collect()?;
+        let indexed_ids: Vec<(BlockHeight, BlockId)> =
+            self.iter_all::<FuelBlockIds>(None).try_collect()?;
+
+        let mut repaired = 0usize;
+        for (block_id, _) in indexed_heights {
+            if !expected.contains_key(&block_id) {
+                self.storage::<FuelBlockSecondaryKeyBlockHeights>()
+                    .remove(&block_id)?;
+                repaired = repaired.saturating_add(1);
+            }
+        }
+        for (height, _) in indexed_ids {
+            if !self.storage::<FuelBlocks>().contains_key(&height)? {
+                self.storage::<FuelBlockIds>().remove(&height)?;
+                repaired = repaired.saturating_add(1);
+            }
+        }
+
+        for (block_id, height) in expected {
+            let indexed_height = self.get_block_height(&block_id)?;
+            if indexed_height != Some(height) {
+                self.storage::<FuelBlockSecondaryKeyBlockHeights>()
+                    .insert(&block_id, &height)?;
+                repaired = repaired.saturating_add(1);
+            }
+            let indexed_id = self.storage::<FuelBlockIds>().get(&height)?;
+            if indexed_id.as_deref() != Some(&block_id) {
+                self.storage::<FuelBlockIds>().insert(&height, &block_id)?;
+                repaired = repaired.saturating_add(1);
+            }
+        }
+
+        Ok(repaired)
+    }
+
+    /// Walks the committed blocks in the `range` and verifies that each block links
+    /// to its parent, and that `FuelBlocks`, `FuelBlockSecondaryKeyBlockHeights`,
+    /// `FuelBlockIds` and `SealedBlockConsensus` are consistent with each other.
+    ///
+    /// Stops at the first inconsistency. The lowest stored block is the genesis,
+    /// so it doesn't need a parent.
+    pub fn verify_chain(&self, range: Range<u32>) -> StorageResult<ChainReport> {
+        let genesis_height = self
+            .iter_all::<FuelBlocks>(None)
+            .next()
+            .transpose()?
+            .map(|(height, _)| height);
+        let mut report = ChainReport {
+            verified: 0,
+            inconsistency: None,
+        };
+        for height in range.map(BlockHeight::from) {
+            let inconsistency = self.verify_chain_link(&height, genesis_height)?;
+            if inconsistency.is_some() {
+                report.inconsistency = inconsistency;
+                break
+            }
+            report.verified = report.verified.saturating_add(1);
+        }
+        Ok(report)
+    }
+
+    fn verify_chain_link(
+        &self,
+        height: &BlockHeight,
+        genesis_height: Option<BlockHeight>,
+    ) -> StorageResult<Option<ChainInconsistency>> {
+        let Some(block) = self.storage::<FuelBlocks>().get(height)? else {
+            return Ok(Some(ChainInconsistency::MissingBlock(*height)))
+        };
+
+        let block_id = block.id();
+        if self.get_block_height(&block_id)? != Some(*height) {
+            return Ok(Some(ChainInconsistency::IndexMismatch(*height)))
+        }
+        let indexed_id = self.storage::<FuelBlockIds>().get(height)?;
+        if indexed_id.as_deref() != Some(&block_id) {
+            return Ok(Some(ChainInconsistency::IndexMismatch(*height)))
+        }
+
+        if !self
+            .storage::<SealedBlockConsensus>()
+            .contains_key(height)?
+        {
+            return Ok(Some(ChainInconsistency::MissingConsensus(*height)))
+        }
+
+        if Some(*height) != genesis_height {
+            let Some(prev_height) = height.pred() else {
+                return Ok(Some(ChainInconsistency::MissingParent(*height)))
+            };
+            if !self.storage::<FuelBlocks>().contains_key(&prev_height)? {
+                return Ok(Some(ChainInconsistency::MissingParent(*height)))
+            }
+            let prev_root = self.storage::<FuelBlocks>().root(&prev_height)?;
+            if *block.header().prev_root() != Bytes32::from(prev_root) {
+                return Ok(Some(ChainInconsistency::ParentMismatch(*height)))
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Retrieve the full block and all associated transactions
     pub(crate) fn get_full_block(
         &self,
@@ -272,12 +485,39 @@ impl Database {
             proof_index,
         })
     }
+
+    /// Produces the proof that the block at the `height` is committed by
+    /// the root of the block commitments at the current tip of the chain.
+    pub fn prove_block(&self, height: &BlockHeight) -> StorageResult<BlockProof> {
+        let block_id = self
+            .storage::<FuelBlocks>()
+            .get(height)?
+            .ok_or(not_found!(FuelBlocks))?
+            .id();
+        let tip = self
+            .latest_compressed_block()?
+            .ok_or(not_found!(FuelBlocks))?;
+        let tip_height = tip.header().height();
+        let tip_metadata = self
+            .storage::<FuelBlockMerkleMetadata>()
+            .get(tip_height)?
+            .ok_or(not_found!(FuelBlockMerkleMetadata))?;
+        let proof = self.block_history_proof(height, tip_height)?;
+
+        Ok(BlockProof {
+            block_id,
+            root: Bytes32::from(*tip_metadata.root()),
+            blocks_count: tip_metadata.version(),
+            proof,
+        })
+    }
 }
 
 #[allow(clippy::arithmetic_side_effects)]
 #[cfg(test)]
 mod tests {
     use super::*;
+    use fuel_core_importer::ports::ExecutorDatabase;
     use fuel_core_types::{
         blockchain::{
             block::PartialFuelBlock,
@@ -286,6 +526,7 @@ mod tests {
                 PartialBlockHeader,
             },
             primitives::Empty,
+            SealedBlock,
         },
         fuel_types::ChainId,
         fuel_vm::crypto::ephemeral_merkle_root,
@@ -440,4 +681,202 @@ mod tests {
         );
         assert!(result.is_err());
     }
+
+    #[test]
+    fn prove_block__proof_of_committed_block_is_verified() {
+        // given
+        let mut database = Database::default();
+        insert_test_ascending_blocks(&mut database, BlockHeight::from(100));
+        let height = BlockHeight::from(103);
+
+        // when
+        let proof = database.prove_block(&height).unwrap();
+
+        // then
+        let expected_id = database
+            .storage::<FuelBlocks>()
+            .get(&height)
+            .unwrap()
+            .unwrap()
+            .id();
+        assert_eq!(proof.block_id, expected_id);
+        assert_eq!(proof.blocks_count, TEST_BLOCKS_COUNT as u64);
+        assert!(verify_block_proof(&proof));
+    }
+
+    #[test]
+    fn verify_block_proof__tampered_proof_is_rejected() {
+        // given
+        let mut database = Database::default();
+        insert_test_ascending_blocks(&mut database, BlockHeight::from(0));
+        let proof = database.prove_block(&BlockHeight::from(3)).unwrap();
+        let mut tampered = proof.clone();
+        tampered.block_id = BlockId::from([0xAA; 32]);
+
+        // when
+        let verified = verify_block_proof(&tampered);
+
+        // then
+        assert!(!verified);
+    }
+
+    #[test]
+    fn verify_block_heights_index_repairs_corrupted_entries() {
+        let mut database = Database::default();
+        insert_test_ascending_blocks(&mut database, BlockHeight::from(0));
+        let blocks: Vec<(BlockHeight, BlockId)> = database
+            .iter_all::<FuelBlocks>(None)
+            .map_ok(|(height, block)| (height, block.id()))
+            .try_collect()
+            .unwrap();
+
+        // given
+        let (_, missing_id) = blocks[0];
+        let (_, mismatched_id) = blocks[1];
+        let stale_id = BlockId::from([0xAA; 32]);
+        database
+            .storage::<FuelBlockSecondaryKeyBlockHeights>()
+            .remove(&missing_id)
+            .unwrap();
+        database
+            .storage::<FuelBlockSecondaryKeyBlockHeights>()
+            .insert(&mismatched_id, &BlockHeight::from(1000))
+            .unwrap();
+        database
+            .storage::<FuelBlockSecondaryKeyBlockHeights>()
+            .insert(&stale_id, &BlockHeight::from(2))
+            .unwrap();
+        let (missing_height, _) = blocks[2];
+        let (mismatched_height, _) = blocks[3];
+        let stale_height = BlockHeight::from(1000);
+        database
+            .storage::<FuelBlockIds>()
+            .remove(&missing_height)
+            .unwrap();
+        database
+            .storage::<FuelBlockIds>()
+            .insert(&mismatched_height, &stale_id)
+            .unwrap();
+        database
+            .storage::<FuelBlockIds>()
+            .insert(&stale_height, &stale_id)
+            .unwrap();
+
+        // when
+        let repaired = database.verify_block_heights_index().unwrap();
+
+        // then
+        assert_eq!(repaired, 6);
+        for (height, block_id) in &blocks {
+            assert_eq!(database.get_block_height(block_id).unwrap(), Some(*height));
+            let indexed_id = database.storage::<FuelBlockIds>().get(height).unwrap();
+            assert_eq!(indexed_id.as_deref(), Some(block_id));
+        }
+        assert_eq!(database.get_block_height(&stale_id).unwrap(), None);
+        assert!(!database
+            .storage::<FuelBlockIds>()
+            .contains_key(&stale_height)
+            .unwrap());
+        assert_eq!(database.verify_block_heights_index().unwrap(), 0);
+    }
+
+    fn store_linked_blocks(database: &mut Database, heights: Range<u32>) {
+        for height in heights.map(BlockHeight::from) {
+            let mut block = Block::default();
+            block.header_mut().set_block_height(height);
+            if let Some(prev_height) = height.pred() {
+                let prev_root =
+                    database.storage::<FuelBlocks>().root(&prev_height).unwrap();
+                block.header_mut().set_previous_root(prev_root.into());
+            }
+            let block = SealedBlock {
+                entity: block,
+                consensus: Default::default(),
+            };
+            database
+                .store_new_block(&ChainId::default(), &block)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn verify_chain_passes_on_the_healthy_chain() {
+        // given
+        let mut database = Database::default();
+        store_linked_blocks(&mut database, 0..10);
+
+        // when
+        let report = database.verify_chain(0..10).unwrap();
+
+        // then
+        assert_eq!(
+            report,
+            ChainReport {
+                verified: 10,
+                inconsistency: None,
+            }
+        );
+    }
+
+    #[test]
+    fn verify_chain_reports_the_height_of_the_broken_link() {
+        // given
+        let mut database = Database::default();
+        store_linked_blocks(&mut database, 0..10);
+        let broken_height = BlockHeight::from(6);
+        let mut block = Block::default();
+        block.header_mut().set_block_height(broken_height);
+        block
+            .header_mut()
+            .set_previous_root(Bytes32::from([0xAA; 32]));
+        database
+            .data
+            .storage_as_mut::<FuelBlocks>()
+            .insert(&broken_height, &block.compress(&ChainId::default()))
+            .unwrap();
+        database
+            .storage::<FuelBlockSecondaryKeyBlockHeights>()
+            .insert(&block.id(), &broken_height)
+            .unwrap();
+        database
+            .storage::<FuelBlockIds>()
+            .insert(&broken_height, &block.id())
+            .unwrap();
+
+        // when
+        let report = database.verify_chain(0..10).unwrap();
+
+        // then
+        assert_eq!(
+            report,
+            ChainReport {
+                verified: 6,
+                inconsistency: Some(ChainInconsistency::ParentMismatch(broken_height)),
+            }
+        );
+    }
+
+    #[test]
+    fn verify_chain_reports_the_mismatch_of_the_block_ids_index() {
+        // given
+        let mut database = Database::default();
+        store_linked_blocks(&mut database, 0..10);
+        let broken_height = BlockHeight::from(4);
+        database
+            .storage::<FuelBlockIds>()
+            .insert(&broken_height, &BlockId::from([0xAA; 32]))
+            .unwrap();
+
+        // when
+        let report = database.verify_chain(0..10).unwrap();
+
+        // then
+        assert_eq!(
+            report,
+            ChainReport {
+                verified: 4,
+                inconsistency: Some(ChainInconsistency::IndexMismatch(broken_height)),
+            }
+        );
+    }
 }
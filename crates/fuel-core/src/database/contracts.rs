@@ -12,6 +12,7 @@ use fuel_core_storage::{
     ContractsAssetKey,
     Result as StorageResult,
     StorageAsRef,
+    StorageSize,
 };
 use fuel_core_types::{
     entities::contract::ContractUtxoInfo,
@@ -87,6 +88,16 @@ impl Database {
         })
     }
 
+    /// Returns the size of the contract's bytecode without loading it.
+    /// The code is stored as raw bytes, so the size of the stored value is
+    /// the length of the code.
+    pub fn contract_code_size(
+        &self,
+        contract_id: &ContractId,
+    ) -> StorageResult<Option<usize>> {
+        StorageSize::<ContractsRawCode>::size_of_value(self, contract_id)
+    }
+
     pub fn contract_balances(
         &self,
         contract: ContractId,
@@ -149,4 +160,29 @@ mod tests {
             .into_owned();
         assert_eq!(returned, contract);
     }
+
+    #[test]
+    fn contract_code_size_matches_the_stored_code_length() {
+        let contract_id = ContractId::from([1u8; 32]);
+        let contract = Contract::from(vec![7u8; 1234]);
+
+        let database = &mut Database::<OnChain>::default();
+        database
+            .storage::<ContractsRawCode>()
+            .insert(&contract_id, contract.as_ref())
+            .unwrap();
+
+        let size = database.contract_code_size(&contract_id).unwrap();
+        assert_eq!(size, Some(contract.as_ref().len()));
+    }
+
+    #[test]
+    fn contract_code_size_is_none_for_the_absent_contract() {
+        let database = Database::<OnChain>::default();
+
+        let size = database
+            .contract_code_size(&ContractId::from([2u8; 32]))
+            .unwrap();
+        assert_eq!(size, None);
+    }
 }
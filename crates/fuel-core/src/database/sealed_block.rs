@@ -1,11 +1,23 @@
-use crate::database::Database;
+use crate::database::{
+    database_description::on_chain::OnChain,
+    metadata::MetadataTable,
+    Database,
+};
 use fuel_core_storage::{
+    blueprint::plain::Plain,
+    codec::{
+        postcard::Postcard,
+        primitive::Primitive,
+    },
     iter::IterDirection,
     not_found,
+    structured_storage::TableWithBlueprint,
     tables::{
         FuelBlocks,
         SealedBlockConsensus,
     },
+    Error as StorageError,
+    Mappable,
     Result as StorageResult,
     StorageAsRef,
 };
@@ -22,7 +34,43 @@ use fuel_core_types::{
     fuel_types::BlockHeight,
     services::p2p::Transactions,
 };
-use std::ops::Range;
+use std::{
+    io::{
+        ErrorKind,
+        Read,
+        Write,
+    },
+    ops::Range,
+};
+
+/// The table of the sealed block headers imported without the transactions
+/// of their blocks, e.g. by the headers only synchronization.
+pub struct SealedBlockHeaders;
+
+impl Mappable for SealedBlockHeaders {
+    /// Primary key - `BlockHeight`.
+    type Key = BlockHeight;
+    type OwnedKey = Self::Key;
+    /// The sealed header of the block at the height.
+    type Value = SealedBlockHeader;
+    type OwnedValue = Self::Value;
+}
+
+impl TableWithBlueprint for SealedBlockHeaders {
+    type Blueprint = Plain<Primitive<4>, Postcard>;
+    type Column = fuel_core_storage::column::Column;
+
+    fn column() -> Self::Column {
+        Self::Column::SealedBlockHeaders
+    }
+}
+
+#[cfg(test)]
+fuel_core_storage::basic_storage_tests!(
+    SealedBlockHeaders,
+    <SealedBlockHeaders as Mappable>::Key::default(),
+    <SealedBlockHeaders as Mappable>::Value::default()
+);
 
 impl Database {
     /// Returns `SealedBlock` by `height`.
@@ -74,6 +122,10 @@ impl Database {
         Ok(headers)
     }
 
+    /// Returns the `SealedBlockHeader` of the stored block at the `height`.
+    ///
+    /// The headers from the [`SealedBlockHeaders`] are not returned, so they
+    /// are never served to the peers that would request their transactions next.
     pub fn get_sealed_block_header(
         &self,
         height: &BlockHeight,
@@ -93,6 +145,70 @@ impl Database {
         }
     }
 
+    /// Returns the height of the highest header stored in the [`SealedBlockHeaders`],
+    /// so the headers only import can resume from it after a restart.
+    pub fn latest_sealed_header_height(&self) -> StorageResult<Option<BlockHeight>> {
+        let pair = self
+            .iter_all::<SealedBlockHeaders>(Some(IterDirection::Reverse))
+            .next()
+            .transpose()?;
+        Ok(pair.map(|(height, _)| height))
+    }
+
+    /// Returns the `SealedBlockHeader` of the block at the committed height
+    /// without loading its transactions.
+    ///
+    /// The header is read with [`Self::get_sealed_block_header`], not from the
+    /// [`SealedBlockHeaders`], because that table only holds the headers imported
+    /// without their blocks, which are above the committed height.
+    pub fn latest_committed_header(&self) -> StorageResult<Option<SealedBlockHeader>> {
+        let Some(metadata) = self.storage::<MetadataTable<OnChain>>().get(&())? else {
+            return Ok(None)
+        };
+        self.get_sealed_block_header(metadata.height())
+    }
+
+    /// Writes the committed sealed blocks of the `range` to the `writer`.
+    /// Each block is encoded with `postcard` and prefixed by its length as
+    /// a little-endian `u32`. Use [`read_exported_blocks`] to decode them.
+    ///
+    /// Fails with the missing height before writing anything
+    /// if some block of the `range` is not committed.
+    pub fn export_range<W: Write>(
+        &self,
+        range: Range<u32>,
+        mut writer: W,
+    ) -> StorageResult<()> {
+        for height in range.clone().map(BlockHeight::from) {
+            let committed = self.storage::<FuelBlocks>().contains_key(&height)?
+                && self
+                    .storage::<SealedBlockConsensus>()
+                    .contains_key(&height)?;
+            if !committed {
+                Err(anyhow::anyhow!(
+                    "The block at height {} is missing",
+                    *height
+                ))?;
+            }
+        }
+
+        for height in range.map(BlockHeight::from) {
+            let block = self
+                .get_sealed_block_by_height(&height)?
+                .ok_or(not_found!(FuelBlocks))?;
+            let bytes = postcard::to_allocvec(&block)
+                .map_err(|e| StorageError::Codec(anyhow::anyhow!(e)))?;
+            let len = u32::try_from(bytes.len()).map_err(|_| {
+                anyhow::anyhow!("The block at height {} is too big", *height)
+            })?;
+            writer
+                .write_all(&len.to_le_bytes())
+                .and_then(|_| writer.write_all(&bytes))
+                .map_err(|e| StorageError::Other(e.into()))?;
+        }
+        writer.flush().map_err(|e| StorageError::Other(e.into()))
+    }
+
     pub fn get_transactions_on_blocks(
         &self,
         block_height_range: Range<u32>,
@@ -111,3 +227,158 @@ impl Database {
         Ok(transactions)
     }
 }
+
+/// Decodes the sealed blocks written by [`Database::export_range`].
+pub fn read_exported_blocks<R: Read>(
+    mut reader: R,
+) -> impl Iterator<Item = StorageResult<SealedBlock>> {
+    core::iter::from_fn(move || read_exported_block(&mut reader).transpose())
+}
+
+fn read_exported_block<R: Read>(reader: &mut R) -> StorageResult<Option<SealedBlock>> {
+    let mut len = [0u8; 4];
+    let mut read = 0usize;
+    while read < len.len() {
+        match reader.read(&mut len[read..]) {
+            // The end of the stream between the blocks.
+            Ok(0) if read == 0 => return Ok(None),
+            Ok(0) => Err(anyhow::anyhow!("The length of the block is truncated"))?,
+            Ok(n) => read = read.saturating_add(n),
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => Err(StorageError::Other(e.into()))?,
+        }
+    }
+
+    let len = usize::try_from(u32::from_le_bytes(len))
+        .map_err(|e| StorageError::Other(e.into()))?;
+    let mut bytes = vec![0u8; len];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(|e| StorageError::Other(e.into()))?;
+    let block = postcard::from_bytes(&bytes)
+        .map_err(|e| StorageError::Codec(anyhow::anyhow!(e)))?;
+    Ok(Some(block))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuel_core_importer::ports::ExecutorDatabase;
+    use fuel_core_storage::StorageAsMut;
+    use fuel_core_types::{
+        blockchain::block::Block,
+        fuel_types::ChainId,
+    };
+
+    fn store_blocks(database: &mut Database, heights: Range<u32>) {
+        for height in heights {
+            let mut block = Block::default();
+            block.header_mut().set_block_height(height.into());
+            let block = SealedBlock {
+                entity: block,
+                consensus: Default::default(),
+            };
+            database
+                .store_new_block(&ChainId::default(), &block)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn latest_committed_header_is_the_header_of_the_committed_height() {
+        // given
+        let mut database = Database::default();
+        assert_eq!(database.latest_committed_header().unwrap(), None);
+        store_blocks(&mut database, 0..5);
+        let mut imported_header = SealedBlockHeader::default();
+        imported_header.entity.set_block_height(5u32.into());
+        database
+            .storage_as_mut::<SealedBlockHeaders>()
+            .insert(&5u32.into(), &imported_header)
+            .unwrap();
+
+        // when
+        let header = database.latest_committed_header().unwrap();
+
+        // then
+        let header = header.expect("The blocks are committed");
+        assert_eq!(*header.entity.height(), 4u32.into());
+        assert_eq!(
+            Some(header),
+            database.get_sealed_block_header(&4u32.into()).unwrap()
+        );
+    }
+
+    #[test]
+    fn exported_range_can_be_imported_back() {
+        // given
+        let mut database = Database::default();
+        store_blocks(&mut database, 0..5);
+
+        // when
+        let mut exported = vec![];
+        database.export_range(1..4, &mut exported).unwrap();
+
+        // then
+        let mut imported = Database::default();
+        for block in read_exported_blocks(exported.as_slice()) {
+            imported
+                .store_new_block(&ChainId::default(), &block.unwrap())
+                .unwrap();
+        }
+        for height in (1..4).map(BlockHeight::from) {
+            assert_eq!(
+                imported.get_sealed_block_by_height(&height).unwrap(),
+                database.get_sealed_block_by_height(&height).unwrap(),
+            );
+        }
+        assert_eq!(
+            imported.get_sealed_block_by_height(&0.into()).unwrap(),
+            None
+        );
+        assert_eq!(
+            imported.get_sealed_block_by_height(&4.into()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn export_range_fails_on_the_missing_height() {
+        // given
+        let mut database = Database::default();
+        store_blocks(&mut database, 0..3);
+        store_blocks(&mut database, 4..6);
+
+        // when
+        let mut exported = vec![];
+        let result = database.export_range(0..6, &mut exported);
+
+        // then
+        let err = result.expect_err("The height 3 is missing");
+        assert!(err.to_string().contains("height 3"), "{err}");
+        assert!(exported.is_empty());
+    }
+    #[test]
+    fn headers_only_headers_are_not_returned_as_the_block_headers() {
+        // given
+        let mut database = Database::default();
+        store_blocks(&mut database, 0..2);
+        let mut header = SealedBlockHeader::default();
+        header.entity.set_block_height(5u32.into());
+        database
+            .storage_as_mut::<SealedBlockHeaders>()
+            .insert(&5u32.into(), &header)
+            .unwrap();
+
+        // when
+        let headers = database.get_sealed_block_headers(0..6).unwrap();
+
+        // then
+        assert_eq!(headers.len(), 2);
+        assert_eq!(database.get_sealed_block_header(&5u32.into()).unwrap(), None);
+        assert_eq!(
+            database.latest_sealed_header_height().unwrap(),
+            Some(5u32.into())
+        );
+    }
+}
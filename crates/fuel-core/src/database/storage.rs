@@ -1,12 +1,18 @@
 use crate::{
     database::{
-        block::FuelBlockSecondaryKeyBlockHeights,
+        block::{
+            FuelBlockIds,
+            FuelBlockSecondaryKeyBlockHeights,
+        },
         coin::OwnedCoins,
         database_description::DatabaseDescription,
         message::OwnedMessageIds,
+        sealed_block::SealedBlockHeaders,
         Database,
     },
     fuel_core_graphql_api::storage::{
+        failed_heights::FailedHeights,
+        import_timings::BlockImportTimings,
         receipts::Receipts,
         transactions::{
             OwnedTransactions,
@@ -47,6 +53,7 @@ use fuel_core_storage::{
     StorageInspect,
     StorageMutate,
     StorageRead,
+    StorageReplace,
     StorageSize,
 };
 use std::borrow::Cow;
@@ -85,6 +92,8 @@ use_structured_implementation!(
     Transactions,
     ProcessedTransactions,
     Receipts,
+    BlockImportTimings,
+    FailedHeights,
     ContractsStateMerkleMetadata,
     ContractsStateMerkleData,
     ContractsAssetsMerkleMetadata,
@@ -94,6 +103,8 @@ use_structured_implementation!(
     OwnedTransactions,
     TransactionStatuses,
     FuelBlockSecondaryKeyBlockHeights,
+    FuelBlockIds,
+    SealedBlockHeaders,
     FuelBlockMerkleData,
     FuelBlockMerkleMetadata
 );
@@ -141,6 +152,22 @@ where
     }
 }
 
+impl<Description, M> StorageReplace<M> for Database<Description>
+where
+    Description: DatabaseDescription,
+    M: Mappable,
+    StructuredStorage<DataSource<Description>>:
+        StorageReplace<M, Error = StorageError> + UseStructuredImplementation<M>,
+{
+    fn replace(
+        &mut self,
+        key: &M::Key,
+        value: &M::Value,
+    ) -> StorageResult<Option<M::OwnedValue>> {
+        StorageReplace::replace(&mut self.data, key, value)
+    }
+}
+
 impl<Description, Key, M> MerkleRootStorage<Key, M> for Database<Description>
 where
     Description: DatabaseDescription,
@@ -1,5 +1,7 @@
 use fuel_core_storage::kv_store::StorageColumn;
 
+pub mod failed_heights;
+pub mod import_timings;
 pub mod receipts;
 pub mod transactions;
 
@@ -31,6 +33,10 @@ pub enum Column {
     OwnedMessageIds = 5,
     /// The column of the table that stores statistic about the blockchain.
     Statistic = 6,
+    /// See [`BlockImportTimings`](import_timings::BlockImportTimings)
+    BlockImportTimings = 7,
+    /// See [`FailedHeights`](failed_heights::FailedHeights)
+    FailedHeights = 8,
 }
 
 impl Column {
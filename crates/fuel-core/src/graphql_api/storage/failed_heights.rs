@@ -0,0 +1,32 @@
+use fuel_core_storage::{
+    blueprint::plain::Plain,
+    codec::postcard::Postcard,
+    structured_storage::TableWithBlueprint,
+    Mappable,
+};
+use std::ops::RangeInclusive;
+
+/// The table stores the heights that failed to import from the network,
+/// so they are not lost on the restart of the node.
+pub struct FailedHeights;
+
+impl Mappable for FailedHeights {
+    /// The table has only one entry.
+    type Key = Self::OwnedKey;
+    type OwnedKey = ();
+    type Value = Self::OwnedValue;
+    /// The disjoint ranges of the failed heights.
+    type OwnedValue = Vec<RangeInclusive<u32>>;
+}
+
+impl TableWithBlueprint for FailedHeights {
+    type Blueprint = Plain<Postcard, Postcard>;
+    type Column = super::Column;
+
+    fn column() -> Self::Column {
+        Self::Column::FailedHeights
+    }
+}
+
+#[cfg(test)]
+fuel_core_storage::basic_storage_tests!(FailedHeights, (), vec![4..=7, 10..=12]);
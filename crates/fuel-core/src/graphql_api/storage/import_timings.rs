@@ -0,0 +1,50 @@
+use fuel_core_storage::{
+    blueprint::plain::Plain,
+    codec::{
+        postcard::Postcard,
+        primitive::Primitive,
+    },
+    structured_storage::TableWithBlueprint,
+    Mappable,
+};
+use fuel_core_types::fuel_types::BlockHeight;
+
+/// The table stores the time spent to import each block from the network.
+pub struct BlockImportTimings;
+
+impl Mappable for BlockImportTimings {
+    type Key = Self::OwnedKey;
+    type OwnedKey = BlockHeight;
+    type Value = Self::OwnedValue;
+    type OwnedValue = ImportTimings;
+}
+
+impl TableWithBlueprint for BlockImportTimings {
+    type Blueprint = Plain<Primitive<4>, Postcard>;
+    type Column = super::Column;
+
+    fn column() -> Self::Column {
+        Self::Column::BlockImportTimings
+    }
+}
+
+/// The time spent to import a block, in milliseconds.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub struct ImportTimings {
+    /// The time to fetch the transactions of the block from the network.
+    pub fetch_ms: u64,
+    /// The time to execute and commit the block.
+    pub execute_ms: u64,
+}
+
+#[cfg(test)]
+fuel_core_storage::basic_storage_tests!(
+    BlockImportTimings,
+    <BlockImportTimings as Mappable>::Key::from(1u32),
+    ImportTimings {
+        fetch_ms: 15,
+        execute_ms: 40,
+    }
+);
@@ -225,6 +225,14 @@ impl Task {
         let da_block_height = 0u64.into();
         database.init(&block_height, &da_block_height)?;
 
+        if config.verify_block_index {
+            tracing::info!("Verifying the block index");
+            let repaired = database.on_chain_mut().verify_block_heights_index()?;
+            if repaired > 0 {
+                tracing::warn!("Repaired {repaired} entries of the block index");
+            }
+        }
+
         // initialize sub services
         tracing::info!("Initializing sub services");
         let (services, shared) = sub_services::init_sub_services(&config, database)?;
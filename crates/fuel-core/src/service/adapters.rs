@@ -1,6 +1,9 @@
 use crate::{
     database::{
-        database_description::relayer::Relayer,
+        database_description::{
+            off_chain::OffChain,
+            relayer::Relayer,
+        },
         Database,
     },
     service::sub_services::BlockProducerService,
@@ -130,6 +133,10 @@ pub struct BlockProducerAdapter {
 pub struct BlockImporterAdapter {
     pub block_importer:
         Arc<fuel_core_importer::Importer<Database, ExecutorAdapter, VerifierAdapter>>,
+    /// The on-chain database updated by the importer.
+    pub database: Database,
+    /// Stores the statistic about the imported blocks.
+    pub off_chain_database: Database<OffChain>,
 }
 
 impl BlockImporterAdapter {
@@ -157,6 +164,7 @@ pub struct PeerReportConfig {
     pub bad_block_header: AppScore,
     pub missing_transactions: AppScore,
     pub invalid_transactions: AppScore,
+    pub incompatible_chain_config: AppScore,
 }
 
 #[cfg(not(feature = "p2p"))]
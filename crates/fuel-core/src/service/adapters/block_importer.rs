@@ -1,6 +1,10 @@
 use super::TransactionsSource;
 use crate::{
-    database::Database,
+    database::{
+        block::FuelBlockIds,
+        database_description::off_chain::OffChain,
+        Database,
+    },
     service::adapters::{
         BlockImporterAdapter,
         ExecutorAdapter,
@@ -27,11 +31,13 @@ use fuel_core_storage::{
     transactional::StorageTransaction,
     Result as StorageResult,
     StorageAsMut,
+    StorageAsRef,
 };
 use fuel_core_types::{
     blockchain::{
         block::Block,
         consensus::Consensus,
+        primitives::BlockId,
         SealedBlock,
     },
     fuel_tx::UniqueIdentifier,
@@ -51,13 +57,16 @@ impl BlockImporterAdapter {
     pub fn new(
         config: Config,
         database: Database,
+        off_chain_database: Database<OffChain>,
         executor: ExecutorAdapter,
         verifier: VerifierAdapter,
     ) -> Self {
-        let importer = Importer::new(config, database, executor, verifier);
+        let importer = Importer::new(config, database.clone(), executor, verifier);
         importer.init_metrics();
         Self {
             block_importer: Arc::new(importer),
+            database,
+            off_chain_database,
         }
     }
 
@@ -88,6 +97,17 @@ impl ImporterDatabase for Database {
             .transpose()?
             .map(|(height, _)| height))
     }
+
+    fn block_id(&self, height: &BlockHeight) -> StorageResult<Option<BlockId>> {
+        if let Some(id) = self.storage::<FuelBlockIds>().get(height)? {
+            return Ok(Some(id.into_owned()))
+        }
+        // The blocks committed before the index was introduced are not indexed.
+        Ok(self
+            .storage::<FuelBlocks>()
+            .get(height)?
+            .map(|block| block.id()))
+    }
 }
 
 impl ExecutorDatabase for Database {
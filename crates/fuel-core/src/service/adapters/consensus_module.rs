@@ -79,3 +79,21 @@ impl RelayerPort for MaybeRelayerAdapter {
         }
     }
 }
+
+impl MaybeRelayerAdapter {
+    /// Returns the finalized DA height synced by the relayer,
+    /// or `None` if there is no relayer.
+    pub fn da_tip(&self) -> anyhow::Result<Option<DaBlockHeight>> {
+        #[cfg(feature = "relayer")]
+        {
+            self.relayer_synced
+                .as_ref()
+                .map(|sync| sync.get_finalized_da_height())
+                .transpose()
+        }
+        #[cfg(not(feature = "relayer"))]
+        {
+            Ok(None)
+        }
+    }
+}
@@ -20,13 +20,18 @@ use fuel_core_storage::{
     Result as StorageResult,
     StorageAsRef,
 };
+use fuel_core_txpool::types::Page;
 use fuel_core_types::{
     blockchain::{
         block::CompressedBlock,
         primitives,
     },
     fuel_tx,
-    fuel_tx::Transaction,
+    fuel_tx::{
+        ContractId,
+        Transaction,
+        TxId,
+    },
     fuel_types::{
         BlockHeight,
         Bytes32,
@@ -39,6 +44,7 @@ use fuel_core_types::{
             TransactionExecutionStatus,
             UncommittedResult,
         },
+        txpool::ArcPoolTx,
     },
 };
 use std::{
@@ -63,6 +69,37 @@ impl TxPool for TxPoolAdapter {
     }
 }
 
+impl TxPoolAdapter {
+    /// Returns a page of the includable transactions after the `after` cursor,
+    /// ordered by the gas price and then by the configured tie-break.
+    /// The fee oracle, the priority lane, and the selection mode aren't applied,
+    /// so the order may differ from the selection for the next block.
+    pub fn get_includable_page(
+        &self,
+        after: Option<TxId>,
+        first: usize,
+    ) -> Page<ArcPoolTx> {
+        self.service.includable_page(after, first)
+    }
+
+    /// Returns the total fee that the next block with the `max_gas` would collect
+    /// from the current pool. The selection is the same as for the produced block.
+    pub fn estimate_block_fee(&self, max_gas: u64) -> u64 {
+        self.service.estimate_block_fee(max_gas)
+    }
+
+    /// Returns the transactions that the next block with the `max_gas` would include
+    /// if it was scoped to the transactions calling any of the `contracts`.
+    /// The selection is otherwise the same as for the produced block.
+    pub fn get_includable_txs_for_contracts(
+        &self,
+        contracts: &[ContractId],
+        max_gas: u64,
+    ) -> Vec<ArcPoolTx> {
+        self.service.includable_for_contracts(contracts, max_gas)
+    }
+}
+
 impl fuel_core_producer::ports::Executor<TransactionsSource> for ExecutorAdapter {
     type Database = Database;
 
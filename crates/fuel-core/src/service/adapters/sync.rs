@@ -3,11 +3,27 @@ use super::{
     ConsensusAdapter,
     P2PAdapter,
 };
+use crate::{
+    database::sealed_block::SealedBlockHeaders,
+    fuel_core_graphql_api::storage::{
+        failed_heights::FailedHeights,
+        import_timings::{
+            self,
+            BlockImportTimings,
+        },
+    },
+};
 use fuel_core_poa::ports::RelayerPort;
 use fuel_core_services::stream::BoxStream;
+use fuel_core_storage::{
+    StorageAsMut,
+    StorageAsRef,
+};
 use fuel_core_sync::ports::{
     BlockImporterPort,
     ConsensusPort,
+    FailedHeightsStore,
+    ImportTimings,
     PeerReportReason,
     PeerToPeerPort,
 };
@@ -17,7 +33,10 @@ use fuel_core_types::{
         SealedBlock,
         SealedBlockHeader,
     },
-    fuel_types::BlockHeight,
+    fuel_types::{
+        BlockHeight,
+        Bytes32,
+    },
     services::p2p::{
         peer_reputation::{
             AppScore,
@@ -28,7 +47,10 @@ use fuel_core_types::{
         Transactions,
     },
 };
-use std::ops::Range;
+use std::ops::{
+    Range,
+    RangeInclusive,
+};
 
 #[async_trait::async_trait]
 impl PeerToPeerPort for P2PAdapter {
@@ -65,6 +87,41 @@ impl PeerToPeerPort for P2PAdapter {
         }
     }
 
+    async fn get_sealed_block_headers_from_peer(
+        &self,
+        block_height_range: SourcePeer<Range<u32>>,
+    ) -> anyhow::Result<Option<Vec<SealedBlockHeader>>> {
+        let SourcePeer {
+            peer_id,
+            data: range,
+        } = block_height_range;
+        if let Some(service) = &self.service {
+            service
+                .get_sealed_block_headers_from_peer(peer_id.into(), range)
+                .await
+        } else {
+            Err(anyhow::anyhow!("No P2P service available"))
+        }
+    }
+
+    async fn peers_with_height(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Vec<PeerId>> {
+        if let Some(service) = &self.service {
+            let peers = service
+                .get_all_peers()
+                .await?
+                .into_iter()
+                .filter(|(_, info)| info.heartbeat_data.block_height >= Some(height))
+                .map(|(peer_id, _)| peer_id.to_bytes().into())
+                .collect();
+            Ok(peers)
+        } else {
+            Err(anyhow::anyhow!("No P2P service available"))
+        }
+    }
+
     async fn get_transactions(
         &self,
         range: SourcePeer<Range<u32>>,
@@ -82,6 +139,12 @@ impl PeerToPeerPort for P2PAdapter {
         }
     }
 
+    // The p2p handshake includes the checksum of the chain config, so the peers
+    // with a different chain config never connect.
+    async fn chain_config_hash(&self, _: PeerId) -> anyhow::Result<Option<Bytes32>> {
+        Ok(None)
+    }
+
     fn report_peer(&self, peer: PeerId, report: PeerReportReason) -> anyhow::Result<()> {
         if let Some(service) = &self.service {
             let service_name = "Sync";
@@ -110,6 +173,9 @@ impl P2PAdapter {
             PeerReportReason::InvalidTransactions => {
                 self.peer_report_config.invalid_transactions
             }
+            PeerReportReason::IncompatibleChainConfig => {
+                self.peer_report_config.incompatible_chain_config
+            }
         };
         P2PAdapterPeerReport { score }
     }
@@ -141,6 +207,78 @@ impl BlockImporterPort for BlockImporterAdapter {
     async fn execute_and_commit(&self, block: SealedBlock) -> anyhow::Result<()> {
         self.execute_and_commit(block).await
     }
+
+    fn can_accept(&self) -> bool {
+        self.block_importer.is_ready()
+    }
+
+    fn record_import_timings(
+        &self,
+        height: BlockHeight,
+        timings: ImportTimings,
+    ) -> anyhow::Result<()> {
+        let as_millis = |duration: std::time::Duration| {
+            u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+        };
+        let timings = import_timings::ImportTimings {
+            fetch_ms: as_millis(timings.fetch),
+            execute_ms: as_millis(timings.execute),
+        };
+        self.off_chain_database
+            .clone()
+            .storage_as_mut::<BlockImportTimings>()
+            .insert(&height, &timings)?;
+        Ok(())
+    }
+    fn local_sealed_header(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Option<SealedBlockHeader>> {
+        Ok(self.database.get_sealed_block_header(&height)?)
+    }
+
+    fn local_transactions(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Option<Transactions>> {
+        let block = self.database.get_sealed_block_by_height(&height)?;
+        Ok(block.map(|block| Transactions(block.entity.into_inner().1)))
+    }
+
+    async fn store_sealed_header(&self, header: SealedBlockHeader) -> anyhow::Result<()> {
+        let height = *header.entity.height();
+        self.database
+            .clone()
+            .storage_as_mut::<SealedBlockHeaders>()
+            .insert(&height, &header)?;
+        Ok(())
+    }
+}
+
+impl FailedHeightsStore for BlockImporterAdapter {
+    fn load_failed_heights(&self) -> anyhow::Result<Vec<RangeInclusive<u32>>> {
+        let failed = self
+            .off_chain_database
+            .storage::<FailedHeights>()
+            .get(&())?
+            .map(|failed| failed.into_owned())
+            .unwrap_or_default();
+        Ok(failed)
+    }
+
+    fn store_failed_heights(
+        &self,
+        failed: Vec<RangeInclusive<u32>>,
+    ) -> anyhow::Result<()> {
+        let mut database = self.off_chain_database.clone();
+        let mut storage = database.storage_as_mut::<FailedHeights>();
+        if failed.is_empty() {
+            storage.remove(&())?;
+        } else {
+            storage.insert(&(), &failed)?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -156,4 +294,8 @@ impl ConsensusPort for ConsensusAdapter {
         )
         .await?
     }
+
+    fn da_tip(&self) -> anyhow::Result<Option<DaBlockHeight>> {
+        self.maybe_relayer.da_tip()
+    }
 }
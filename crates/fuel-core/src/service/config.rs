@@ -69,6 +69,8 @@ pub struct Config {
     pub time_until_synced: Duration,
     /// Time to wait after submitting a query before debug info will be logged about query.
     pub query_log_threshold_time: Duration,
+    /// Verify and repair the block id indexes of the on-chain database on startup.
+    pub verify_block_index: bool,
 }
 
 impl Config {
@@ -114,6 +116,7 @@ impl Config {
             min_connected_reserved_peers: 0,
             time_until_synced: Duration::ZERO,
             query_log_threshold_time: Duration::from_secs(2),
+            verify_block_index: false,
         }
     }
 
@@ -76,6 +76,7 @@ pub fn init_sub_services(
     let importer_adapter = BlockImporterAdapter::new(
         config.block_importer.clone(),
         database.on_chain().clone(),
+        database.off_chain().clone(),
         executor.clone(),
         verifier.clone(),
     );
@@ -122,6 +123,7 @@ pub fn init_sub_services(
             bad_block_header: -100.,
             missing_transactions: -100.,
             invalid_transactions: -100.,
+            incompatible_chain_config: -100.,
         };
         P2PAdapter::new(
             network.as_ref().map(|network| network.shared.clone()),
@@ -171,9 +173,21 @@ pub fn init_sub_services(
     });
     let poa_adapter = PoAAdapter::new(poa.as_ref().map(|service| service.shared.clone()));
 
+    // The headers only import doesn't store the blocks, so it resumes from
+    // the highest stored header instead.
+    #[cfg(feature = "p2p")]
+    let sync_height = if config.sync.headers_only {
+        database
+            .on_chain()
+            .latest_sealed_header_height()?
+            .map_or(last_height, |height| height.max(last_height))
+    } else {
+        last_height
+    };
+
     #[cfg(feature = "p2p")]
     let sync = fuel_core_sync::service::new_service(
-        last_height,
+        sync_height,
         p2p_adapter.clone(),
         importer_adapter.clone(),
         super::adapters::ConsensusAdapter::new(
@@ -182,6 +196,8 @@ pub fn init_sub_services(
             relayer_adapter,
         ),
         config.sync,
+        Some(Arc::new(importer_adapter.clone())),
+        None,
     )?;
 
     // TODO: Figure out on how to move it into `fuel-core-graphql-api`.
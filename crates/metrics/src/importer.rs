@@ -1,6 +1,8 @@
 use crate::timing_buckets;
 use prometheus_client::{
+    encoding::EncodeLabelSet,
     metrics::{
+        family::Family,
         gauge::Gauge,
         histogram::Histogram,
     },
@@ -11,11 +13,18 @@ use std::sync::{
     OnceLock,
 };
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct StageLabel {
+    // the stage of the import of the block from the network
+    stage: String,
+}
+
 pub struct ImporterMetrics {
     pub registry: Registry,
     pub block_height: Gauge,
     pub latest_block_import_timestamp: Gauge<f64, AtomicU64>,
     pub execute_and_commit_duration: Histogram,
+    sync_stage_duration: Family<StageLabel, Histogram>,
 }
 
 impl Default for ImporterMetrics {
@@ -26,6 +35,10 @@ impl Default for ImporterMetrics {
         let latest_block_import_ms = Gauge::default();
         let execute_and_commit_duration =
             Histogram::new(timing_buckets().iter().cloned());
+        let sync_stage_duration =
+            Family::<StageLabel, Histogram>::new_with_constructor(|| {
+                Histogram::new(timing_buckets().iter().cloned())
+            });
 
         registry.register(
             "importer_block_height",
@@ -45,15 +58,31 @@ impl Default for ImporterMetrics {
             execute_and_commit_duration.clone(),
         );
 
+        registry.register(
+            "importer_sync_stage_duration_s",
+            "Records the duration of each stage of the import of a block from the network",
+            sync_stage_duration.clone(),
+        );
+
         Self {
             registry,
             block_height: block_height_gauge,
             latest_block_import_timestamp: latest_block_import_ms,
             execute_and_commit_duration,
+            sync_stage_duration,
         }
     }
 }
 
+impl ImporterMetrics {
+    pub fn sync_stage_observe(&self, stage: &str, time: f64) {
+        let histogram = self.sync_stage_duration.get_or_create(&StageLabel {
+            stage: stage.to_string(),
+        });
+        histogram.observe(time);
+    }
+}
+
 // Setup a global static for accessing importer metrics
 static IMPORTER_METRICS: OnceLock<ImporterMetrics> = OnceLock::new();
 
@@ -27,6 +27,7 @@ use fuel_core_storage::{
     vm_storage::VmStorage,
     StorageAsMut,
     StorageAsRef,
+    StorageReplace,
 };
 use fuel_core_types::{
     blockchain::{
@@ -1548,13 +1549,19 @@ where
                     if let Some(Input::Contract(Contract { contract_id, .. })) =
                         inputs.get(contract.input_index as usize)
                     {
-                        db.storage::<ContractsLatestUtxo>().insert(
+                        let previous = StorageReplace::<ContractsLatestUtxo>::replace(
+                            db,
                             contract_id,
                             &ContractUtxoInfo {
                                 utxo_id,
                                 tx_pointer: TxPointer::new(block_height, tx_idx),
                             },
                         )?;
+                        // The contract input has spent the latest UTXO of the contract,
+                        // so with the UTXO validation there must be one to replace.
+                        if self.options.utxo_validation && previous.is_none() {
+                            return Err(ExecutorError::ContractUtxoMissing(*contract_id))
+                        }
                     } else {
                         return Err(ExecutorError::TransactionValidity(
                             TransactionValidityError::InvalidContractInputIndex(utxo_id),
@@ -17,6 +17,7 @@ use fuel_core_storage::{
     StorageBatchMutate,
     StorageMutate,
     StorageRead,
+    StorageReplace,
 };
 use fuel_core_types::{
     blockchain::primitives::DaBlockHeight,
@@ -77,6 +78,7 @@ pub trait ExecutorDatabaseTrait<D>:
     + StorageMutate<Coins, Error = StorageError>
     + StorageMutate<SpentMessages, Error = StorageError>
     + StorageMutate<ContractsLatestUtxo, Error = StorageError>
+    + StorageReplace<ContractsLatestUtxo>
     + StorageMutate<ContractsRawCode, Error = StorageError>
     + StorageRead<ContractsRawCode>
     + StorageMutate<ContractsInfo, Error = StorageError>
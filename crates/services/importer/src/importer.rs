@@ -54,6 +54,14 @@ use tokio::sync::{
     oneshot,
     TryAcquireError,
 };
+use tokio_stream::{
+    wrappers::{
+        errors::BroadcastStreamRecvError,
+        BroadcastStream,
+    },
+    Stream,
+    StreamExt,
+};
 
 #[cfg(test)]
 pub mod test;
@@ -91,6 +99,8 @@ pub enum Error {
     ExecuteGenesis,
     #[display(fmt = "The database already contains the data at the height {_0}.")]
     NotUnique(BlockHeight),
+    #[display(fmt = "The subscriber lagged behind and missed {_0} blocks.")]
+    Lagged(u64),
     #[from]
     StorageError(StorageError),
     UnsupportedConsensusVariant(String),
@@ -141,6 +151,21 @@ impl<D, E, V> Importer<D, E, V> {
         self.broadcast.subscribe()
     }
 
+    /// Returns `true` if all listeners processed the previously imported block,
+    /// so the next block can be committed without waiting for them.
+    /// The channel of the previous block is dropped only once it is complete,
+    /// because the completed channel can't be awaited by the next commit.
+    pub fn is_ready(&self) -> bool {
+        let mut previous = self.prev_block_process_result.lock().expect("poisoned");
+        let ready = previous.as_mut().map_or(true, |channel| {
+            !matches!(channel.try_recv(), Err(oneshot::error::TryRecvError::Empty))
+        });
+        if ready {
+            *previous = None;
+        }
+        ready
+    }
+
     pub(crate) fn lock(&self) -> Result<tokio::sync::SemaphorePermit, Error> {
         let guard = self.guard.try_acquire();
         match guard {
@@ -328,6 +353,63 @@ where
             .latest_block_import_timestamp
             .set(current_time);
     }
+
+    /// Returns the stream of the ids of the committed blocks starting from the `from` height.
+    ///
+    /// The ids of the blocks committed before the call are replayed from the database
+    /// lazily, one height at a time. After that the stream follows the newly committed
+    /// blocks without a gap at the handoff. It allows building an external index of
+    /// the blocks. If the subscriber lags behind the notifications about new blocks,
+    /// the stream yields [`Error::Lagged`] and ends, so the subscriber can resume
+    /// from the height after the last received id.
+    pub fn committed_ids(
+        &self,
+        from: BlockHeight,
+    ) -> Result<impl Stream<Item = Result<(BlockHeight, BlockId), Error>> + '_, Error>
+    {
+        // Subscribe before reading the database to not miss blocks committed in between.
+        let live = self.broadcast.subscribe();
+        let latest = self.database.latest_block_height()?;
+
+        let replayed = latest
+            .into_iter()
+            .flat_map(move |latest| u32::from(from)..=u32::from(latest))
+            .map(move |height| {
+                let height = BlockHeight::from(height);
+                let id = self
+                    .database
+                    .block_id(&height)?
+                    .ok_or(not_found!("BlockId"))?;
+                Ok((height, id))
+            });
+
+        let mut lagged = false;
+        let live = BroadcastStream::new(live)
+            .map(|result| match result {
+                Ok(result) => {
+                    let block = &result.sealed_block.entity;
+                    Ok((*block.header().height(), block.id()))
+                }
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    Err(Error::Lagged(skipped))
+                }
+            })
+            .filter(move |item| {
+                item.as_ref().map_or(true, |(height, _)| {
+                    *height >= from && latest.map_or(true, |latest| *height > latest)
+                })
+            })
+            // The ids after the lag have a gap, so the stream ends with the error.
+            .map_while(move |item| {
+                if lagged {
+                    return None
+                }
+                lagged = item.is_err();
+                Some(item)
+            });
+
+        Ok(tokio_stream::iter(replayed).chain(live))
+    }
 }
 
 impl<IDatabase, E, V> Importer<IDatabase, E, V>
@@ -21,6 +21,7 @@ use fuel_core_types::{
     blockchain::{
         block::Block,
         consensus::Consensus,
+        primitives::BlockId,
         SealedBlock,
     },
     fuel_tx::TxId,
@@ -41,17 +42,27 @@ use fuel_core_types::{
         Uncommitted,
     },
 };
+use std::sync::{
+    atomic::{
+        AtomicU32,
+        Ordering,
+    },
+    Arc,
+};
 use test_case::test_case;
 use tokio::sync::{
     broadcast::error::TryRecvError,
     TryAcquireError,
 };
+use tokio_stream::StreamExt;
 
 mockall::mock! {
     pub Database {}
 
     impl ImporterDatabase for Database {
         fn latest_block_height(&self) -> StorageResult<Option<BlockHeight>>;
+
+        fn block_id(&self, height: &BlockHeight) -> StorageResult<Option<BlockId>>;
     }
 
     impl ExecutorDatabase for Database {
@@ -267,6 +278,101 @@ async fn commit_result_genesis(
     commit_result_assert(sealed_block, underlying_db(), executor_db()).await
 }
 
+#[tokio::test]
+async fn is_ready_only_after_listeners_process_the_imported_block() {
+    // given
+    let importer = Importer::new(Default::default(), underlying_db(ok(None))(), (), ());
+    let uncommitted_result = UncommittedResult::new(
+        ImportResult::new_from_local(genesis(0), vec![]),
+        StorageTransaction::new(executor_db(ok(None), ok(true), 1)()),
+    );
+    let mut imported_blocks = importer.subscribe();
+    assert!(importer.is_ready());
+
+    // when
+    importer.commit_result(uncommitted_result).await.unwrap();
+
+    // then
+    let imported_block = imported_blocks.try_recv().unwrap();
+    assert!(!importer.is_ready());
+    drop(imported_block);
+    assert!(importer.is_ready());
+    // The check doesn't change the readiness.
+    assert!(importer.is_ready());
+}
+
+#[tokio::test]
+async fn commit_after_is_ready_succeeds() {
+    // given
+    let latest_height = Arc::new(AtomicU32::new(0));
+    let mut db = MockDatabase::default();
+    db.expect_latest_block_height().returning({
+        let latest_height = latest_height.clone();
+        move || Ok(Some(latest_height.load(Ordering::SeqCst).into()))
+    });
+    let importer = Importer::new(Default::default(), db, (), ());
+    let mut imported_blocks = importer.subscribe();
+    let uncommitted_result = UncommittedResult::new(
+        ImportResult::new_from_local(poa_block(1), vec![]),
+        StorageTransaction::new(executor_db(ok(Some(0)), ok(true), 1)()),
+    );
+    importer.commit_result(uncommitted_result).await.unwrap();
+    latest_height.store(1, Ordering::SeqCst);
+    drop(imported_blocks.try_recv().unwrap());
+    while !importer.is_ready() {
+        tokio::task::yield_now().await;
+    }
+
+    // when
+    let uncommitted_result = UncommittedResult::new(
+        ImportResult::new_from_local(poa_block(2), vec![]),
+        StorageTransaction::new(executor_db(ok(Some(1)), ok(true), 1)()),
+    );
+    let result = importer.commit_result(uncommitted_result).await;
+
+    // then
+    assert!(result.is_ok());
+    assert_eq!(
+        *imported_blocks.try_recv().unwrap().sealed_block.entity.header().height(),
+        2u32.into()
+    );
+}
+
+#[tokio::test]
+async fn committed_ids_replays_the_past_and_follows_new_blocks_without_gaps() {
+    // given
+    let latest_height = Arc::new(AtomicU32::new(2));
+    let mut db = MockDatabase::default();
+    db.expect_latest_block_height().returning({
+        let latest_height = latest_height.clone();
+        move || Ok(Some(latest_height.load(Ordering::SeqCst).into()))
+    });
+    db.expect_block_id()
+        .returning(|height| Ok(Some(poa_block(**height).entity.id())));
+    let importer = Importer::new(Default::default(), db, (), ());
+    let mut ids = Box::pin(importer.committed_ids(1u32.into()).unwrap());
+
+    // when
+    let mut actual = vec![];
+    actual.push(ids.next().await.unwrap().unwrap());
+    actual.push(ids.next().await.unwrap().unwrap());
+    for height in 3..=4u32 {
+        let uncommitted_result = UncommittedResult::new(
+            ImportResult::new_from_local(poa_block(height), vec![]),
+            StorageTransaction::new(executor_db(ok(Some(height - 1)), ok(true), 1)()),
+        );
+        importer.commit_result(uncommitted_result).await.unwrap();
+        latest_height.store(height, Ordering::SeqCst);
+        actual.push(ids.next().await.unwrap().unwrap());
+    }
+
+    // then
+    let expected = (1..=4u32)
+        .map(|height| (height.into(), poa_block(height).entity.id()))
+        .collect::<Vec<_>>();
+    assert_eq!(actual, expected);
+}
+
 //////////////////////////// PoA Block ////////////////////////////
 #[test_case(
     poa_block(1),
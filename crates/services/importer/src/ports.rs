@@ -6,6 +6,7 @@ use fuel_core_types::{
     blockchain::{
         block::Block,
         consensus::Consensus,
+        primitives::BlockId,
         SealedBlock,
     },
     fuel_types::{
@@ -36,6 +37,9 @@ pub trait Executor: Send + Sync {
 pub trait ImporterDatabase: Send + Sync {
     /// Returns the latest block height.
     fn latest_block_height(&self) -> StorageResult<Option<BlockHeight>>;
+
+    /// Returns the id of the committed block at the `height`.
+    fn block_id(&self, height: &BlockHeight) -> StorageResult<Option<BlockId>>;
 }
 
 /// The port for returned database from the executor.
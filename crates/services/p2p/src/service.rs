@@ -97,6 +97,7 @@ enum TaskRequest {
     },
     GetSealedHeaders {
         block_height_range: Range<u32>,
+        from_peer: Option<PeerId>,
         channel: OnResponse<Option<Vec<SealedBlockHeader>>>,
     },
     GetTransactions {
@@ -531,14 +532,14 @@ where
                         let peer_ids = self.p2p_service.get_peer_ids();
                         let _ = channel.send(peer_ids);
                     }
-                    Some(TaskRequest::GetSealedHeaders { block_height_range, channel}) => {
+                    Some(TaskRequest::GetSealedHeaders { block_height_range, from_peer, channel}) => {
                         let channel = ResponseSender::SealedHeaders(channel);
                         let request_msg = RequestMessage::SealedHeaders(block_height_range.clone());
 
                         // Note: this range has already been checked for
                         // validity in `SharedState::get_sealed_block_headers`.
                         let height = BlockHeight::from(block_height_range.end.saturating_sub(1));
-                        let peer = self.p2p_service.get_peer_id_with_height(&height);
+                        let peer = from_peer.or_else(|| self.p2p_service.get_peer_id_with_height(&height));
                         if self.p2p_service.send_request_msg(peer, request_msg, channel).is_err() {
                             tracing::warn!("No peers found for block at height {:?}", height);
                         }
@@ -699,6 +700,32 @@ impl SharedState {
     pub async fn get_sealed_block_headers(
         &self,
         block_height_range: Range<u32>,
+    ) -> anyhow::Result<(Vec<u8>, Option<Vec<SealedBlockHeader>>)> {
+        self.request_sealed_block_headers(block_height_range, None)
+            .await
+    }
+
+    /// Requests the headers from the `peer_id` instead of the peer
+    /// chosen by the service.
+    pub async fn get_sealed_block_headers_from_peer(
+        &self,
+        peer_id: Vec<u8>,
+        block_height_range: Range<u32>,
+    ) -> anyhow::Result<Option<Vec<SealedBlockHeader>>> {
+        let from_peer = PeerId::from_bytes(&peer_id)?;
+        let (response_from_peer, headers) = self
+            .request_sealed_block_headers(block_height_range, Some(from_peer))
+            .await?;
+        if response_from_peer != peer_id {
+            return Err(anyhow!("Bug: response from non-requested peer"))
+        }
+        Ok(headers)
+    }
+
+    async fn request_sealed_block_headers(
+        &self,
+        block_height_range: Range<u32>,
+        from_peer: Option<PeerId>,
     ) -> anyhow::Result<(Vec<u8>, Option<Vec<SealedBlockHeader>>)> {
         let (sender, receiver) = oneshot::channel();
 
@@ -711,6 +738,7 @@ impl SharedState {
         self.request_sender
             .send(TaskRequest::GetSealedHeaders {
                 block_height_range,
+                from_peer,
                 channel: sender,
             })
             .await?;
@@ -0,0 +1,117 @@
+//! # Clock
+//! Source of time for the sync service. All time reads of the import go
+//! through the [`Clock`], so timeouts can be tested deterministically.
+
+use std::{
+    future::Future,
+    time::Duration,
+};
+use tokio::time::Instant;
+
+#[cfg(test)]
+mod tests;
+
+#[async_trait::async_trait]
+/// The source of time.
+pub trait Clock: Send + Sync {
+    /// Returns the current instant.
+    fn now(&self) -> Instant;
+
+    /// Waits until the `deadline` is reached.
+    async fn sleep_until(&self, deadline: Instant);
+
+    /// Waits for the `duration` to elapse.
+    async fn sleep(&self, duration: Duration) {
+        let deadline = self.now().checked_add(duration);
+        match deadline {
+            Some(deadline) => self.sleep_until(deadline).await,
+            None => futures::future::pending().await,
+        }
+    }
+}
+
+/// Runs the `future` until it completes or the `duration` elapses on the `clock`.
+/// Returns `None` if the `duration` elapsed first.
+pub async fn timeout<F>(
+    clock: &dyn Clock,
+    duration: Duration,
+    future: F,
+) -> Option<F::Output>
+where
+    F: Future,
+{
+    let sleep = clock.sleep(duration);
+    futures::pin_mut!(future);
+    futures::pin_mut!(sleep);
+    match futures::future::select(future, sleep).await {
+        futures::future::Either::Left((output, _)) => Some(output),
+        futures::future::Either::Right(_) => None,
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+/// The clock based on the `tokio` timer.
+pub struct TokioClock;
+
+#[async_trait::async_trait]
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep_until(&self, deadline: Instant) {
+        tokio::time::sleep_until(deadline).await
+    }
+}
+
+#[cfg(any(test, feature = "benchmarking"))]
+#[derive(Debug, Clone)]
+/// The clock that only moves when it is advanced manually.
+pub struct MockClock {
+    now: std::sync::Arc<tokio::sync::watch::Sender<Instant>>,
+}
+
+#[cfg(any(test, feature = "benchmarking"))]
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(any(test, feature = "benchmarking"))]
+impl MockClock {
+    /// Creates a new mock clock starting at the current instant.
+    pub fn new() -> Self {
+        let (now, _) = tokio::sync::watch::channel(Instant::now());
+        Self {
+            now: std::sync::Arc::new(now),
+        }
+    }
+
+    /// Moves the clock forward by the `duration` and wakes up all sleepers
+    /// whose deadline is reached.
+    pub fn advance(&self, duration: Duration) {
+        self.now.send_modify(|now| {
+            *now = now
+                .checked_add(duration)
+                .expect("The mock clock can't overflow `Instant`");
+        });
+    }
+}
+
+#[cfg(any(test, feature = "benchmarking"))]
+#[async_trait::async_trait]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.borrow()
+    }
+
+    async fn sleep_until(&self, deadline: Instant) {
+        let mut now = self.now.subscribe();
+        while *now.borrow_and_update() < deadline {
+            if now.changed().await.is_err() {
+                return
+            }
+        }
+    }
+}
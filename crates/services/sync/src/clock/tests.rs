@@ -0,0 +1,50 @@
+#![allow(non_snake_case)]
+
+use super::*;
+use futures::FutureExt;
+
+#[tokio::test]
+async fn mock_clock__timeout_fires_after_advance() {
+    // given
+    let clock = MockClock::new();
+    let duration = Duration::from_secs(60 * 60);
+    let fut = timeout(&clock, duration, futures::future::pending::<()>());
+    futures::pin_mut!(fut);
+    assert_eq!((&mut fut).now_or_never(), None);
+
+    // when
+    clock.advance(duration);
+
+    // then
+    let result = tokio::time::timeout(Duration::from_secs(1), fut)
+        .await
+        .expect("The timeout should fire without real sleeping");
+    assert_eq!(result, None);
+}
+
+#[tokio::test]
+async fn mock_clock__timeout_does_not_fire_before_deadline() {
+    // given
+    let clock = MockClock::new();
+    let fut = timeout(
+        &clock,
+        Duration::from_secs(10),
+        futures::future::pending::<()>(),
+    );
+    futures::pin_mut!(fut);
+
+    // when
+    clock.advance(Duration::from_secs(9));
+
+    // then
+    assert_eq!((&mut fut).now_or_never(), None);
+}
+
+#[tokio::test]
+async fn mock_clock__timeout_returns_output_of_ready_future() {
+    let clock = MockClock::new();
+
+    let result = timeout(&clock, Duration::from_secs(10), async { 42 }).await;
+
+    assert_eq!(result, Some(42));
+}
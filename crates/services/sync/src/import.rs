@@ -2,6 +2,7 @@
 //! This module contains the import task which is responsible for
 //! importing blocks from the network into the local blockchain.
 
+use fuel_core_metrics::importer::importer_metrics;
 use fuel_core_services::{
     SharedMutex,
     StateWatcher,
@@ -10,10 +11,16 @@ use fuel_core_types::{
     self,
     blockchain::{
         block::Block,
+        primitives::BlockId,
         SealedBlock,
         SealedBlockHeader,
     },
-    fuel_types::BlockHeight,
+    fuel_tx::Transaction,
+    fuel_types::{
+        canonical::Serialize,
+        BlockHeight,
+        Bytes32,
+    },
     services::p2p::{
         PeerId,
         SourcePeer,
@@ -25,25 +32,65 @@ use futures::{
     FutureExt,
     Stream,
 };
+use rand::Rng;
 use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+        HashMap,
+        HashSet,
+    },
     future::Future,
     ops::{
         Range,
         RangeInclusive,
     },
-    sync::Arc,
+    sync::{
+        atomic::{
+            AtomicBool,
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{
+    sync::{
+        broadcast,
+        watch,
+        Notify,
+        Semaphore,
+    },
+    time::Instant,
 };
-use tokio::sync::Notify;
 use tracing::Instrument;
 
 use crate::{
+    clock::{
+        self,
+        Clock,
+        TokioClock,
+    },
+    peer_selector::PeerSelector,
     ports::{
         BlockImporterPort,
         ConsensusPort,
+        DiskMonitor,
+        FailedHeightsStore,
+        FinalityVerifier,
+        ImportTimings,
         PeerReportReason,
         PeerToPeerPort,
+        ReplayExecutor,
+        StateRoot,
+        TransactionChecker,
+    },
+    state::{
+        RangeSet,
+        State,
+        SyncStateReport,
     },
-    state::State,
     tracing_helpers::TraceErr,
 };
 
@@ -65,6 +112,118 @@ pub struct Config {
     pub block_stream_buffer_size: usize,
     /// The maximum number of headers to request in a single batch.
     pub header_batch_size: usize,
+    /// Wait for the DA layer to reach the `da_height` of the headers before
+    /// requesting transactions. Chains without a DA layer can disable it.
+    pub require_da: bool,
+    /// The maximum number of blocks that can be downloaded but not yet committed.
+    /// New headers are not requested until commits drain below this limit.
+    /// `None` means no limit.
+    pub max_uncommitted_blocks: Option<usize>,
+    /// The maximum total size in bytes of the transactions that are downloaded
+    /// but not yet committed. The transactions of the next batch are not fetched
+    /// while the buffered size is at or above this limit. The size of a batch is
+    /// unknown before it is fetched, so the batches are fetched one at a time and
+    /// the limit can be exceeded by at most one batch. `None` means no limit.
+    pub max_buffered_tx_bytes: Option<usize>,
+    /// The maximum time the execution and commit of one block can take.
+    /// The height is marked as failed if it is exceeded. The timeout is local,
+    /// so the peer of the block is not reported. `None` means no limit.
+    pub execution_timeout: Option<Duration>,
+    /// The policy of the retries of the failed fetches of headers and transactions.
+    /// The peers that failed to provide the headers are excluded from the remaining
+    /// attempts. The transactions are always requested from the peer of the headers.
+    pub retry_policy: RetryPolicy,
+    /// The number of the latest observed heights that are not imported yet.
+    /// The import lags behind the network tip by this number of blocks to
+    /// avoid importing blocks that may be reorganized soon.
+    pub confirmation_lag: u32,
+    /// The maximum time the stream can go without committing a block.
+    /// If it is exceeded, the stream is cancelled and restarted from the
+    /// last committed height. `None` disables the watchdog.
+    pub stream_stall_timeout: Option<Duration>,
+    /// Record the time spent to fetch and execute each committed block
+    /// via [`BlockImporterPort::record_import_timings`].
+    pub record_import_timings: bool,
+    /// Track the throughput of the download and of the execution of the blocks
+    /// separately, see [`Import::throughput`].
+    pub record_throughput: bool,
+    /// Persist the failed heights on each update and reload them on the startup
+    /// via [`FailedHeightsStore`], if the import has the store.
+    pub persist_failed_heights: bool,
+    /// How often to ask the executor if it can accept more blocks while it
+    /// reports the saturation. No new headers are requested until it can.
+    pub executor_poll_interval: Duration,
+    /// Look up the headers of each batch in the local storage before requesting
+    /// them from the network. The batch is served locally only if all of its headers
+    /// are found. Its transactions are requested from any peer.
+    pub local_headers_first: bool,
+    /// Reconstruct the blocks of each batch from the transactions in the local storage
+    /// before requesting them from the network. The batch is served locally only if
+    /// the transactions of all of its blocks are found and match their headers.
+    pub local_transactions_first: bool,
+    /// The maximum number of heights imported by one iteration of the import.
+    /// The rest of the range is imported by the next iterations, so the progress
+    /// is reported and the failures are localized per iteration. `None` imports
+    /// the whole range at once.
+    pub max_range_per_iteration: Option<usize>,
+    /// The hash of the local chain config. If it is set, the headers are not
+    /// accepted from the peers advertising a different hash, and such peers are
+    /// reported. The peers that don't advertise the hash are accepted.
+    /// `None` disables the check.
+    pub chain_config_hash: Option<Bytes32>,
+    /// Import only the headers. They are checked by the consensus and stored
+    /// via [`BlockImporterPort::store_sealed_header`] once the DA layer reaches
+    /// their `da_height`. The transactions are neither fetched nor executed.
+    /// The store must keep the headers apart from the blocks, so they are not served
+    /// to the peers. The import starts from the height it is given, so the node
+    /// resumes it from the highest stored header.
+    pub headers_only: bool,
+    /// The maximum number of bytes of the headers and transactions fetched from
+    /// one peer within the `bandwidth_window`. The headers from the peers over
+    /// the budget are skipped until their window ends. `None` means no limit.
+    pub max_bytes_per_peer_per_window: Option<usize>,
+    /// The duration of the window of the `max_bytes_per_peer_per_window`.
+    pub bandwidth_window: Duration,
+    /// The number of headers fetched at the start of each stream before its first
+    /// block is executed, so the execution starts with a buffer of headers.
+    /// The headers are fetched in whole batches of the `header_batch_size`.
+    /// Zero disables the warmup.
+    pub warmup_headers: usize,
+    /// The order in which the header batches of each stream are requested.
+    pub fetch_order: FetchOrder,
+    /// Pauses the import after consecutive failures. `None` disables the breaker.
+    pub circuit_breaker: Option<CircuitBreaker>,
+    /// The maximum distance of the `da_height` of a header ahead of the DA tip of
+    /// the node. The headers further ahead are rejected and their peer is reported
+    /// without waiting for the DA layer. `None` disables the check.
+    pub max_da_lookahead: Option<u64>,
+    /// Reports a peer only once it accumulates enough incidents within a window.
+    /// `None` reports the peer on each incident.
+    pub peer_strikes: Option<PeerStrikes>,
+    /// The handling of the range to process overlapping with the range in flight.
+    pub overlap_policy: OverlapPolicy,
+    /// The maximum duration of one iteration of the import. Once it passes, no new
+    /// batches are requested, the batches in flight are still committed, and the rest
+    /// of the range is left for the next iteration. `None` means no limit.
+    pub max_import_duration: Option<Duration>,
+    /// The ranges of at most this many heights to the tip are imported sequentially,
+    /// one batch at a time, for the lower latency. The longer ranges are pipelined
+    /// with the `block_stream_buffer_size`. `None` pipelines all ranges.
+    pub pipeline_threshold: Option<usize>,
+    /// The number of the consecutive batches a peer serves the headers of, but not
+    /// the transactions, after which the transactions of its batches are requested
+    /// from another peer with the heights, if any. `None` always requests
+    /// the transactions from the peer of the headers.
+    pub missing_transactions_threshold: Option<usize>,
+    /// The maximum number of the heights below the committed tip that the chain
+    /// of the peer serving the first headers of the range may fork at. The import from
+    /// the chain forking deeper is refused with [`ReorgTooDeep`], so the finalized
+    /// history is never overwritten. `None` means no limit.
+    pub max_reorg_depth: Option<u32>,
+    /// The minimum free disk space in bytes. If the import has the [`DiskMonitor`],
+    /// it pauses before committing the next blocks while the free space is below it,
+    /// checking it every `executor_poll_interval`. `None` never pauses.
+    pub min_free_disk_space: Option<u64>,
 }
 
 impl Default for Config {
@@ -72,7 +231,612 @@ impl Default for Config {
         Self {
             block_stream_buffer_size: 10,
             header_batch_size: 100,
+            require_da: true,
+            max_uncommitted_blocks: None,
+            max_buffered_tx_bytes: None,
+            execution_timeout: None,
+            retry_policy: RetryPolicy::default(),
+            confirmation_lag: 0,
+            stream_stall_timeout: None,
+            record_import_timings: false,
+            record_throughput: false,
+            persist_failed_heights: false,
+            executor_poll_interval: Duration::from_millis(100),
+            local_headers_first: false,
+            local_transactions_first: false,
+            max_range_per_iteration: None,
+            chain_config_hash: None,
+            headers_only: false,
+            max_bytes_per_peer_per_window: None,
+            bandwidth_window: Duration::from_secs(60),
+            warmup_headers: 0,
+            fetch_order: FetchOrder::default(),
+            circuit_breaker: None,
+            max_da_lookahead: None,
+            peer_strikes: None,
+            overlap_policy: OverlapPolicy::default(),
+            max_import_duration: None,
+            pipeline_threshold: None,
+            missing_transactions_threshold: None,
+            max_reorg_depth: None,
+            min_free_disk_space: None,
+        }
+    }
+}
+
+/// The error of the import from the chain forking deeper than the `max_reorg_depth`
+/// below the committed tip.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReorgTooDeep {
+    /// The height where the chain of the network differs from the local one.
+    /// The fork point is below it.
+    pub diverged_at: u32,
+    /// The committed tip.
+    pub tip: u32,
+    /// The `max_reorg_depth` of the [`Config`].
+    pub max_reorg_depth: u32,
+}
+
+impl std::fmt::Display for ReorgTooDeep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The chain diverges at {}, forking more than {} heights below the committed tip {}",
+            self.diverged_at, self.max_reorg_depth, self.tip
+        )
+    }
+}
+
+impl std::error::Error for ReorgTooDeep {}
+
+/// Marks the range in flight until it is dropped, so the range and the heights
+/// cancelled during its stream are released even if the import is cancelled.
+struct InFlight<'a> {
+    in_flight: &'a SharedMutex<Option<RangeInclusive<u32>>>,
+    cancelled_heights: &'a CancelledHeights,
+}
+
+impl<'a> InFlight<'a> {
+    fn start(
+        in_flight: &'a SharedMutex<Option<RangeInclusive<u32>>>,
+        cancelled_heights: &'a CancelledHeights,
+        range: RangeInclusive<u32>,
+    ) -> Self {
+        in_flight.apply(|in_flight| *in_flight = Some(range));
+        Self {
+            in_flight,
+            cancelled_heights,
+        }
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        // The cancellations are cleared under the same lock as the range, so
+        // a cancellation can't outlive the stream it was issued for.
+        self.in_flight.apply(|in_flight| {
+            *in_flight = None;
+            self.cancelled_heights.send_replace(BTreeSet::new());
+        });
+    }
+}
+
+/// The handling of the range to process that overlaps with the range
+/// imported by another stream in flight.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// The import of the range fails without fetching anything.
+    #[default]
+    Reject,
+    /// Only the part of the range that doesn't overlap is imported.
+    Clamp,
+}
+
+/// The stream that imports each range, derived from the [`Config`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportStrategy {
+    /// The blocks are fetched with their transactions and executed.
+    Blocks,
+    /// Like [`ImportStrategy::Blocks`], restarted each time it stalls
+    /// for the `stream_stall_timeout`.
+    WatchedBlocks,
+    /// Only the headers are fetched and stored, see `headers_only`.
+    HeadersOnly,
+    /// Like [`ImportStrategy::HeadersOnly`], restarted each time it stalls
+    /// for the `stream_stall_timeout`.
+    WatchedHeadersOnly,
+}
+
+impl From<&Config> for ImportStrategy {
+    fn from(config: &Config) -> Self {
+        match (config.headers_only, config.stream_stall_timeout.is_some()) {
+            (false, false) => Self::Blocks,
+            (false, true) => Self::WatchedBlocks,
+            (true, false) => Self::HeadersOnly,
+            (true, true) => Self::WatchedHeadersOnly,
+        }
+    }
+}
+
+/// How the batches of a range are fetched relative to the execution of their blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pipelining {
+    /// The next batch is fetched only after the previous one is committed.
+    Sequential,
+    /// Up to `block_stream_buffer_size` batches are fetched ahead of the execution.
+    Pipelined,
+}
+
+impl Pipelining {
+    /// Chooses the pipelining of the range with the `gap` heights to the tip
+    /// according to the `pipeline_threshold`.
+    pub fn for_gap(config: &Config, gap: usize) -> Self {
+        match config.pipeline_threshold {
+            Some(threshold) if gap <= threshold => Self::Sequential,
+            _ => Self::Pipelined,
+        }
+    }
+}
+
+/// The order in which the header batches of a range are requested.
+/// The blocks are executed in the order of heights regardless of it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FetchOrder {
+    /// The batches are requested in the order of heights.
+    #[default]
+    Sequential,
+    /// Every `stride`-th batch is requested first, then the batches following
+    /// each of them, and so on, so a sparse sample across the whole range is
+    /// fetched early and the gaps are detected before the import reaches them.
+    /// With the `max_uncommitted_blocks` limit, the range is split into the windows
+    /// of at most that many heights, and the batches of the next window are not
+    /// requested until the whole current window is reserved for the execution.
+    Strided {
+        /// The distance between the batches requested one after another.
+        stride: usize,
+    },
+}
+
+/// The circuit breaker of the import.
+///
+/// It trips after `max_consecutive_failures` consecutive imports fail without
+/// committing a block, and the import is paused for the `cooldown`. Then the
+/// breaker half-opens: it closes if the next import commits a block and trips
+/// again otherwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CircuitBreaker {
+    /// The number of the consecutive failed imports that trips the breaker.
+    pub max_consecutive_failures: usize,
+    /// The duration of the pause after the breaker trips.
+    pub cooldown: Duration,
+}
+
+/// The policy of the reporting of the peers after repeated incidents.
+///
+/// The incidents of each peer are counted within its window, and the peer is
+/// reported with the reason of the incident reaching the `strike_threshold`.
+/// Then the counting starts over. The transient issues that don't repeat
+/// within the `window` are never reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PeerStrikes {
+    /// The number of the incidents within the `window` that triggers the report.
+    pub strike_threshold: usize,
+    /// The duration of the window the incidents are counted within.
+    pub window: Duration,
+}
+
+/// The state of the [`CircuitBreaker`].
+#[derive(Clone, Copy, Debug)]
+enum BreakerState {
+    /// The import runs, counting the consecutive failures.
+    Closed { failures: usize },
+    /// The import is paused since the breaker tripped.
+    Open { tripped_at: Instant },
+    /// The next import tests the recovery.
+    HalfOpen,
+}
+
+/// The event emitted on each transition of the [`CircuitBreaker`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitBreakerEvent {
+    /// The breaker tripped after the `failures` consecutive failed imports.
+    Tripped {
+        /// The number of the consecutive failed imports.
+        failures: usize,
+    },
+    /// The cooldown ended, the next import tests the recovery.
+    HalfOpened,
+    /// The import recovered.
+    Closed,
+}
+
+/// The event emitted when the import pauses or resumes because of the free disk space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiskSpaceEvent {
+    /// The free disk space dropped below the `min_free_disk_space`,
+    /// no blocks are committed until it recovers.
+    Paused {
+        /// The free disk space in bytes.
+        free_space: u64,
+        /// The `min_free_disk_space` of the [`Config`].
+        min_free_space: u64,
+    },
+    /// The free disk space recovered, the import commits the blocks again.
+    Resumed,
+}
+
+/// The policy of the retries of the failed requests to the network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts of a request, including the first one.
+    pub max_attempts: usize,
+    /// The delay before the first retry. It doubles with each next retry.
+    pub base_delay: Duration,
+    /// The upper bound of the delay between the attempts.
+    pub max_delay: Duration,
+    /// Randomize the delay between zero and the computed delay to spread
+    /// the retries of the concurrent requests.
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+            jitter: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay before the `retry`, starting from zero for the first retry.
+    pub fn delay(&self, retry: usize) -> Duration {
+        let factor = u32::try_from(retry)
+            .ok()
+            .and_then(|retry| 2u32.checked_pow(retry))
+            .unwrap_or(u32::MAX);
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        if self.jitter {
+            rand::thread_rng().gen_range(Duration::ZERO..=delay)
+        } else {
+            delay
+        }
+    }
+}
+
+/// The hook called with every block committed by the import.
+pub type OnCommitted = Arc<dyn Fn(&SealedBlock) + Send + Sync>;
+
+/// The hook called with the height and the id of every block committed by the import,
+/// e.g. to append the block to an external merkle accumulator.
+pub type Accumulator = Arc<dyn Fn(BlockHeight, BlockId) + Send + Sync>;
+
+/// The hook called each time the import catches up to the observed network tip.
+pub type OnCaughtUp = Arc<dyn Fn() + Send + Sync>;
+
+/// The number of the peer report events kept for the lagging subscribers.
+const PEER_REPORT_EVENTS_CAPACITY: usize = 1024;
+
+/// The number of the circuit breaker events kept for the lagging subscribers.
+const CIRCUIT_BREAKER_EVENTS_CAPACITY: usize = 64;
+
+/// The number of the disk space events kept for the lagging subscribers.
+const DISK_SPACE_EVENTS_CAPACITY: usize = 64;
+
+/// The event emitted each time the import reports a peer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeerReportEvent {
+    /// The reported peer.
+    pub peer_id: PeerId,
+    /// The reason of the report.
+    pub reason: PeerReportReason,
+    /// The height of the block that caused the report, or the first height
+    /// of the requested range if the report is not about a single block.
+    pub height: BlockHeight,
+}
+
+/// The outcome of the last iteration of the import.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// The range of the iteration.
+    pub range: RangeInclusive<u32>,
+    /// The number of the heights committed from the start of the `range`.
+    pub committed: usize,
+    /// `true` if the iteration stopped at the `max_import_duration`. The heights
+    /// after the committed ones are not failed, the next iteration imports them.
+    pub deadline_reached: bool,
+}
+
+/// The number of the requests of each stage in flight, see [`Import::inflight`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InflightStats {
+    /// The requests of the headers in flight.
+    pub header_fetches: usize,
+    /// The requests of the transactions in flight.
+    pub transaction_fetches: usize,
+    /// The blocks being executed.
+    pub executions: usize,
+}
+
+/// The counters of the requests of each stage in flight.
+#[derive(Clone, Debug, Default)]
+struct InflightCounters {
+    header_fetches: Arc<AtomicUsize>,
+    transaction_fetches: Arc<AtomicUsize>,
+    executions: Arc<AtomicUsize>,
+}
+
+impl InflightCounters {
+    fn stats(&self) -> InflightStats {
+        InflightStats {
+            header_fetches: self.header_fetches.load(Ordering::Relaxed),
+            transaction_fetches: self.transaction_fetches.load(Ordering::Relaxed),
+            executions: self.executions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The throughput of a stage of the import, see [`Import::throughput`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StageThroughput {
+    /// The blocks processed by the stage per second of its work.
+    pub blocks_per_second: f64,
+    /// The bytes of the transactions processed by the stage per second of its work.
+    pub bytes_per_second: f64,
+}
+
+/// The throughput of the download and of the execution of the blocks.
+/// The lower one bounds the import, so it tells if the import is bound by
+/// the network or by the executor.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Throughput {
+    /// The fetch of the headers and the transactions.
+    pub fetch: StageThroughput,
+    /// The execution and the commit of the blocks.
+    pub execute: StageThroughput,
+}
+
+/// The work done by a stage of the import.
+#[derive(Clone, Copy, Debug, Default)]
+struct StageWork {
+    blocks: usize,
+    bytes: usize,
+    busy: Duration,
+}
+
+impl StageWork {
+    fn record(&mut self, blocks: usize, bytes: usize, busy: Duration) {
+        self.blocks = self.blocks.saturating_add(blocks);
+        self.bytes = self.bytes.saturating_add(bytes);
+        self.busy = self.busy.saturating_add(busy);
+    }
+
+    fn throughput(&self) -> StageThroughput {
+        if self.busy.is_zero() {
+            return StageThroughput::default()
+        }
+        let seconds = self.busy.as_secs_f64();
+        StageThroughput {
+            blocks_per_second: self.blocks as f64 / seconds,
+            bytes_per_second: self.bytes as f64 / seconds,
+        }
+    }
+}
+
+/// The work done by each stage of the import.
+#[derive(Clone, Copy, Debug, Default)]
+struct ThroughputWork {
+    fetch: StageWork,
+    execute: StageWork,
+}
+
+/// Counts the request in flight until it is dropped, so the count is released
+/// even if the request is cancelled.
+struct InflightGuard(Arc<AtomicUsize>);
+
+impl InflightGuard {
+    fn start(counter: &Arc<AtomicUsize>) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter.clone())
+    }
+}
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Reports the peers to the network and emits the [`PeerReportEvent`]s.
+/// Accounts the bytes fetched from the peers and chooses the peers of the headers.
+struct PeerReporter<P> {
+    p2p: Arc<P>,
+    events: broadcast::Sender<PeerReportEvent>,
+    bandwidth: PeerBandwidth,
+    selector: Option<Arc<dyn PeerSelector>>,
+    strikes: PeerStrikeCounter,
+    missing_transactions: MissingTransactions,
+    inflight: InflightCounters,
+}
+
+impl<P> Clone for PeerReporter<P> {
+    fn clone(&self) -> Self {
+        Self {
+            p2p: self.p2p.clone(),
+            events: self.events.clone(),
+            bandwidth: self.bandwidth.clone(),
+            selector: self.selector.clone(),
+            strikes: self.strikes.clone(),
+            missing_transactions: self.missing_transactions.clone(),
+            inflight: self.inflight.clone(),
+        }
+    }
+}
+
+impl<P> PeerReporter<P>
+where
+    P: PeerToPeerPort + Send + Sync + 'static,
+{
+    fn report(&self, peer_id: PeerId, reason: PeerReportReason, height: BlockHeight) {
+        if !self.strikes.strike(&peer_id) {
+            tracing::debug!("Recorded a strike of peer for {:?}", reason);
+            return
         }
+        tracing::info!("Reporting peer for {:?}", reason);
+
+        // Failure to report a peer is a non-fatal error; ignore the error
+        let _ = self
+            .p2p
+            .report_peer(peer_id.clone(), reason)
+            .trace_err(&format!("Failed to report peer {:?}", peer_id));
+        // No subscribers is not an error.
+        let _ = self.events.send(PeerReportEvent {
+            peer_id,
+            reason,
+            height,
+        });
+    }
+}
+
+/// The incidents of a peer within its current window.
+#[derive(Clone, Copy, Debug)]
+struct WindowStrikes {
+    started_at: Instant,
+    strikes: usize,
+}
+
+/// Counts the incidents of each peer within a window according to the [`PeerStrikes`].
+#[derive(Clone)]
+struct PeerStrikeCounter {
+    strikes: SharedMutex<HashMap<PeerId, WindowStrikes>>,
+    policy: Option<PeerStrikes>,
+    clock: Arc<dyn Clock>,
+}
+
+impl PeerStrikeCounter {
+    /// Records an incident of the `peer_id`.
+    /// Returns `true` if the peer should be reported.
+    fn strike(&self, peer_id: &PeerId) -> bool {
+        let Some(policy) = self.policy else {
+            return true
+        };
+        let now = self.clock.now();
+        self.strikes.apply(|strikes| {
+            let entry = strikes.entry(peer_id.clone()).or_insert(WindowStrikes {
+                started_at: now,
+                strikes: 0,
+            });
+            if now.saturating_duration_since(entry.started_at) >= policy.window {
+                *entry = WindowStrikes {
+                    started_at: now,
+                    strikes: 0,
+                };
+            }
+            entry.strikes = entry.strikes.saturating_add(1);
+            if entry.strikes >= policy.strike_threshold {
+                strikes.remove(peer_id);
+                true
+            } else {
+                false
+            }
+        })
+    }
+}
+
+/// Counts the consecutive batches each peer served the headers of,
+/// but didn't provide the transactions of.
+#[derive(Clone)]
+struct MissingTransactions {
+    misses: SharedMutex<HashMap<PeerId, usize>>,
+    threshold: Option<usize>,
+}
+
+impl MissingTransactions {
+    /// Records that the `peer_id` didn't provide the requested transactions.
+    fn record_miss(&self, peer_id: &PeerId) {
+        if self.threshold.is_none() {
+            return
+        }
+        self.misses.apply(|misses| {
+            let entry = misses.entry(peer_id.clone()).or_insert(0);
+            *entry = entry.saturating_add(1);
+        });
+    }
+
+    /// Records that the `peer_id` provided the requested transactions.
+    fn record_delivery(&self, peer_id: &PeerId) {
+        self.misses.apply(|misses| misses.remove(peer_id));
+    }
+
+    /// Returns `true` if the transactions shouldn't be requested from the `peer_id`
+    /// while another peer has them.
+    fn is_deprioritized(&self, peer_id: &PeerId) -> bool {
+        let Some(threshold) = self.threshold else {
+            return false
+        };
+        self.misses.apply(|misses| {
+            misses
+                .get(peer_id)
+                .map_or(false, |misses| *misses >= threshold)
+        })
+    }
+}
+
+/// The bytes fetched from a peer within its current window.
+#[derive(Clone, Copy, Debug)]
+struct WindowUsage {
+    started_at: Instant,
+    bytes: usize,
+}
+
+/// Accounts the bytes fetched from each peer within a window
+/// and limits them by the budget.
+#[derive(Clone)]
+struct PeerBandwidth {
+    usage: SharedMutex<HashMap<PeerId, WindowUsage>>,
+    budget: Option<usize>,
+    window: Duration,
+    clock: Arc<dyn Clock>,
+}
+
+impl PeerBandwidth {
+    /// Adds the `bytes` fetched from the `peer_id` to its current window.
+    fn record(&self, peer_id: &PeerId, bytes: usize) {
+        let now = self.clock.now();
+        self.usage.apply(|usage| {
+            let entry = usage.entry(peer_id.clone()).or_insert(WindowUsage {
+                started_at: now,
+                bytes: 0,
+            });
+            if now.saturating_duration_since(entry.started_at) >= self.window {
+                *entry = WindowUsage {
+                    started_at: now,
+                    bytes: 0,
+                };
+            }
+            entry.bytes = entry.bytes.saturating_add(bytes);
+        });
+    }
+
+    /// The bytes fetched from the `peer_id` within its current window.
+    fn bytes(&self, peer_id: &PeerId) -> usize {
+        let now = self.clock.now();
+        self.usage.apply(|usage| {
+            usage
+                .get(peer_id)
+                .filter(|entry| {
+                    now.saturating_duration_since(entry.started_at) < self.window
+                })
+                .map_or(0, |entry| entry.bytes)
+        })
+    }
+
+    /// Returns `true` if the `peer_id` has used up its budget of the current window.
+    fn is_over_budget(&self, peer_id: &PeerId) -> bool {
+        self.budget
+            .map_or(false, |budget| self.bytes(peer_id) >= budget)
     }
 }
 
@@ -83,14 +847,69 @@ pub struct Import<P, E, C> {
     state: SharedMutex<State>,
     /// Notify import when sync has new work.
     notify: Arc<Notify>,
-    /// Configuration parameters.
-    params: Config,
+    /// Configuration parameters. Can be updated at runtime.
+    params: SharedMutex<Config>,
     /// Network port.
     p2p: Arc<P>,
     /// Executor port.
     executor: Arc<E>,
     /// Consensus port.
     consensus: Arc<C>,
+    /// The source of time.
+    clock: Arc<dyn Clock>,
+    /// The hook called after each successfully committed block.
+    on_committed: Option<OnCommitted>,
+    /// The hook called once the import catches up to the observed network tip.
+    on_caught_up: Option<OnCaughtUp>,
+    /// Called with the id of each committed block, in the order of heights.
+    accumulator: Option<Accumulator>,
+    /// The last height passed to the `accumulator`.
+    accumulated_height: SharedMutex<Option<u32>>,
+    /// `true` if the import has caught up and no new heights were observed since.
+    caught_up: AtomicBool,
+    /// Notified with the height of each successfully committed block.
+    committed_height: Arc<watch::Sender<Option<u32>>>,
+    /// Notified with each report of a peer.
+    peer_reports: broadcast::Sender<PeerReportEvent>,
+    /// The bytes fetched from each peer within its current window.
+    peer_usage: SharedMutex<HashMap<PeerId, WindowUsage>>,
+    /// The incidents of each peer within its current window.
+    peer_strikes: SharedMutex<HashMap<PeerId, WindowStrikes>>,
+    /// The consecutive batches each peer didn't provide the transactions of.
+    missing_transactions: SharedMutex<HashMap<PeerId, usize>>,
+    /// Chooses the peer of each request of the headers.
+    /// `None` leaves the choice to the network.
+    peer_selector: Option<Arc<dyn PeerSelector>>,
+    /// The state of the circuit breaker.
+    breaker: SharedMutex<BreakerState>,
+    /// Notified with each transition of the circuit breaker.
+    breaker_events: broadcast::Sender<CircuitBreakerEvent>,
+    /// The heights cancelled during the current stream.
+    cancelled_heights: CancelledHeights,
+    /// Verifies the external finality evidence of the headers.
+    /// `None` accepts the headers passing the consensus check.
+    finality_verifier: Option<Arc<dyn FinalityVerifier>>,
+    /// Checks the downloaded transactions before the execution of their block.
+    /// `None` leaves all checks to the execution.
+    transaction_checker: Option<Arc<dyn TransactionChecker>>,
+    /// Persists the failed heights if the `persist_failed_heights` is enabled.
+    /// `None` keeps them only in memory.
+    failed_heights_store: Option<Arc<dyn FailedHeightsStore>>,
+    /// Reports the free disk space checked against the `min_free_disk_space`.
+    /// `None` never pauses the import.
+    disk_monitor: Option<Arc<dyn DiskMonitor>>,
+    /// Notified each time the import pauses or resumes because of the free disk space.
+    disk_space_events: broadcast::Sender<DiskSpaceEvent>,
+    /// The range imported by the stream in flight.
+    in_flight: SharedMutex<Option<RangeInclusive<u32>>>,
+    /// The outcome of the last iteration of the import.
+    last_summary: SharedMutex<Option<ImportSummary>>,
+    /// The reorg refused by the stream in flight, if any.
+    refused_reorg: SharedMutex<Option<ReorgTooDeep>>,
+    /// The requests of each stage in flight.
+    inflight: InflightCounters,
+    /// The work done by each stage, if the `record_throughput` is enabled.
+    throughput: SharedMutex<ThroughputWork>,
 }
 
 impl<P, E, C> Import<P, E, C> {
@@ -107,10 +926,249 @@ impl<P, E, C> Import<P, E, C> {
         Self {
             state,
             notify,
-            params,
+            params: SharedMutex::new(params),
             p2p,
             executor,
             consensus,
+            clock: Arc::new(TokioClock),
+            on_committed: None,
+            on_caught_up: None,
+            accumulator: None,
+            accumulated_height: SharedMutex::new(None),
+            caught_up: AtomicBool::new(false),
+            committed_height: Arc::new(watch::channel(None).0),
+            peer_reports: broadcast::channel(PEER_REPORT_EVENTS_CAPACITY).0,
+            peer_usage: SharedMutex::new(HashMap::new()),
+            peer_strikes: SharedMutex::new(HashMap::new()),
+            missing_transactions: SharedMutex::new(HashMap::new()),
+            peer_selector: None,
+            breaker: SharedMutex::new(BreakerState::Closed { failures: 0 }),
+            breaker_events: broadcast::channel(CIRCUIT_BREAKER_EVENTS_CAPACITY).0,
+            cancelled_heights: Arc::new(watch::channel(BTreeSet::new()).0),
+            finality_verifier: None,
+            transaction_checker: None,
+            failed_heights_store: None,
+            disk_monitor: None,
+            disk_space_events: broadcast::channel(DISK_SPACE_EVENTS_CAPACITY).0,
+            in_flight: SharedMutex::new(None),
+            last_summary: SharedMutex::new(None),
+            refused_reorg: SharedMutex::new(None),
+            inflight: InflightCounters::default(),
+            throughput: SharedMutex::new(ThroughputWork::default()),
+        }
+    }
+
+    /// Get the current configuration of the import.
+    pub fn config(&self) -> Config {
+        self.params.apply(|params| *params)
+    }
+
+    /// Replace the configuration of the import.
+    ///
+    /// The streams in progress keep using the old configuration,
+    /// the next stream picks up the new values.
+    pub fn set_config(&self, config: Config) {
+        self.params.apply(|params| *params = config);
+    }
+
+    /// Replace the source of time used by the import.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Set the hook called after each successfully committed block.
+    ///
+    /// The hook is called inside of the import loop, so it must not block.
+    /// Heavy work should be offloaded to another task, e.g. via a channel.
+    pub fn with_on_committed(mut self, on_committed: OnCommitted) -> Self {
+        self.on_committed = Some(on_committed);
+        self
+    }
+
+    /// Set the hook called once the committed height reaches the observed network tip.
+    ///
+    /// The hook is called again only after new heights are observed and the import
+    /// catches up with them. Like `on_committed`, it must not block.
+    pub fn with_on_caught_up(mut self, on_caught_up: OnCaughtUp) -> Self {
+        self.on_caught_up = Some(on_caught_up);
+        self
+    }
+
+    /// Set the hook called with the id of each committed block.
+    ///
+    /// The blocks are committed in the order of heights under all strategies, so the
+    /// hook is called exactly once per block, in the strictly increasing order of
+    /// heights. Like `on_committed`, it must not block.
+    pub fn with_accumulator(mut self, accumulator: Accumulator) -> Self {
+        self.accumulator = Some(accumulator);
+        self
+    }
+
+    /// Set the verifier of the external finality evidence of the headers.
+    ///
+    /// It is called for each header passing the consensus check. The headers it
+    /// rejects are not imported, like the headers failing the consensus check,
+    /// but their peers are not reported, because the evidence comes from elsewhere.
+    pub fn with_finality_verifier(
+        mut self,
+        finality_verifier: Arc<dyn FinalityVerifier>,
+    ) -> Self {
+        self.finality_verifier = Some(finality_verifier);
+        self
+    }
+
+    /// Set the checker of the downloaded transactions.
+    ///
+    /// It is called for each transaction of a block matching its header, before the
+    /// execution. The block with a malformed transaction is not executed, and its
+    /// peer is reported for the invalid transactions.
+    pub fn with_transaction_checker(
+        mut self,
+        transaction_checker: Arc<dyn TransactionChecker>,
+    ) -> Self {
+        self.transaction_checker = Some(transaction_checker);
+        self
+    }
+
+    /// Set the monitor of the free disk space.
+    ///
+    /// If the `min_free_disk_space` is set, the import pauses before committing
+    /// the next blocks while the free disk space is below it, and resumes once
+    /// it recovers. The transitions are emitted as [`DiskSpaceEvent`]s.
+    pub fn with_disk_monitor(mut self, disk_monitor: Arc<dyn DiskMonitor>) -> Self {
+        self.disk_monitor = Some(disk_monitor);
+        self
+    }
+
+    /// Set the store of the failed heights.
+    ///
+    /// If the `persist_failed_heights` is enabled, the failed heights persisted
+    /// by the previous run are loaded into the state, and each later update of them
+    /// is persisted, so the failed heights survive the restart of the node.
+    pub fn with_failed_heights_store(
+        mut self,
+        failed_heights_store: Arc<dyn FailedHeightsStore>,
+    ) -> Self {
+        if self.config().persist_failed_heights {
+            match failed_heights_store.load_failed_heights() {
+                Ok(failed) => self
+                    .state
+                    .apply(|s| s.restore_failed(failed.into_iter().collect())),
+                Err(err) => {
+                    tracing::warn!("Failed to load the failed heights: {:?}", err)
+                }
+            }
+        }
+        self.failed_heights_store = Some(failed_heights_store);
+        self
+    }
+
+    /// Set the policy of the choice of the peer of each request of the headers.
+    ///
+    /// The selector chooses among the connected peers that advertise the last
+    /// height of the batch and are neither excluded by the retries nor over
+    /// their bandwidth budget. If there are no such peers, the network chooses.
+    pub fn with_peer_selector(mut self, peer_selector: Arc<dyn PeerSelector>) -> Self {
+        self.peer_selector = Some(peer_selector);
+        self
+    }
+
+    /// Subscribe to the events emitted each time a peer is reported.
+    ///
+    /// The subscriber that lags behind by more than the capacity of
+    /// the channel misses the oldest events.
+    pub fn subscribe_peer_reports(&self) -> broadcast::Receiver<PeerReportEvent> {
+        self.peer_reports.subscribe()
+    }
+
+    /// Subscribe to the transitions of the circuit breaker.
+    ///
+    /// The subscriber that lags behind by more than the capacity of
+    /// the channel misses the oldest events.
+    pub fn subscribe_circuit_breaker(&self) -> broadcast::Receiver<CircuitBreakerEvent> {
+        self.breaker_events.subscribe()
+    }
+
+    /// Subscribe to the pauses and resumes of the import because of the free disk space.
+    ///
+    /// The subscriber that lags behind by more than the capacity of
+    /// the channel misses the oldest events.
+    pub fn subscribe_disk_space(&self) -> broadcast::Receiver<DiskSpaceEvent> {
+        self.disk_space_events.subscribe()
+    }
+
+    /// The number of bytes of the headers and transactions fetched from
+    /// the `peer_id` within its current `bandwidth_window`.
+    pub fn bytes_fetched_from(&self, peer_id: &PeerId) -> usize {
+        self.peer_bandwidth().bytes(peer_id)
+    }
+
+    /// The stream used by the next iteration of the import.
+    pub fn active_strategy(&self) -> ImportStrategy {
+        ImportStrategy::from(&self.config())
+    }
+
+    /// The report of the committed, failed and pending heights for the tooling.
+    pub fn state_report(&self) -> SyncStateReport {
+        self.state.apply(|s| s.to_report())
+    }
+
+    /// The outcome of the last iteration of the import that launched a stream.
+    pub fn last_summary(&self) -> Option<ImportSummary> {
+        self.last_summary.apply(|summary| summary.clone())
+    }
+
+    /// The number of the header fetches, the transaction fetches and the executions
+    /// in flight right now.
+    pub fn inflight(&self) -> InflightStats {
+        self.inflight.stats()
+    }
+
+    /// The throughput of the download and of the execution of the blocks imported
+    /// so far, per second of the work of each stage. It stays zero unless
+    /// the `record_throughput` is enabled.
+    pub fn throughput(&self) -> Throughput {
+        self.throughput.apply(|work| Throughput {
+            fetch: work.fetch.throughput(),
+            execute: work.execute.throughput(),
+        })
+    }
+
+    fn record_execution(&self, bytes: usize, busy: Duration) {
+        if self.config().record_throughput {
+            self.throughput
+                .apply(|work| work.execute.record(1, bytes, busy));
+        }
+    }
+
+    fn peer_bandwidth(&self) -> PeerBandwidth {
+        let config = self.config();
+        PeerBandwidth {
+            usage: self.peer_usage.clone(),
+            budget: config.max_bytes_per_peer_per_window,
+            window: config.bandwidth_window,
+            clock: self.clock.clone(),
+        }
+    }
+
+    /// Move the failed heights back to the processing range and
+    /// wake up the import to process them.
+    pub fn reset_failed(&self) {
+        if self.state.apply(|s| s.reset_failed()) {
+            self.persist_failed_heights(&RangeSet::new());
+            self.notify.notify_one();
+        }
+    }
+
+    fn persist_failed_heights(&self, failed: &RangeSet) {
+        if !self.config().persist_failed_heights {
+            return
+        }
+        if let Some(failed_heights_store) = &self.failed_heights_store {
+            let _ = failed_heights_store
+                .store_failed_heights(failed.ranges().collect())
+                .trace_err("Failed to persist the failed heights");
         }
     }
 
@@ -118,32 +1176,189 @@ impl<P, E, C> Import<P, E, C> {
     pub fn notify_one(&self) {
         self.notify.notify_one()
     }
+
+    /// Cancels the download and the execution of the in-flight `height`,
+    /// e.g. because a reorg pruned it.
+    ///
+    /// The download of the batch with the `height` is aborted, and neither the
+    /// `height` nor the following heights of the stream are executed, so they
+    /// are reported as failed. The execution of a block that is already in progress
+    /// is not interrupted. The heights outside of the range in flight are ignored,
+    /// so a cancellation issued between the streams doesn't affect the next one.
+    pub fn cancel_height(&self, height: BlockHeight) {
+        self.in_flight.apply(|in_flight| {
+            if in_flight
+                .as_ref()
+                .is_some_and(|range| range.contains(&*height))
+            {
+                self.cancelled_heights.send_modify(|heights| {
+                    heights.insert(*height);
+                });
+            }
+        });
+    }
+
+    fn is_cancelled(&self, height: BlockHeight) -> bool {
+        self.cancelled_heights.borrow().contains(&height)
+    }
 }
 
+/// The heights cancelled during the current stream.
+type CancelledHeights = Arc<watch::Sender<BTreeSet<u32>>>;
+
 #[derive(Debug)]
 struct Batch<T> {
-    peer: PeerId,
+    /// The peer that served the batch, `None` if it was served from the local storage.
+    peer: Option<PeerId>,
     range: Range<u32>,
     results: Vec<T>,
+    /// The time spent on the stages of the import of the batch so far.
+    timings: ImportTimings,
 }
 
 impl<T> Batch<T> {
-    pub fn new(peer: PeerId, range: Range<u32>, results: Vec<T>) -> Self {
+    pub fn new(peer: Option<PeerId>, range: Range<u32>, results: Vec<T>) -> Self {
         Self {
             peer,
             range,
             results,
+            timings: ImportTimings::default(),
         }
     }
 
+    pub fn with_timings(mut self, timings: ImportTimings) -> Self {
+        self.timings = timings;
+        self
+    }
+
     pub fn is_err(&self) -> bool {
         self.results.len() < self.range.len()
     }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Batch<U> {
+        Batch::new(
+            self.peer,
+            self.range,
+            self.results.into_iter().map(f).collect(),
+        )
+        .with_timings(self.timings)
+    }
+}
+
+/// The error of a fetch from the network.
+#[derive(Debug)]
+struct SourcePeerError {
+    /// The peer that failed the request, if it is known.
+    peer_id: Option<PeerId>,
+    error: anyhow::Error,
+}
+
+impl SourcePeerError {
+    fn new(peer_id: PeerId, error: anyhow::Error) -> Self {
+        Self {
+            peer_id: Some(peer_id),
+            error,
+        }
+    }
+
+    fn unknown(error: anyhow::Error) -> Self {
+        Self {
+            peer_id: None,
+            error,
+        }
+    }
 }
 
 type SealedHeaderBatch = Batch<SealedBlockHeader>;
 type SealedBlockBatch = Batch<SealedBlock>;
 
+/// The size of the transactions that are downloaded but not yet committed.
+#[derive(Clone, Copy, Debug, Default)]
+struct BufferedTxBytes {
+    /// The total size of the buffered transactions.
+    buffered: usize,
+    /// The transactions of the reserved batch are being fetched.
+    fetching: bool,
+}
+
+/// Limits the number of blocks and the size of their transactions that are
+/// downloaded but not yet committed.
+#[derive(Clone, Debug)]
+struct UncommittedBlocks {
+    limit: Option<(usize, Arc<Semaphore>)>,
+    tx_bytes: Option<(usize, Arc<watch::Sender<BufferedTxBytes>>)>,
+}
+
+impl UncommittedBlocks {
+    fn new(
+        max_uncommitted_blocks: Option<usize>,
+        max_buffered_tx_bytes: Option<usize>,
+    ) -> Self {
+        let limit = max_uncommitted_blocks.map(|max| {
+            let max = max.clamp(1, Semaphore::MAX_PERMITS);
+            (max, Arc::new(Semaphore::new(max)))
+        });
+        let tx_bytes = max_buffered_tx_bytes
+            .map(|max| (max, Arc::new(watch::channel(Default::default()).0)));
+        Self { limit, tx_bytes }
+    }
+
+    /// Waits until there is a room for the blocks from the `range`.
+    ///
+    /// If the size of the transactions is limited, it also waits until the
+    /// transactions of the previously reserved batch are fetched, so every
+    /// reservation must be followed by [`Self::fetched`].
+    async fn reserve(&self, range: &Range<u32>) {
+        if let Some((max, semaphore)) = &self.limit {
+            let permits = Self::permits(*max, range);
+            if let Ok(permit) = semaphore.acquire_many(permits).await {
+                permit.forget();
+            }
+        }
+        if let Some((max, tx_bytes)) = &self.tx_bytes {
+            let mut state = tx_bytes.subscribe();
+            loop {
+                let BufferedTxBytes { buffered, fetching } = *state.borrow_and_update();
+                if !fetching && buffered < *max {
+                    break
+                }
+                if state.changed().await.is_err() {
+                    break
+                }
+            }
+            tx_bytes.send_modify(|state| state.fetching = true);
+        }
+    }
+
+    /// Adds the `size` of the fetched transactions of the reserved batch.
+    fn fetched(&self, size: usize) {
+        if let Some((_, tx_bytes)) = &self.tx_bytes {
+            tx_bytes.send_modify(|state| {
+                state.buffered = state.buffered.saturating_add(size);
+                state.fetching = false;
+            });
+        }
+    }
+
+    /// Frees the room reserved for the blocks from the `range` with
+    /// the transactions of the `size`.
+    fn release(&self, range: &Range<u32>, size: usize) {
+        if let Some((max, semaphore)) = &self.limit {
+            semaphore.add_permits(Self::permits(*max, range) as usize);
+        }
+        if let Some((_, tx_bytes)) = &self.tx_bytes {
+            tx_bytes.send_modify(|state| {
+                state.buffered = state.buffered.saturating_sub(size);
+            });
+        }
+    }
+
+    fn permits(max: usize, range: &Range<u32>) -> u32 {
+        u32::try_from(range.len().min(max))
+            .expect("The size of the range can't exceed `u32`")
+    }
+}
+
 impl<P, E, C> Import<P, E, C>
 where
     P: PeerToPeerPort + Send + Sync + 'static,
@@ -153,36 +1368,400 @@ where
     #[tracing::instrument(skip_all)]
     /// Execute imports until a shutdown is requested.
     pub async fn import(&self, shutdown: &mut StateWatcher) -> anyhow::Result<bool> {
-        self.import_inner(shutdown).await?;
+        let failed_before = self.state.apply(|s| s.failed_heights().clone());
+        let imported = self.import_inner(shutdown).await;
+        // The committed heights don't need to be tracked anymore.
+        let failed = self.state.apply(|s| {
+            if let Some(committed) = s.committed_height() {
+                s.trim_below(committed.saturating_add(1));
+            }
+            s.failed_heights().clone()
+        });
+        if failed != failed_before {
+            self.persist_failed_heights(&failed);
+        }
+        imported?;
 
         Ok(wait_for_notify_or_shutdown(&self.notify, shutdown).await)
     }
 
+    /// Waits until the committed height reaches the `target`.
+    /// Returns `false` if the shutdown signal was received first.
+    pub async fn wait_until_height(
+        &self,
+        target: BlockHeight,
+        shutdown: &mut StateWatcher,
+    ) -> anyhow::Result<bool> {
+        // Subscribe before checking the state to not miss a commit in between.
+        let mut committed = self.committed_height.subscribe();
+        loop {
+            let reached = self
+                .state
+                .apply(|s| s.committed_height())
+                .map_or(false, |height| height >= *target);
+            if reached {
+                return Ok(true)
+            }
+
+            tokio::select! {
+                changed = committed.changed() => {
+                    if changed.is_err() {
+                        return Ok(false)
+                    }
+                }
+                _ = shutdown.while_started() => return Ok(false),
+            }
+        }
+    }
+
+    /// Executes the blocks of the `range` on the `replay_executor` and returns the root
+    /// of the state after each of them, so they can be compared with the canonical chain.
+    ///
+    /// The blocks are read from the local storage, or fetched from the network
+    /// if they are not there. Nothing is committed to the main database, and
+    /// the state of the import is not affected.
+    pub async fn replay_range(
+        &self,
+        range: RangeInclusive<u32>,
+        replay_executor: &dyn ReplayExecutor,
+    ) -> anyhow::Result<Vec<(BlockHeight, StateRoot)>> {
+        let mut roots = Vec::with_capacity(range.clone().count());
+        for height in range {
+            let block = match self.local_block(height) {
+                Some(block) => block,
+                None => self.fetch_block(height).await?,
+            };
+            let root = replay_executor.replay(block)?;
+            roots.push((height.into(), root));
+        }
+        Ok(roots)
+    }
+
+    /// Returns the block at the `height` from the local storage, if all of it is there.
+    fn local_block(&self, height: u32) -> Option<SealedBlock> {
+        let header = self
+            .executor
+            .local_sealed_header(height.into())
+            .trace_err("Failed to get the local header")
+            .ok()
+            .flatten()?;
+        let transactions = self
+            .executor
+            .local_transactions(height.into())
+            .trace_err("Failed to get the local transactions")
+            .ok()
+            .flatten()?;
+        let block = Block::try_from_executed(header.entity, transactions.0)?;
+        Some(SealedBlock {
+            entity: block,
+            consensus: header.consensus,
+        })
+    }
+
+    /// Fetches the block at the `height` from the network and checks its consensus.
+    async fn fetch_block(&self, height: u32) -> anyhow::Result<SealedBlock> {
+        let range = height..height.saturating_add(1);
+        let SourcePeer { peer_id, data } =
+            self.p2p.get_sealed_block_headers(range.clone()).await?;
+        let header = data
+            .and_then(|headers| headers.into_iter().next())
+            .ok_or_else(|| anyhow::anyhow!("The header at {height} is not available"))?;
+        if !self.consensus.check_sealed_header(&header)? {
+            return Err(anyhow::anyhow!("The header at {height} has invalid consensus"))
+        }
+        let transactions = self
+            .p2p
+            .get_transactions(SourcePeer {
+                peer_id,
+                data: range,
+            })
+            .await?
+            .and_then(|transactions| transactions.into_iter().next())
+            .ok_or_else(|| {
+                anyhow::anyhow!("The transactions at {height} are not available")
+            })?;
+        let block = Block::try_from_executed(header.entity, transactions.0)
+            .ok_or_else(|| {
+                anyhow::anyhow!("The transactions at {height} don't match the header")
+            })?;
+        Ok(SealedBlock {
+            entity: block,
+            consensus: header.consensus,
+        })
+    }
+
     async fn import_inner(&self, shutdown: &StateWatcher) -> anyhow::Result<()> {
-        // If there is a range to process, launch the stream.
-        if let Some(range) = self.state.apply(|s| s.process_range()) {
-            // Launch the stream to import the range.
-            let count = self.launch_stream(range.clone(), shutdown).await;
-
-            // Get the size of the range.
-            let range_len = range.size_hint().0;
-
-            // If we did not process the entire range, mark the failed heights as failed.
-            if count < range_len {
-                let count = u32::try_from(count)
-                    .expect("Size of the range can't be more than maximum `BlockHeight`");
-                let incomplete_range = range.start().saturating_add(count)..=*range.end();
-                self.state
-                    .apply(|s| s.failed_to_process(incomplete_range.clone()));
-                Err(anyhow::anyhow!(
-                    "Failed to import range of blocks: {:?}",
-                    incomplete_range
-                ))?;
+        tracing::debug!("Importing with the {:?} strategy", self.active_strategy());
+        // If there is no range to process, there is nothing to do.
+        let Some(range) = self.range_to_process() else {
+            return Ok(())
+        };
+
+        // An empty range doesn't require setting up the stream.
+        if range.is_empty() {
+            tracing::debug!("The range {:?} is empty, skipping the import", range);
+            return Ok(())
+        }
+        let Some(range) = self.exclude_in_flight(range)? else {
+            return Ok(())
+        };
+        // There are new heights to import, so the import fell behind the tip.
+        self.caught_up.store(false, Ordering::Relaxed);
+
+        if !self.await_circuit_breaker(shutdown).await {
+            return Ok(())
+        }
+
+        // Launch the stream to import the range.
+        let started_at = self.clock.now();
+        let deadline = self
+            .config()
+            .max_import_duration
+            .and_then(|duration| started_at.checked_add(duration));
+        let in_flight =
+            InFlight::start(&self.in_flight, &self.cancelled_heights, range.clone());
+        self.refused_reorg.apply(|refused| *refused = None);
+        let count = self
+            .launch_watched_stream(range.clone(), deadline, shutdown)
+            .await;
+        drop(in_flight);
+        tracing::debug!(
+            "Imported {} blocks in {:?}",
+            count,
+            self.clock.now().saturating_duration_since(started_at)
+        );
+
+        let incomplete_range = incomplete_range(&range, count)?;
+        let deadline_reached = incomplete_range.is_some()
+            && deadline.map_or(false, |deadline| self.clock.now() >= deadline);
+        self.last_summary.apply(|summary| {
+            *summary = Some(ImportSummary {
+                range: range.clone(),
+                committed: count,
+                deadline_reached,
+            })
+        });
+
+        if deadline_reached {
+            tracing::debug!(
+                "The import reached the deadline after {} blocks of the range {:?}",
+                count,
+                range
+            );
+            // The rest of the range is resumed by the next iteration.
+            self.notify.notify_one();
+            return Ok(())
+        }
+        self.record_import_outcome(count, incomplete_range.is_some());
+
+        // If we did not process the entire range, mark the failed heights as failed.
+        if let Some(incomplete_range) = incomplete_range {
+            self.state
+                .apply(|s| s.failed_to_process(incomplete_range.clone()));
+            if let Some(refused) = self.refused_reorg.apply(|refused| refused.take()) {
+                return Err(refused.into())
             }
+            Err(anyhow::anyhow!(
+                "Failed to import range of blocks: {:?}",
+                incomplete_range
+            ))?;
+        }
+
+        // If more heights were observed during the stream, continue without
+        // waiting for the next external notification.
+        if self
+            .range_to_process()
+            .map_or(false, |range| !range.is_empty())
+        {
+            self.notify.notify_one();
         }
         Ok(())
     }
 
+    /// Applies the `overlap_policy` to the `range` if it overlaps with the range in flight.
+    /// Returns `None` if the whole `range` is in flight.
+    fn exclude_in_flight(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> anyhow::Result<Option<RangeInclusive<u32>>> {
+        let Some(in_flight) = self.in_flight.apply(|in_flight| in_flight.clone()) else {
+            return Ok(Some(range))
+        };
+        if range.end() < in_flight.start() || range.start() > in_flight.end() {
+            return Ok(Some(range))
+        }
+        match self.config().overlap_policy {
+            OverlapPolicy::Reject => Err(anyhow::anyhow!(
+                "The range {:?} overlaps with the range {:?} in flight",
+                range,
+                in_flight
+            )),
+            OverlapPolicy::Clamp => {
+                let above = in_flight
+                    .end()
+                    .checked_add(1)
+                    .map(|start| start..=*range.end())
+                    .filter(|above| !above.is_empty());
+                let below = in_flight
+                    .start()
+                    .checked_sub(1)
+                    .map(|end| *range.start()..=end)
+                    .filter(|below| !below.is_empty());
+                let clamped = above.or(below);
+                tracing::warn!(
+                    "The range {:?} overlaps with the range {:?} in flight, clamped to {:?}",
+                    range,
+                    in_flight,
+                    clamped
+                );
+                Ok(clamped)
+            }
+        }
+    }
+
+    /// Waits until the cooldown of the tripped circuit breaker ends
+    /// and half-opens it. Returns `false` if the shutdown signal was
+    /// received first.
+    async fn await_circuit_breaker(&self, shutdown: &StateWatcher) -> bool {
+        let Some(breaker) = self.config().circuit_breaker else {
+            return true
+        };
+        let tripped_at = self.breaker.apply(|state| match *state {
+            BreakerState::Open { tripped_at } => Some(tripped_at),
+            _ => None,
+        });
+        let Some(tripped_at) = tripped_at else {
+            return true
+        };
+
+        let elapsed = self.clock.now().saturating_duration_since(tripped_at);
+        let remaining = breaker.cooldown.saturating_sub(elapsed);
+        tracing::warn!("The import is paused by the circuit breaker for {:?}", remaining);
+        let mut shutdown = shutdown.clone();
+        tokio::select! {
+            _ = self.clock.sleep(remaining) => {},
+            _ = shutdown.while_started() => return false,
+        }
+
+        self.breaker.apply(|state| *state = BreakerState::HalfOpen);
+        // No subscribers is not an error.
+        let _ = self.breaker_events.send(CircuitBreakerEvent::HalfOpened);
+        true
+    }
+
+    /// Updates the circuit breaker with the outcome of an import that
+    /// `committed` blocks and `failed` to import the whole range.
+    fn record_import_outcome(&self, committed: usize, failed: bool) {
+        let Some(breaker) = self.config().circuit_breaker else {
+            return
+        };
+        let max_failures = breaker.max_consecutive_failures.max(1);
+        let now = self.clock.now();
+        let event = self.breaker.apply(|state| {
+            let (failures, half_open) = match *state {
+                BreakerState::Closed { failures } => (failures, false),
+                // A single failure trips the half-open breaker again.
+                BreakerState::HalfOpen | BreakerState::Open { .. } => {
+                    (max_failures.saturating_sub(1), true)
+                }
+            };
+            let failures = if committed > 0 { 0 } else { failures };
+            let failures = if failed {
+                failures.saturating_add(1)
+            } else {
+                failures
+            };
+
+            if failures >= max_failures {
+                *state = BreakerState::Open { tripped_at: now };
+                Some(CircuitBreakerEvent::Tripped { failures })
+            } else {
+                *state = BreakerState::Closed { failures };
+                half_open.then_some(CircuitBreakerEvent::Closed)
+            }
+        });
+        if let Some(event) = event {
+            tracing::info!("The circuit breaker of the import: {:?}", event);
+            // No subscribers is not an error.
+            let _ = self.breaker_events.send(event);
+        }
+    }
+
+    /// Get the range to import, excluding the heights within the
+    /// `confirmation_lag` of the observed network tip and limited
+    /// by the `max_range_per_iteration`.
+    fn range_to_process(&self) -> Option<RangeInclusive<u32>> {
+        let config = self.config();
+        let range = self.state.apply(|s| s.process_range())?;
+        let end = range.end().checked_sub(config.confirmation_lag)?;
+        let end = match config.max_range_per_iteration {
+            Some(max) => {
+                let max = u32::try_from(max).unwrap_or(u32::MAX).max(1);
+                end.min(range.start().saturating_add(max.saturating_sub(1)))
+            }
+            None => end,
+        };
+        Some(*range.start()..=end)
+    }
+
+    /// Launches the stream to import the `range` and restarts it from the last
+    /// committed height each time it commits no block within the
+    /// `stream_stall_timeout`.
+    ///
+    /// The requests of the cancelled stream are abandoned, their responses are
+    /// dropped. Returns the number of imported blocks from the `range`.
+    async fn launch_watched_stream(
+        &self,
+        range: RangeInclusive<u32>,
+        deadline: Option<Instant>,
+        shutdown: &StateWatcher,
+    ) -> usize {
+        let Some(stall_timeout) = self.config().stream_stall_timeout else {
+            return self.launch_stream(range, deadline, shutdown).await
+        };
+
+        let mut remaining = range.clone();
+        loop {
+            // Subscribe before launching the stream to not miss the first commit.
+            let mut committed = self.committed_height.subscribe();
+            let stream = self.launch_stream(remaining.clone(), deadline, shutdown);
+            // Resolves once no block is committed within the `stall_timeout`.
+            let watchdog = async {
+                loop {
+                    let clock = self.clock.as_ref();
+                    let changed =
+                        clock::timeout(clock, stall_timeout, committed.changed()).await;
+                    if !matches!(changed, Some(Ok(()))) {
+                        break
+                    }
+                }
+            };
+            futures::pin_mut!(stream);
+            futures::pin_mut!(watchdog);
+            let skipped = remaining.start().saturating_sub(*range.start());
+            let skipped = usize::try_from(skipped)
+                .expect("The size of the range can't exceed `usize`");
+            match futures::future::select(stream, watchdog).await {
+                futures::future::Either::Left((count, _)) => {
+                    return skipped.saturating_add(count)
+                }
+                futures::future::Either::Right(_) => {
+                    let next = self
+                        .state
+                        .apply(|s| s.committed_height())
+                        .map_or(*remaining.start(), |height| height.saturating_add(1))
+                        .max(*remaining.start());
+                    tracing::warn!(
+                        "The import stream stalled for {:?}, restarting it from the height {}",
+                        stall_timeout,
+                        next
+                    );
+                    remaining = next..=*range.end();
+                }
+            }
+        }
+    }
+
     #[tracing::instrument(skip(self, shutdown))]
     /// Launches a stream to import and execute a range of blocks.
     ///
@@ -190,47 +1769,118 @@ where
     /// an error occurs.
     /// If an error occurs, the preceding blocks still be processed
     /// and the error will be returned.
+    /// No new batches are requested after the `deadline`.
     async fn launch_stream(
         &self,
         range: RangeInclusive<u32>,
+        deadline: Option<Instant>,
         shutdown: &StateWatcher,
     ) -> usize {
         let Self {
-            state,
-            params,
-            p2p,
             executor,
             consensus,
             ..
         } = &self;
+        let reporter = self.peer_reporter();
+        let params = &self.config();
+        if params.headers_only {
+            return self.launch_headers_stream(range, deadline, shutdown).await
+        }
 
         let shutdown_signal = shutdown.clone();
         let (shutdown_guard, mut shutdown_guard_recv) =
             tokio::sync::mpsc::channel::<()>(1);
 
-        let block_stream =
-            get_block_stream(range.clone(), params, p2p.clone(), consensus.clone());
+        let warmup = {
+            let mut shutdown = shutdown.clone();
+            tokio::select! {
+                warmup = get_warmup_batches(
+                    range.clone(),
+                    params,
+                    &reporter,
+                    executor.as_ref(),
+                    self.clock.as_ref(),
+                ) => warmup,
+                _ = shutdown.while_started() => return 0,
+            }
+        };
+
+        let uncommitted_blocks = UncommittedBlocks::new(
+            params.max_uncommitted_blocks,
+            params.max_buffered_tx_bytes,
+        );
+        let block_stream = get_block_stream(
+            range.clone(),
+            params,
+            reporter.clone(),
+            executor.clone(),
+            consensus.clone(),
+            self.finality_verifier.clone(),
+            self.transaction_checker.clone(),
+            self.clock.clone(),
+            uncommitted_blocks.clone(),
+            warmup,
+            self.cancelled_heights.clone(),
+            self.refused_reorg.clone(),
+        );
+        let gap = usize::try_from(range.end().saturating_sub(*range.start()))
+            .expect("The size of the range can't exceed `usize`")
+            .saturating_add(1);
+        let pipelining = Pipelining::for_gap(params, gap);
+        tracing::debug!("Importing {} heights {:?}", gap, pipelining);
+        let buffer_size = match pipelining {
+            Pipelining::Sequential => 1,
+            Pipelining::Pipelined => params.block_stream_buffer_size,
+        };
+        let clock = self.clock.clone();
+        let throughput = params.record_throughput.then(|| self.throughput.clone());
         let result = block_stream
+            // Stop requesting new batches once the deadline passes.
+            // The batches in flight are still committed.
+            .take_until(deadline_passed(self.clock.clone(), deadline))
             .map(move |stream_block_batch| {
                 let shutdown_guard = shutdown_guard.clone();
                 let shutdown_signal = shutdown_signal.clone();
+                let clock = clock.clone();
+                let throughput = throughput.clone();
+                // Keep the spans of the spawned task under the span of the stream.
                 tokio::spawn(async move {
                     // Hold a shutdown sender for the lifetime of the spawned task
                     let _shutdown_guard = shutdown_guard.clone();
                     let mut shutdown_signal = shutdown_signal.clone();
+                    let started_at = clock.now();
                     tokio::select! {
                     // Stream a batch of blocks
-                    blocks = stream_block_batch => Some(blocks),
+                    blocks = stream_block_batch => {
+                        let timings = ImportTimings {
+                            fetch: clock.now().saturating_duration_since(started_at),
+                            ..blocks.timings
+                        };
+                        if let Some(throughput) = &throughput {
+                            let bytes = blocks
+                                .results
+                                .iter()
+                                .map(transactions_size)
+                                .fold(0usize, usize::saturating_add);
+                            let busy = timings
+                                .header_fetch
+                                .saturating_add(timings.transaction_fetch);
+                            throughput.apply(|work| {
+                                work.fetch.record(blocks.results.len(), bytes, busy)
+                            });
+                        }
+                        Some(blocks.map(|block| (block, timings)))
+                    },
                     // If a shutdown signal is received during the stream, terminate early and
                     // return an empty response
                     _ = shutdown_signal.while_started() => None
                 }
-                }).map(|task| {
+                }.in_current_span()).map(|task| {
                     task.trace_err("Failed to join the task").ok().flatten()
                 })
             })
-            // Request up to `block_stream_buffer_size` transactions from the network.
-            .buffered(params.block_stream_buffer_size)
+            // Request up to `buffer_size` transactions from the network.
+            .buffered(buffer_size)
             // Continue the stream until the shutdown signal is received.
             .take_until({
                 let mut s = shutdown.clone();
@@ -244,34 +1894,33 @@ where
             .into_scan_err()
             .scan_err()
             .then(|batch| {
+                let uncommitted_blocks = uncommitted_blocks.clone();
+                let reporter = reporter.clone();
                 async move {
                     let Batch {
                         peer,
                         range,
                         results,
+                        ..
                     } = batch;
 
-                    let mut done = vec![];
-                    for sealed_block in results {
-                        let res = execute_and_commit(executor.as_ref(), state, sealed_block).await;
-
-                        match &res {
-                            Ok(_) => {
-                                done.push(());
-                            },
-                            Err(e) => {
-                                // If this fails, then it means that consensus has approved a block that is invalid.
-                                // This would suggest a more serious issue than a bad peer, e.g. a fork or an out-of-date client.
-                                tracing::error!("Failed to execute and commit block from peer {:?}: {:?}", peer, e);
-                                break;
-                            },
-                        };
-                    }
+                    let size = results
+                        .iter()
+                        .map(|(block, _)| transactions_size(block))
+                        .fold(0usize, usize::saturating_add);
+                    let committed =
+                        self.execute_and_commit_blocks(peer.as_ref(), results).await;
+                    let done = vec![(); committed];
 
+                    uncommitted_blocks.release(&range, size);
                     let batch = Batch::new(peer.clone(), range, done);
 
-                    if !batch.is_err() {
-                        report_peer(p2p, peer, PeerReportReason::SuccessfulBlockImport);
+                    if let Some(peer) = peer.filter(|_| !batch.is_err()) {
+                        reporter.report(
+                            peer,
+                            PeerReportReason::SuccessfulBlockImport,
+                            batch.range.start.into(),
+                        );
                     }
 
                     batch
@@ -293,58 +1942,477 @@ where
         let _ = shutdown_guard_recv.recv().await;
         result
     }
+
+    /// Imports the headers of the `range` without the transactions of their blocks.
+    ///
+    /// The headers checked by the consensus are stored in order. The stream stops
+    /// at the first header that fails the check or can't be stored.
+    /// Returns the number of the stored headers.
+    async fn launch_headers_stream(
+        &self,
+        range: RangeInclusive<u32>,
+        deadline: Option<Instant>,
+        shutdown: &StateWatcher,
+    ) -> usize {
+        let reporter = self.peer_reporter();
+        let params = self.config();
+        let uncommitted_blocks =
+            UncommittedBlocks::new(params.max_uncommitted_blocks, None);
+        get_header_batch_stream(
+            range,
+            &params,
+            reporter.clone(),
+            self.executor.clone(),
+            self.clock.clone(),
+            uncommitted_blocks.clone(),
+            vec![],
+        )
+        .take_until(deadline_passed(self.clock.clone(), deadline))
+        // Continue the stream until the shutdown signal is received.
+        .take_until({
+            let mut s = shutdown.clone();
+            async move {
+                let _ = s.while_started().await;
+                tracing::info!("In progress import stream shutting down");
+            }
+        })
+        .then(|header_batch| {
+            let reporter = reporter.clone();
+            let uncommitted_blocks = uncommitted_blocks.clone();
+            async move {
+                let Batch {
+                    peer,
+                    range,
+                    results,
+                    timings,
+                } = header_batch;
+                let checked_headers = results
+                    .into_iter()
+                    .take_while(|header| {
+                        check_sealed_header(header, peer.clone(), &reporter, &self.consensus)
+                            && check_finality(header, self.finality_verifier.as_deref())
+                            && check_da_lookahead(
+                                header,
+                                peer.clone(),
+                                &reporter,
+                                &self.consensus,
+                                params.max_da_lookahead,
+                            )
+                    })
+                    .collect::<Vec<_>>();
+                if params.require_da {
+                    if let Some(header) = checked_headers.last() {
+                        await_da_height(header, &self.consensus).await;
+                    }
+                }
+                let mut stored = 0usize;
+                for header in checked_headers {
+                    let height = *header.entity.height();
+                    let block_id = self.accumulator.as_ref().map(|_| header.entity.id());
+                    if let Err(e) = self.executor.store_sealed_header(header).await {
+                        tracing::error!(
+                            "Failed to store the header {} from peer {:?}: {:?}",
+                            height,
+                            peer,
+                            e
+                        );
+                        break
+                    }
+                    self.mark_committed(height, block_id, None, timings);
+                    stored = stored.saturating_add(1);
+                }
+                uncommitted_blocks.release(&range, 0);
+                Batch::new(peer, range, vec![(); stored])
+            }
+            .instrument(tracing::debug_span!("store_headers"))
+            .in_current_span()
+        })
+        // Continue the stream unless an error occurs.
+        .into_scan_err()
+        .scan_err()
+        // Count the number of stored headers.
+        .fold(0usize, |count, batch| async move {
+            count
+                .checked_add(batch.results.len())
+                .expect("It is impossible to fetch so much data to overflow `usize`")
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip_all, fields(peer = ?peer))]
+    /// Executes and commits the `blocks` one by one. Returns the number of
+    /// the committed blocks, it stops at the first block that fails.
+    async fn execute_and_commit_blocks(
+        &self,
+        peer: Option<&PeerId>,
+        blocks: Vec<(SealedBlock, ImportTimings)>,
+    ) -> usize {
+        let mut committed = 0usize;
+        let blocks = blocks
+            .into_iter()
+            .take_while(|(block, _)| !self.is_cancelled(*block.entity.header().height()));
+        for (block, timings) in blocks {
+            self.await_disk_space().await;
+            if let Err(e) = self.execute_and_commit(peer, block, timings).await {
+                // If this fails, then it means that consensus has approved a block that is invalid.
+                // This would suggest a more serious issue than a bad peer, e.g. a fork or an out-of-date client.
+                tracing::error!(
+                    "Failed to execute and commit block from peer {:?}: {:?}",
+                    peer,
+                    e
+                );
+                break
+            }
+            committed = committed.saturating_add(1);
+        }
+        committed
+    }
+
+    /// Waits until the free disk space reported by the `disk_monitor` is at least
+    /// the `min_free_disk_space`. The failure to get the free space doesn't pause
+    /// the import.
+    async fn await_disk_space(&self) {
+        let Some(disk_monitor) = &self.disk_monitor else {
+            return
+        };
+        let mut paused = false;
+        loop {
+            let config = self.config();
+            let Some(min_free_space) = config.min_free_disk_space else {
+                break
+            };
+            let free_space = match disk_monitor.free_disk_space() {
+                Ok(free_space) => free_space,
+                Err(err) => {
+                    tracing::warn!("Failed to get the free disk space: {:?}", err);
+                    break
+                }
+            };
+            if free_space >= min_free_space {
+                break
+            }
+            if !paused {
+                paused = true;
+                tracing::warn!(
+                    "Pausing the import, the free disk space {} is below {}",
+                    free_space,
+                    min_free_space
+                );
+                let _ = self.disk_space_events.send(DiskSpaceEvent::Paused {
+                    free_space,
+                    min_free_space,
+                });
+            }
+            self.clock.sleep(config.executor_poll_interval).await;
+        }
+        if paused {
+            tracing::info!("Resuming the import, the free disk space recovered");
+            let _ = self.disk_space_events.send(DiskSpaceEvent::Resumed);
+        }
+    }
+
+    /// Records the commit of the block at the `height`.
+    ///
+    /// Must be called only once the executor reports the durable commit of the block,
+    /// so the `State` never gets ahead of the database. A block that was executed
+    /// but failed to commit is not recorded.
+    fn mark_committed(
+        &self,
+        height: BlockHeight,
+        block_id: Option<BlockId>,
+        block: Option<SealedBlock>,
+        timings: ImportTimings,
+    ) {
+        observe_stages(&timings);
+        if self.config().record_import_timings {
+            let _ = self
+                .executor
+                .record_import_timings(height, timings)
+                .trace_err("Failed to record the import timings");
+        }
+        self.state.apply(|s| s.commit(*height));
+        let caught_up = self
+            .range_to_process()
+            .map_or(true, |range| range.is_empty());
+        self.committed_height.send_replace(Some(*height));
+        if let (Some(accumulator), Some(block_id)) = (&self.accumulator, block_id) {
+            let accumulated = self.accumulated_height.apply(|accumulated| {
+                let is_new = accumulated.map_or(true, |accumulated| *height > accumulated);
+                if is_new {
+                    *accumulated = Some(*height);
+                }
+                is_new
+            });
+            if accumulated {
+                accumulator(height, block_id);
+            }
+        }
+        if let (Some(on_committed), Some(block)) = (&self.on_committed, block) {
+            on_committed(&block);
+        }
+        if caught_up && !self.caught_up.swap(true, Ordering::Relaxed) {
+            if let Some(on_caught_up) = &self.on_caught_up {
+                on_caught_up();
+            }
+        }
+    }
+
+    fn peer_reporter(&self) -> PeerReporter<P> {
+        PeerReporter {
+            p2p: self.p2p.clone(),
+            events: self.peer_reports.clone(),
+            bandwidth: self.peer_bandwidth(),
+            selector: self.peer_selector.clone(),
+            strikes: PeerStrikeCounter {
+                strikes: self.peer_strikes.clone(),
+                policy: self.config().peer_strikes,
+                clock: self.clock.clone(),
+            },
+            missing_transactions: MissingTransactions {
+                misses: self.missing_transactions.clone(),
+                threshold: self.config().missing_transactions_threshold,
+            },
+            inflight: self.inflight.clone(),
+        }
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            height = **block.entity.header().height(),
+            id = %block.entity.header().consensus().generated.application_hash,
+            peer = ?peer,
+        ),
+        err
+    )]
+    async fn execute_and_commit(
+        &self,
+        peer: Option<&PeerId>,
+        block: SealedBlock,
+        timings: ImportTimings,
+    ) -> anyhow::Result<()> {
+        // Execute and commit the block.
+        let height = *block.entity.header().height();
+        let size = transactions_size(&block);
+        let block_id = self.accumulator.as_ref().map(|_| block.entity.id());
+        let started_at = self.clock.now();
+        // The block is consumed by the executor, keep a copy only if someone needs it.
+        let committed_block = self.on_committed.as_ref().map(|_| block.clone());
+        let _inflight = InflightGuard::start(&self.inflight.executions);
+        let execution = self.executor.execute_and_commit(block);
+        let r = match self.config().execution_timeout {
+            // The block importer commits the block without yielding after the last
+            // await point, so the timeout drops the execution either before the commit
+            // or not at all. The execution already running on the thread pool is not
+            // interrupted, but its result is discarded.
+            Some(timeout) => clock::timeout(self.clock.as_ref(), timeout, execution)
+                .await
+                .unwrap_or_else(|| {
+                    Err(anyhow::anyhow!(
+                        "Execution of the block timed out after {:?}",
+                        timeout
+                    ))
+                }),
+            None => execution.await,
+        };
+
+        // If the block executed successfully, mark it as committed.
+        if r.is_ok() {
+            let timings = ImportTimings {
+                execute: self.clock.now().saturating_duration_since(started_at),
+                ..timings
+            };
+            self.record_execution(size, timings.execute);
+            self.mark_committed(height, block_id, committed_block, timings);
+        } else {
+            tracing::error!(
+                "Execution of height {} from peer {:?} failed: {:?}",
+                *height,
+                peer,
+                r
+            );
+        }
+        r
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn get_block_stream<
     P: PeerToPeerPort + Send + Sync + 'static,
+    E: BlockImporterPort + Send + Sync + 'static,
     C: ConsensusPort + Send + Sync + 'static,
 >(
     range: RangeInclusive<u32>,
     params: &Config,
-    p2p: Arc<P>,
+    reporter: PeerReporter<P>,
+    executor: Arc<E>,
     consensus: Arc<C>,
+    finality_verifier: Option<Arc<dyn FinalityVerifier>>,
+    transaction_checker: Option<Arc<dyn TransactionChecker>>,
+    clock: Arc<dyn Clock>,
+    uncommitted_blocks: UncommittedBlocks,
+    warmup: Vec<SealedHeaderBatch>,
+    cancelled_heights: CancelledHeights,
+    refused_reorg: SharedMutex<Option<ReorgTooDeep>>,
 ) -> impl Stream<Item = impl Future<Output = SealedBlockBatch>> + '_ {
-    let header_stream = get_header_batch_stream(range.clone(), params, p2p.clone());
+    let start = *range.start();
+    let max_reorg_depth = params.max_reorg_depth;
+    let require_da = params.require_da;
+    let retry_policy = params.retry_policy;
+    let max_da_lookahead = params.max_da_lookahead;
+    let local_transactions_first = params.local_transactions_first;
+    let header_stream = get_header_batch_stream(
+        range.clone(),
+        params,
+        reporter.clone(),
+        executor.clone(),
+        clock.clone(),
+        uncommitted_blocks.clone(),
+        warmup,
+    );
     header_stream
+        .then({
+            let reporter = reporter.clone();
+            let executor = executor.clone();
+            move |header_batch: SealedHeaderBatch| {
+                let reporter = reporter.clone();
+                let executor = executor.clone();
+                let refused_reorg = refused_reorg.clone();
+                async move {
+                    let Some(max_reorg_depth) = max_reorg_depth else {
+                        return header_batch
+                    };
+                    match check_reorg_depth(
+                        header_batch,
+                        start,
+                        max_reorg_depth,
+                        &reporter.p2p,
+                        executor.as_ref(),
+                    )
+                    .await
+                    {
+                        Ok(header_batch) => header_batch,
+                        Err((header_batch, refused)) => {
+                            refused_reorg.apply(|slot| *slot = Some(refused));
+                            Batch::new(header_batch.peer, header_batch.range, vec![])
+                        }
+                    }
+                }
+            }
+        })
         .map({
             let consensus = consensus.clone();
-            let p2p = p2p.clone();
+            let reporter = reporter.clone();
+            let clock = clock.clone();
             move |header_batch: SealedHeaderBatch| {
                 let Batch {
                     peer,
                     range,
                     results,
+                    timings,
                 } = header_batch;
+                let started_at = clock.now();
                 let checked_headers = results
                     .into_iter()
                     .take_while(|header| {
-                        check_sealed_header(header, peer.clone(), &p2p, &consensus)
+                        check_sealed_header(header, peer.clone(), &reporter, &consensus)
+                            && check_finality(header, finality_verifier.as_deref())
+                            && check_da_lookahead(
+                                header,
+                                peer.clone(),
+                                &reporter,
+                                &consensus,
+                                max_da_lookahead,
+                            )
                     })
                     .collect::<Vec<_>>();
-                Batch::new(peer, range, checked_headers)
+                let timings = ImportTimings {
+                    consensus_check: clock.now().saturating_duration_since(started_at),
+                    ..timings
+                };
+                Batch::new(peer, range, checked_headers).with_timings(timings)
             }
         })
         .map(move |headers| {
             let consensus = consensus.clone();
-            let p2p = p2p.clone();
+            let executor = executor.clone();
+            let reporter = reporter.clone();
+            let uncommitted_blocks = uncommitted_blocks.clone();
+            let clock = clock.clone();
+            let cancelled_heights = cancelled_heights.clone();
+            let transaction_checker = transaction_checker.clone();
             async move {
                 let Batch {
                     peer,
                     range,
                     results,
+                    mut timings,
                 } = headers;
-                if results.is_empty() {
-                    SealedBlockBatch::new(peer, range, vec![])
-                } else {
-                    await_da_height(
-                        results
-                            .last()
-                            .expect("We checked headers are not empty above"),
-                        &consensus,
-                    )
-                    .await;
-                    let headers = SealedHeaderBatch::new(peer, range, results);
-                    get_blocks(&p2p, headers).await
+                let mut cancelled = cancelled_heights.subscribe();
+                let cancellation = {
+                    let range = range.clone();
+                    async move {
+                        cancelled
+                            .wait_for(|heights| heights.range(range.clone()).next().is_some())
+                            .await
+                            .is_ok()
+                    }
+                };
+                let (cancelled_peer, cancelled_range) = (peer.clone(), range.clone());
+                let download = async {
+                    if results.is_empty() {
+                        uncommitted_blocks.fetched(0);
+                        SealedBlockBatch::new(peer, range, vec![])
+                    } else {
+                        if require_da {
+                            let started_at = clock.now();
+                            await_da_height(
+                                results
+                                    .last()
+                                    .expect("We checked headers are not empty above"),
+                                &consensus,
+                            )
+                            .await;
+                            timings.da_wait =
+                                clock.now().saturating_duration_since(started_at);
+                        }
+                        let started_at = clock.now();
+                        let headers = SealedHeaderBatch::new(peer, range, results);
+                        let local_blocks = local_transactions_first
+                            .then(|| get_local_blocks(&headers, executor.as_ref()))
+                            .flatten();
+                        let blocks = match local_blocks {
+                            Some(blocks) => blocks,
+                            None => {
+                                get_blocks(
+                                    &reporter,
+                                    headers,
+                                    retry_policy,
+                                    clock.as_ref(),
+                                    transaction_checker.as_deref(),
+                                )
+                                .await
+                            }
+                        };
+                        timings.transaction_fetch =
+                            clock.now().saturating_duration_since(started_at);
+                        let blocks = blocks.with_timings(timings);
+                        let size = blocks
+                            .results
+                            .iter()
+                            .map(transactions_size)
+                            .fold(0usize, usize::saturating_add);
+                        uncommitted_blocks.fetched(size);
+                        blocks
+                    }
+                };
+                tokio::select! {
+                    blocks = download => blocks,
+                    true = cancellation => {
+                        tracing::debug!("The download of {:?} is cancelled", cancelled_range);
+                        uncommitted_blocks.fetched(0);
+                        SealedBlockBatch::new(cancelled_peer, cancelled_range, vec![])
+                    }
                 }
             }
             .instrument(tracing::debug_span!("consensus_and_transactions"))
@@ -352,21 +2420,342 @@ fn get_block_stream<
         })
 }
 
-fn get_header_batch_stream<P: PeerToPeerPort + Send + Sync + 'static>(
+/// Refuses the first `batch` of the stream importing the range from the `start` if
+/// the chain of its peer forks more than the `max_reorg_depth` heights below
+/// the committed tip, the height right below the `start`. The blocks are linked by
+/// their ids, so the chains are the same up to the fork point, and it is enough to
+/// compare the header of the peer at the deepest allowed fork point with the local one.
+///
+/// The ranges always start right above the committed tip, so the depth of the fork
+/// point of the serving peer is limited instead of the depth of the start of the range.
+/// The batches served from the local storage are not checked, and the check is skipped
+/// if the header of the peer or the local header is not available.
+async fn check_reorg_depth<P, E>(
+    batch: SealedHeaderBatch,
+    start: u32,
+    max_reorg_depth: u32,
+    p2p: &Arc<P>,
+    executor: &E,
+) -> Result<SealedHeaderBatch, (SealedHeaderBatch, ReorgTooDeep)>
+where
+    P: PeerToPeerPort + Send + Sync + 'static,
+    E: BlockImporterPort + Send + Sync + 'static,
+{
+    let Some(peer) = batch.peer.clone().filter(|_| batch.range.start == start) else {
+        return Ok(batch)
+    };
+    let Some((tip, height)) = start.checked_sub(1).and_then(|tip| {
+        tip.checked_sub(max_reorg_depth)
+            .map(|height| (tip, height))
+    }) else {
+        return Ok(batch)
+    };
+    let local = executor
+        .local_sealed_header(height.into())
+        .trace_err("Failed to get the local header at the fork point")
+        .ok()
+        .flatten();
+    let Some(local) = local else {
+        return Ok(batch)
+    };
+    let remote = get_sealed_block_headers_from_peer(
+        peer.clone(),
+        height..height.saturating_add(1),
+        p2p,
+    )
+    .await
+    .ok()
+    .and_then(|headers| headers.data.into_iter().next());
+    let Some(remote) = remote else {
+        tracing::debug!(
+            "Skipping the reorg check, the peer {:?} has no header {}",
+            peer,
+            height
+        );
+        return Ok(batch)
+    };
+    if remote.entity.id() != local.entity.id() {
+        tracing::warn!(
+            "The chain of the peer {:?} diverges from the local one at {}",
+            peer,
+            height
+        );
+        return Err((
+            batch,
+            ReorgTooDeep {
+                diverged_at: height,
+                tip,
+                max_reorg_depth,
+            },
+        ))
+    }
+    Ok(batch)
+}
+
+/// The `warmup` batches are served before the rest of the `range` is fetched.
+fn get_header_batch_stream<P, E>(
     range: RangeInclusive<u32>,
     params: &Config,
-    p2p: Arc<P>,
-) -> impl Stream<Item = SealedHeaderBatch> {
-    let Config {
-        header_batch_size, ..
-    } = params;
-    let ranges = range_chunks(range, *header_batch_size);
-    futures::stream::iter(ranges).then(move |range| {
-        let p2p = p2p.clone();
-        async move { get_headers_batch(range, &p2p).await }
+    reporter: PeerReporter<P>,
+    executor: Arc<E>,
+    clock: Arc<dyn Clock>,
+    uncommitted_blocks: UncommittedBlocks,
+    warmup: Vec<SealedHeaderBatch>,
+) -> impl Stream<Item = SealedHeaderBatch>
+where
+    P: PeerToPeerPort + Send + Sync + 'static,
+    E: BlockImporterPort + Send + Sync + 'static,
+{
+    let params = *params;
+    let ranges = range_chunks(range, params.header_batch_size);
+    let warmup = warmup
+        .into_iter()
+        .map(Some)
+        .chain(std::iter::repeat_with(|| None));
+    let stride = match params.fetch_order {
+        FetchOrder::Sequential => {
+            return futures::stream::iter(ranges.zip(warmup))
+                .then(move |(range, warmup)| {
+                    let reporter = reporter.clone();
+                    let executor = executor.clone();
+                    let clock = clock.clone();
+                    let uncommitted_blocks = uncommitted_blocks.clone();
+                    async move {
+                        uncommitted_blocks.reserve(&range).await;
+                        await_executor_capacity(
+                            &executor,
+                            clock.as_ref(),
+                            params.executor_poll_interval,
+                        )
+                        .await;
+                        match warmup {
+                            Some(batch) => batch,
+                            None => {
+                                get_headers(
+                                    range,
+                                    &params,
+                                    &reporter,
+                                    executor.as_ref(),
+                                    clock.as_ref(),
+                                )
+                                .await
+                            }
+                        }
+                    }
+                })
+                .left_stream()
+        }
+        FetchOrder::Strided { stride } => stride.max(1),
+    };
+
+    // The batches of a window are reserved only after all of them are fetched, so
+    // the window bounds the number of the headers fetched ahead.
+    let window = params.max_uncommitted_blocks.map_or(usize::MAX, |max| {
+        max.checked_div(params.header_batch_size)
+            .unwrap_or_default()
+            .max(1)
+    });
+    let mut batches = ranges.zip(warmup).map(Some).collect::<Vec<_>>();
+    let batches = strided_order(batches.len(), window, stride)
+        .filter_map(|index| Some((index, batches.get_mut(index)?.take()?)))
+        .collect::<Vec<_>>();
+    futures::stream::iter(batches)
+        .then({
+            let executor = executor.clone();
+            let clock = clock.clone();
+            move |(index, (range, warmup))| {
+                let reporter = reporter.clone();
+                let executor = executor.clone();
+                let clock = clock.clone();
+                async move {
+                    let batch = match warmup {
+                        Some(batch) => batch,
+                        None => {
+                            get_headers(
+                                range,
+                                &params,
+                                &reporter,
+                                executor.as_ref(),
+                                clock.as_ref(),
+                            )
+                            .await
+                        }
+                    };
+                    (index, batch)
+                }
+            }
+        })
+        // Hold the batches fetched ahead until all the preceding ones are fetched.
+        .scan((BTreeMap::new(), 0usize), |(pending, next), (index, batch)| {
+            pending.insert(index, batch);
+            let mut ready = vec![];
+            while let Some(batch) = pending.remove(next) {
+                ready.push(batch);
+                *next = next.saturating_add(1);
+            }
+            futures::future::ready(Some(futures::stream::iter(ready)))
+        })
+        .flatten()
+        .then(move |batch: SealedHeaderBatch| {
+            let executor = executor.clone();
+            let clock = clock.clone();
+            let uncommitted_blocks = uncommitted_blocks.clone();
+            async move {
+                uncommitted_blocks.reserve(&batch.range).await;
+                await_executor_capacity(
+                    &executor,
+                    clock.as_ref(),
+                    params.executor_poll_interval,
+                )
+                .await;
+                batch
+            }
+        })
+        .right_stream()
+}
+
+/// Returns the order of the `len` batches fetched with the `stride`. The batches are
+/// split into the windows of the `window` consecutive batches, and every `stride`-th
+/// batch of a window goes first, then the batches following each of them, and so on.
+fn strided_order(
+    len: usize,
+    window: usize,
+    stride: usize,
+) -> impl Iterator<Item = usize> {
+    (0..len).step_by(window).flat_map(move |start| {
+        let end = start.saturating_add(window).min(len);
+        let offsets_end = start.saturating_add(stride).min(end);
+        (start..offsets_end).flat_map(move |first| (first..end).step_by(stride))
     })
 }
 
+/// Fetches the headers of the batches covering the first `warmup_headers`
+/// heights of the `range`. Stops at the first failed batch.
+async fn get_warmup_batches<P, E>(
+    range: RangeInclusive<u32>,
+    params: &Config,
+    reporter: &PeerReporter<P>,
+    executor: &E,
+    clock: &dyn Clock,
+) -> Vec<SealedHeaderBatch>
+where
+    P: PeerToPeerPort + Send + Sync + 'static,
+    E: BlockImporterPort + Send + Sync + 'static,
+{
+    let mut warmup = Vec::new();
+    let mut heights = 0usize;
+    for range in range_chunks(range, params.header_batch_size) {
+        if heights >= params.warmup_headers {
+            break
+        }
+        heights = heights.saturating_add(range.len());
+        let batch = get_headers(range, params, reporter, executor, clock).await;
+        let failed = batch.is_err();
+        warmup.push(batch);
+        if failed {
+            break
+        }
+    }
+    if !warmup.is_empty() {
+        tracing::debug!("Fetched {} batches of headers to warm up", warmup.len());
+    }
+    warmup
+}
+
+/// Fetches the headers of the `range` from the local storage if the
+/// `local_headers_first` is set and all of them are present, or from the network.
+async fn get_headers<P, E>(
+    range: Range<u32>,
+    params: &Config,
+    reporter: &PeerReporter<P>,
+    executor: &E,
+    clock: &dyn Clock,
+) -> SealedHeaderBatch
+where
+    P: PeerToPeerPort + Send + Sync + 'static,
+    E: BlockImporterPort + Send + Sync + 'static,
+{
+    let started_at = clock.now();
+    let local_headers = params
+        .local_headers_first
+        .then(|| get_local_headers(range.clone(), executor))
+        .flatten();
+    let batch = match local_headers {
+        Some(headers) => {
+            tracing::debug!("Serving the headers of {:?} from the local storage", range);
+            Batch::new(None, range, headers)
+        }
+        None => {
+            get_headers_batch(
+                range,
+                reporter,
+                params.retry_policy,
+                params.chain_config_hash,
+                clock,
+            )
+            .await
+        }
+    };
+    let timings = ImportTimings {
+        header_fetch: clock.now().saturating_duration_since(started_at),
+        ..Default::default()
+    };
+    batch.with_timings(timings)
+}
+
+/// Returns the headers of the `range` from the local storage
+/// if all of them are present.
+fn get_local_headers<E>(range: Range<u32>, executor: &E) -> Option<Vec<SealedBlockHeader>>
+where
+    E: BlockImporterPort + Send + Sync + 'static,
+{
+    range
+        .map(|height| {
+            executor
+                .local_sealed_header(height.into())
+                .trace_err("Failed to get the local header")
+                .ok()
+                .flatten()
+        })
+        .collect()
+}
+
+/// Waits until the executor reports that it can accept more blocks.
+async fn await_executor_capacity<E>(
+    executor: &Arc<E>,
+    clock: &dyn Clock,
+    poll_interval: Duration,
+) where
+    E: BlockImporterPort + Send + Sync + 'static,
+{
+    while !executor.can_accept() {
+        tracing::debug!("The executor is saturated, waiting for {:?}", poll_interval);
+        clock.sleep(poll_interval).await;
+    }
+}
+
+/// Returns the heights of the `range` left after importing the first `count` of them,
+/// or `None` if the whole range was imported.
+///
+/// The heights are accounted in `u64`, so the ranges ending at `u32::MAX` don't overflow.
+fn incomplete_range(
+    range: &RangeInclusive<u32>,
+    count: usize,
+) -> anyhow::Result<Option<RangeInclusive<u32>>> {
+    let first_missing = u64::try_from(count)
+        .ok()
+        .and_then(|count| u64::from(*range.start()).checked_add(count))
+        .ok_or_else(|| {
+            anyhow::anyhow!("The count {count} of imported blocks overflows {range:?}")
+        })?;
+    if first_missing > u64::from(*range.end()) {
+        return Ok(None)
+    }
+    // It is below the end of the range, so it always fits into `u32`.
+    let first_missing = u32::try_from(first_missing)?;
+    Ok(Some(first_missing..=*range.end()))
+}
+
 fn range_chunks(
     range: RangeInclusive<u32>,
     chunk_size: usize,
@@ -385,20 +2774,115 @@ fn check_sealed_header<
     C: ConsensusPort + Send + Sync + 'static,
 >(
     header: &SealedBlockHeader,
-    peer_id: PeerId,
-    p2p: &Arc<P>,
+    peer_id: Option<PeerId>,
+    reporter: &PeerReporter<P>,
     consensus: &Arc<C>,
 ) -> bool {
     let validity = consensus
         .check_sealed_header(header)
         .trace_err("Failed to check consensus on header")
         .unwrap_or(false);
-    if !validity {
-        report_peer(p2p, peer_id.clone(), PeerReportReason::BadBlockHeader);
+    if let Some(peer_id) = peer_id.filter(|_| !validity) {
+        reporter.report(
+            peer_id,
+            PeerReportReason::BadBlockHeader,
+            *header.entity.height(),
+        );
     }
     validity
 }
 
+/// Returns `false` if the `finality_verifier` rejects the external finality
+/// evidence of the `header`.
+fn check_finality(
+    header: &SealedBlockHeader,
+    finality_verifier: Option<&dyn FinalityVerifier>,
+) -> bool {
+    let Some(finality_verifier) = finality_verifier else {
+        return true
+    };
+    let is_final = finality_verifier
+        .verify_finality(header)
+        .trace_err("Failed to verify the finality of the header")
+        .unwrap_or(false);
+    if !is_final {
+        tracing::warn!(
+            "The header {} lacks the valid finality evidence",
+            header.entity.height()
+        );
+    }
+    is_final
+}
+
+/// Returns `false` if the `transaction_checker` rejects any of the `transactions`
+/// of the block at the `height`.
+fn check_transactions(
+    height: BlockHeight,
+    transactions: &[Transaction],
+    transaction_checker: Option<&dyn TransactionChecker>,
+) -> bool {
+    let Some(transaction_checker) = transaction_checker else {
+        return true
+    };
+    let malformed = transactions.iter().enumerate().find_map(|(index, tx)| {
+        transaction_checker
+            .check_transaction(height, tx)
+            .err()
+            .map(|e| (index, e))
+    });
+    if let Some((index, e)) = &malformed {
+        tracing::warn!(
+            "The transaction {} of the block {} is malformed: {:?}",
+            index,
+            height,
+            e
+        );
+    }
+    malformed.is_none()
+}
+
+/// Returns `false` and reports the peer if the `da_height` of the `header` is
+/// further than `max_da_lookahead` ahead of the DA tip of the node, so waiting
+/// for it would likely never end.
+fn check_da_lookahead<
+    P: PeerToPeerPort + Send + Sync + 'static,
+    C: ConsensusPort + Send + Sync + 'static,
+>(
+    header: &SealedBlockHeader,
+    peer_id: Option<PeerId>,
+    reporter: &PeerReporter<P>,
+    consensus: &Arc<C>,
+    max_da_lookahead: Option<u64>,
+) -> bool {
+    let Some(max_da_lookahead) = max_da_lookahead else {
+        return true
+    };
+    let Ok(Some(da_tip)) = consensus
+        .da_tip()
+        .trace_err("Failed to get the DA tip")
+    else {
+        return true
+    };
+    let da_height = *header.entity.da_height;
+    let plausible = da_height <= da_tip.saturating_add(max_da_lookahead);
+    if !plausible {
+        tracing::warn!(
+            "The header {} references the DA height {} far ahead of the DA tip {}",
+            *header.entity.height(),
+            da_height,
+            *da_tip
+        );
+        if let Some(peer_id) = peer_id {
+            reporter.report(
+                peer_id,
+                PeerReportReason::BadBlockHeader,
+                *header.entity.height(),
+            );
+        }
+    }
+    plausible
+}
+
 async fn await_da_height<C: ConsensusPort + Send + Sync + 'static>(
     header: &SealedBlockHeader,
     consensus: &Arc<C>,
@@ -409,6 +2893,29 @@ async fn await_da_height<C: ConsensusPort + Send + Sync + 'static>(
         .trace_err("Failed to wait for DA layer to sync");
 }
 
+/// Observes the duration of each stage of the import of a block in the metrics.
+fn observe_stages(timings: &ImportTimings) {
+    let stages = [
+        ("header_fetch", timings.header_fetch),
+        ("consensus_check", timings.consensus_check),
+        ("da_wait", timings.da_wait),
+        ("transaction_fetch", timings.transaction_fetch),
+        ("execution", timings.execute),
+    ];
+    for (stage, duration) in stages {
+        importer_metrics().sync_stage_observe(stage, duration.as_secs_f64());
+    }
+}
+
+/// Resolves once the `clock` reaches the `deadline` of the import, so the stream
+/// stops launching the new batches. Never resolves without the `deadline`.
+async fn deadline_passed(clock: Arc<dyn Clock>, deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => clock.sleep_until(deadline).await,
+        None => futures::future::pending::<()>().await,
+    }
+}
+
 /// Waits for a notify or shutdown signal.
 /// Returns true if the notify signal was received.
 async fn wait_for_notify_or_shutdown(
@@ -430,7 +2937,7 @@ async fn wait_for_notify_or_shutdown(
 async fn get_sealed_block_headers<P>(
     range: Range<u32>,
     p2p: &Arc<P>,
-) -> SourcePeer<Vec<SealedBlockHeader>>
+) -> Result<SourcePeer<Vec<SealedBlockHeader>>, SourcePeerError>
 where
     P: PeerToPeerPort + Send + Sync + 'static,
 {
@@ -439,18 +2946,83 @@ where
         range.start,
         range.end
     );
-    p2p.get_sealed_block_headers(range)
+    let SourcePeer { peer_id, data } = p2p
+        .get_sealed_block_headers(range)
         .await
         .trace_err("Failed to get headers")
-        .unwrap_or_default()
-        .map(|inner| inner.unwrap_or_default())
+        .map_err(SourcePeerError::unknown)?;
+    match data {
+        Some(headers) => Ok(peer_id.bind(headers)),
+        None => Err(SourcePeerError::new(
+            peer_id,
+            anyhow::anyhow!("The peer didn't provide headers"),
+        )),
+    }
+}
+
+async fn get_sealed_block_headers_from_peer<P>(
+    peer_id: PeerId,
+    range: Range<u32>,
+    p2p: &Arc<P>,
+) -> Result<SourcePeer<Vec<SealedBlockHeader>>, SourcePeerError>
+where
+    P: PeerToPeerPort + Send + Sync + 'static,
+{
+    tracing::debug!(
+        "getting header range from {} to {} inclusive from the peer {:?}",
+        range.start,
+        range.end,
+        peer_id
+    );
+    let res = p2p
+        .get_sealed_block_headers_from_peer(peer_id.clone().bind(range))
+        .await
+        .trace_err("Failed to get headers");
+    match res {
+        Ok(Some(headers)) => Ok(peer_id.bind(headers)),
+        Ok(None) => Err(SourcePeerError::new(
+            peer_id,
+            anyhow::anyhow!("The peer didn't provide headers"),
+        )),
+        Err(error) => Err(SourcePeerError::new(peer_id, error)),
+    }
+}
+
+/// Returns the peer chosen by the selector of the `reporter` to request the
+/// headers of the `range` from. `None` if there is no selector or no candidate.
+async fn choose_peer<P>(
+    range: &Range<u32>,
+    excluded_peers: &HashSet<PeerId>,
+    reporter: &PeerReporter<P>,
+) -> Option<PeerId>
+where
+    P: PeerToPeerPort + Send + Sync + 'static,
+{
+    let selector = reporter.selector.as_ref()?;
+    let height = BlockHeight::from(range.end.saturating_sub(1));
+    let candidates = reporter
+        .p2p
+        .peers_with_height(height)
+        .await
+        .trace_err("Failed to get the candidate peers")
+        .ok()?
+        .into_iter()
+        .filter(|peer_id| {
+            !excluded_peers.contains(peer_id)
+                && !reporter.bandwidth.is_over_budget(peer_id)
+        })
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        return None
+    }
+    Some(selector.choose(&candidates, height))
 }
 
 async fn get_transactions<P>(
     peer_id: PeerId,
     range: Range<u32>,
     p2p: &Arc<P>,
-) -> Option<Vec<Transactions>>
+) -> Result<Vec<Transactions>, SourcePeerError>
 where
     P: PeerToPeerPort + Send + Sync + 'static,
 {
@@ -460,59 +3032,351 @@ where
         .await
         .trace_err("Failed to get transactions");
     match res {
-        Ok(Some(transactions)) => Some(transactions),
-        _ => {
-            report_peer(p2p, peer_id.clone(), PeerReportReason::MissingTransactions);
-            None
+        Ok(Some(transactions)) => Ok(transactions),
+        Ok(None) => Err(SourcePeerError::new(
+            peer_id,
+            anyhow::anyhow!("The peer didn't provide transactions"),
+        )),
+        Err(error) => Err(SourcePeerError::new(peer_id, error)),
+    }
+}
+
+/// Fetches the transactions of the blocks of the `range` with the `headers`.
+///
+/// If the response covers only the first blocks of the `range`, e.g. because it was
+/// truncated, the transactions of the rest of the `range` are requested from the same
+/// peer, until the blocks of all `headers` are received or the peer stops making progress.
+/// The last block of a response with fewer transactions than its header was cut
+/// by the truncation, so it is requested again with the rest of the `range`.
+async fn get_transactions_resuming<P>(
+    peer_id: PeerId,
+    range: Range<u32>,
+    headers: &[SealedBlockHeader],
+    p2p: &Arc<P>,
+) -> Result<Vec<Transactions>, SourcePeerError>
+where
+    P: PeerToPeerPort + Send + Sync + 'static,
+{
+    let mut transactions: Vec<Transactions> = vec![];
+    loop {
+        let received = u32::try_from(transactions.len()).unwrap_or(u32::MAX);
+        let start = range.start.saturating_add(received);
+        if start >= range.end || transactions.len() >= headers.len() {
+            break
+        }
+        let mut response =
+            match get_transactions(peer_id.clone(), start..range.end, p2p).await {
+                Ok(response) => response,
+                Err(error) if transactions.is_empty() => return Err(error),
+                Err(_) => break,
+            };
+        response.truncate(range.end.saturating_sub(start) as usize);
+        if response.is_empty() {
+            break
+        }
+        if start > range.start {
+            tracing::debug!(
+                "Resumed the transactions of {:?} from the peer {:?}",
+                start..range.end,
+                peer_id
+            );
+        }
+        let last = transactions.len().saturating_add(response.len()).saturating_sub(1);
+        let truncated_last = headers
+            .get(last)
+            .zip(response.last())
+            .map_or(false, |(header, last)| {
+                (last.0.len() as u64) < header.entity.application().transactions_count
+            });
+        if truncated_last && response.len() > 1 {
+            // The block cut by the truncation is requested again with the rest.
+            response.pop();
+            transactions.extend(response);
+            continue
+        }
+        transactions.extend(response);
+        if truncated_last {
+            // The truncated block alone can't be resumed,
+            // it is rejected by the check against its header.
+            break
         }
     }
+    Ok(transactions)
 }
 
-async fn get_headers_batch<P>(range: Range<u32>, p2p: &Arc<P>) -> SealedHeaderBatch
+/// Fetches the headers for the `range`, retrying according to the `retry_policy`.
+/// The peers that failed or advertise a chain config other than the
+/// `chain_config_hash` are not accepted in the remaining attempts.
+async fn get_headers_batch<P>(
+    range: Range<u32>,
+    reporter: &PeerReporter<P>,
+    retry_policy: RetryPolicy,
+    chain_config_hash: Option<Bytes32>,
+    clock: &dyn Clock,
+) -> SealedHeaderBatch
+where
+    P: PeerToPeerPort + Send + Sync + 'static,
+{
+    let mut excluded_peers = HashSet::new();
+    let mut failed_peer = None;
+    for attempt in 0..retry_policy.max_attempts.max(1) {
+        if attempt > 0 {
+            clock
+                .sleep(retry_policy.delay(attempt.saturating_sub(1)))
+                .await;
+        }
+        let headers = {
+            let _inflight = InflightGuard::start(&reporter.inflight.header_fetches);
+            match choose_peer(&range, &excluded_peers, reporter).await {
+                Some(peer_id) => {
+                    get_sealed_block_headers_from_peer(
+                        peer_id,
+                        range.clone(),
+                        &reporter.p2p,
+                    )
+                    .await
+                }
+                None => get_sealed_block_headers(range.clone(), &reporter.p2p).await,
+            }
+        };
+        match headers {
+            Ok(SourcePeer { peer_id, .. }) if excluded_peers.contains(&peer_id) => {
+                tracing::debug!("Ignoring headers from the excluded peer {:?}", peer_id);
+            }
+            Ok(SourcePeer { peer_id, .. })
+                if reporter.bandwidth.is_over_budget(&peer_id) =>
+            {
+                tracing::debug!(
+                    "Skipping the headers from the peer {:?} over its bandwidth budget",
+                    peer_id
+                );
+                excluded_peers.insert(peer_id);
+            }
+            Ok(sourced_headers) => {
+                let peer_id = sourced_headers.peer_id.clone();
+                reporter
+                    .bandwidth
+                    .record(&peer_id, headers_size(&sourced_headers.data));
+                if is_compatible(&peer_id, chain_config_hash, &reporter.p2p).await {
+                    return check_headers_batch(range, sourced_headers, reporter)
+                }
+                tracing::warn!(
+                    "Refusing the headers from the peer {:?} with an incompatible chain config",
+                    peer_id
+                );
+                reporter.report(
+                    peer_id.clone(),
+                    PeerReportReason::IncompatibleChainConfig,
+                    range.start.into(),
+                );
+                excluded_peers.insert(peer_id.clone());
+                failed_peer = Some(peer_id);
+            }
+            Err(SourcePeerError { peer_id, error }) => {
+                tracing::debug!("Failed to get headers: {}", error);
+                if let Some(peer_id) = peer_id {
+                    reporter.report(
+                        peer_id.clone(),
+                        PeerReportReason::MissingBlockHeaders,
+                        range.start.into(),
+                    );
+                    excluded_peers.insert(peer_id.clone());
+                    failed_peer = Some(peer_id);
+                }
+            }
+        }
+    }
+    Batch::new(failed_peer, range, vec![])
+}
+
+/// Returns `false` if the `peer_id` advertises a chain config other than
+/// the `chain_config_hash`.
+async fn is_compatible<P>(
+    peer_id: &PeerId,
+    chain_config_hash: Option<Bytes32>,
+    p2p: &Arc<P>,
+) -> bool
+where
+    P: PeerToPeerPort + Send + Sync + 'static,
+{
+    let Some(expected) = chain_config_hash else {
+        return true
+    };
+    match p2p
+        .chain_config_hash(peer_id.clone())
+        .await
+        .trace_err("Failed to get the chain config hash of the peer")
+    {
+        Ok(Some(advertised)) => advertised == expected,
+        Ok(None) | Err(_) => true,
+    }
+}
+
+fn check_headers_batch<P>(
+    range: Range<u32>,
+    sourced_headers: SourcePeer<Vec<SealedBlockHeader>>,
+    reporter: &PeerReporter<P>,
+) -> SealedHeaderBatch
 where
     P: PeerToPeerPort + Send + Sync + 'static,
 {
-    tracing::debug!(
-        "getting header range from {} to {} inclusive",
-        range.start,
-        range.end
-    );
-    let sourced_headers = get_sealed_block_headers(range.clone(), p2p).await;
     let SourcePeer {
         peer_id,
         data: headers,
     } = sourced_headers;
-    let heights = range.clone().map(BlockHeight::from);
-    let headers = headers
-        .into_iter()
-        .zip(heights)
-        .take_while(move |(header, expected_height)| {
-            let height = header.entity.height();
-            height == expected_height
-        })
-        .map(|(header, _)| header)
-        .collect::<Vec<_>>();
-    if headers.len() != range.len() {
-        report_peer(p2p, peer_id.clone(), PeerReportReason::MissingBlockHeaders);
+    let mut checked: Vec<SealedBlockHeader> = Vec::with_capacity(headers.len());
+    for (header, expected_height) in headers.into_iter().zip(range.clone()) {
+        if *header.entity.height() != BlockHeight::from(expected_height) {
+            break
+        }
+        if let Err(err) = check_header_structure(&header, checked.last()) {
+            tracing::warn!(
+                "Skipping the malformed header at the height {} from the peer {:?}: {}",
+                expected_height,
+                peer_id,
+                err
+            );
+            reporter.report(
+                peer_id.clone(),
+                PeerReportReason::BadBlockHeader,
+                expected_height.into(),
+            );
+            return Batch::new(Some(peer_id), range, checked)
+        }
+        checked.push(header);
+    }
+    if checked.len() != range.len() {
+        let missing = u32::try_from(checked.len())
+            .expect("The number of headers can't exceed the size of the range");
+        reporter.report(
+            peer_id.clone(),
+            PeerReportReason::MissingBlockHeaders,
+            range.start.saturating_add(missing).into(),
+        );
+    }
+    Batch::new(Some(peer_id), range, checked)
+}
+
+/// Checks the fields of the fetched `header` that the consensus rules and the
+/// executor rely on, so a header that deserialized into nonsense is rejected
+/// before it goes further down the pipeline.
+fn check_header_structure(
+    header: &SealedBlockHeader,
+    previous: Option<&SealedBlockHeader>,
+) -> anyhow::Result<()> {
+    let header = &header.entity;
+    if *header.application_hash() == Bytes32::zeroed() {
+        return Err(anyhow::anyhow!("The application hash is missing"))
+    }
+    // The merkle root of any set of transactions, even an empty one, is non-zero.
+    if header.application().transactions_root == Bytes32::zeroed() {
+        return Err(anyhow::anyhow!("The transactions root is missing"))
+    }
+    if let Some(previous) = previous {
+        if header.time() < previous.entity.time() {
+            return Err(anyhow::anyhow!(
+                "The time {:?} is before the time {:?} of the previous block",
+                header.time(),
+                previous.entity.time()
+            ))
+        }
     }
-    Batch::new(peer_id, range, headers)
+    Ok(())
+}
+
+/// The size in bytes of the `headers` as they are encoded on the wire.
+fn headers_size(headers: &[SealedBlockHeader]) -> usize {
+    headers
+        .iter()
+        .map(|header| postcard::experimental::serialized_size(header).unwrap_or(0))
+        .fold(0usize, usize::saturating_add)
+}
+
+/// The size in bytes of the transactions of the block.
+fn transactions_size(block: &SealedBlock) -> usize {
+    block
+        .entity
+        .transactions()
+        .iter()
+        .map(|tx| tx.size())
+        .fold(0usize, usize::saturating_add)
 }
 
-fn report_peer<P>(p2p: &Arc<P>, peer_id: PeerId, reason: PeerReportReason)
+/// Reconstructs the blocks of the `headers` from the transactions in the local storage.
+/// Returns `None` if the transactions of some block are missing or don't match its header.
+fn get_local_blocks<E>(headers: &SealedHeaderBatch, executor: &E) -> Option<SealedBlockBatch>
 where
-    P: PeerToPeerPort + Send + Sync + 'static,
+    E: BlockImporterPort,
 {
-    tracing::info!("Reporting peer for {:?}", reason);
+    let blocks = headers
+        .results
+        .iter()
+        .map(|sealed_header| {
+            let transactions = executor
+                .local_transactions(*sealed_header.entity.height())
+                .trace_err("Failed to get the local transactions")
+                .ok()
+                .flatten()?;
+            let block =
+                Block::try_from_executed(sealed_header.entity.clone(), transactions.0)?;
+            Some(SealedBlock {
+                entity: block,
+                consensus: sealed_header.consensus.clone(),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(Batch::new(
+        headers.peer.clone(),
+        headers.range.clone(),
+        blocks,
+    ))
+}
 
-    // Failure to report a peer is a non-fatal error; ignore the error
-    let _ = p2p
-        .report_peer(peer_id.clone(), reason)
-        .trace_err(&format!("Failed to report peer {:?}", peer_id));
+/// Returns the peer to request the transactions of the `range` from. It is the `peer`
+/// of the headers, unless it is deprioritized for the missing transactions and
+/// another connected peer has the `range`. The headers served from the local storage
+/// have no `peer`, so any connected peer with the `range` is used, if there is one.
+async fn transactions_peer<P>(
+    peer: Option<&PeerId>,
+    range: &Range<u32>,
+    reporter: &PeerReporter<P>,
+) -> Option<PeerId>
+where
+    P: PeerToPeerPort + Send + Sync + 'static,
+{
+    if let Some(peer) =
+        peer.filter(|peer| !reporter.missing_transactions.is_deprioritized(peer))
+    {
+        return Some(peer.clone())
+    }
+    let height = BlockHeight::from(range.end.saturating_sub(1));
+    let candidates = reporter
+        .p2p
+        .peers_with_height(height)
+        .await
+        .trace_err("Failed to get the candidate peers")
+        .unwrap_or_default();
+    candidates
+        .into_iter()
+        .find(|candidate| {
+            Some(candidate) != peer
+                && !reporter.missing_transactions.is_deprioritized(candidate)
+        })
+        .or_else(|| {
+            tracing::debug!("No other peer has the transactions of {:?}", range);
+            peer.cloned()
+        })
 }
 
 /// Get blocks correlating to the headers from a specific peer
-#[tracing::instrument(skip(p2p, headers))]
-async fn get_blocks<P>(p2p: &Arc<P>, headers: SealedHeaderBatch) -> SealedBlockBatch
+#[tracing::instrument(skip(reporter, headers, clock, transaction_checker))]
+async fn get_blocks<P>(
+    reporter: &PeerReporter<P>,
+    headers: SealedHeaderBatch,
+    retry_policy: RetryPolicy,
+    clock: &dyn Clock,
+    transaction_checker: Option<&dyn TransactionChecker>,
+) -> SealedBlockBatch
 where
     P: PeerToPeerPort + Send + Sync + 'static,
 {
@@ -520,11 +3384,56 @@ where
         results: headers,
         peer,
         range,
+        ..
     } = headers;
-    let Some(transaction_data) = get_transactions(peer.clone(), range.clone(), p2p).await
-    else {
+    let Some(source) = transactions_peer(peer.as_ref(), &range, reporter).await else {
+        tracing::debug!("No peer to request the transactions of {:?}", range);
         return Batch::new(peer, range, vec![])
     };
+    let mut transaction_data = None;
+    for attempt in 0..retry_policy.max_attempts.max(1) {
+        if attempt > 0 {
+            clock
+                .sleep(retry_policy.delay(attempt.saturating_sub(1)))
+                .await;
+        }
+        let transactions = {
+            let _inflight = InflightGuard::start(&reporter.inflight.transaction_fetches);
+            get_transactions_resuming(
+                source.clone(),
+                range.clone(),
+                &headers,
+                &reporter.p2p,
+            )
+            .await
+        };
+        match transactions {
+            Ok(data) => {
+                reporter.missing_transactions.record_delivery(&source);
+                transaction_data = Some(data);
+                break
+            }
+            Err(SourcePeerError { peer_id, error }) => {
+                tracing::debug!("Failed to get transactions: {}", error);
+                let peer_id = peer_id.unwrap_or_else(|| source.clone());
+                reporter.missing_transactions.record_miss(&peer_id);
+                reporter.report(
+                    peer_id,
+                    PeerReportReason::MissingTransactions,
+                    range.start.into(),
+                );
+            }
+        }
+    }
+    let Some(transaction_data) = transaction_data else {
+        return Batch::new(peer, range, vec![])
+    };
+    let size = transaction_data
+        .iter()
+        .flat_map(|transactions| transactions.0.iter())
+        .map(|tx| tx.size())
+        .fold(0usize, usize::saturating_add);
+    reporter.bandwidth.record(&source, size);
 
     let iter = headers.into_iter().zip(transaction_data.into_iter());
     let mut blocks = vec![];
@@ -533,50 +3442,29 @@ where
             consensus,
             entity: header,
         } = block_header;
-        let block =
-            Block::try_from_executed(header, transactions.0).map(|block| SealedBlock {
+        let height = *header.height();
+        let block = Block::try_from_executed(header, transactions.0)
+            .filter(|block| {
+                check_transactions(height, block.transactions(), transaction_checker)
+            })
+            .map(|block| SealedBlock {
                 entity: block,
                 consensus,
             });
         if let Some(block) = block {
             blocks.push(block);
         } else {
-            report_peer(p2p, peer.clone(), PeerReportReason::InvalidTransactions);
+            reporter.report(
+                source.clone(),
+                PeerReportReason::InvalidTransactions,
+                height,
+            );
             break
         }
     }
     Batch::new(peer, range, blocks)
 }
 
-#[tracing::instrument(
-    skip_all,
-    fields(
-        height = **block.entity.header().height(),
-        id = %block.entity.header().consensus().generated.application_hash
-    ),
-    err
-)]
-async fn execute_and_commit<E>(
-    executor: &E,
-    state: &SharedMutex<State>,
-    block: SealedBlock,
-) -> anyhow::Result<()>
-where
-    E: BlockImporterPort + Send + Sync + 'static,
-{
-    // Execute and commit the block.
-    let height = *block.entity.header().height();
-    let r = executor.execute_and_commit(block).await;
-
-    // If the block executed successfully, mark it as committed.
-    if r.is_ok() {
-        state.apply(|s| s.commit(*height));
-    } else {
-        tracing::error!("Execution of height {} failed: {:?}", *height, r);
-    }
-    r
-}
-
 /// Extra stream utilities.
 trait StreamUtil: Sized {
     /// Scan the stream for `None`.
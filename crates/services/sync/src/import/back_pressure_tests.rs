@@ -17,6 +17,7 @@ struct Input {
     consensus: Duration,
     transactions: Duration,
     executes: Duration,
+    executor_capacity: Option<usize>,
 }
 
 #[test_case(
@@ -24,6 +25,7 @@ struct Input {
     Config{
         block_stream_buffer_size: 1,
         header_batch_size: 1,
+        ..Default::default()
     }
     => Count::default() ; "Empty sanity test"
 )]
@@ -36,6 +38,7 @@ struct Input {
     Config{
         block_stream_buffer_size: 1,
         header_batch_size: 1,
+        ..Default::default()
     }
     => is less_or_equal_than Count{ headers: 1, consensus: 1, transactions: 1, executes: 1, blocks: 1 }
     ; "Single with slow headers"
@@ -49,6 +52,7 @@ struct Input {
     Config{
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     }
     => is less_or_equal_than Count{ headers: 10, consensus: 10, transactions: 10, executes: 1, blocks: 21 }
     ; "100 headers with max 10 with slow headers"
@@ -62,6 +66,7 @@ struct Input {
     Config{
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     }
     => is less_or_equal_than Count{ headers: 10, consensus: 10, transactions: 10, executes: 1, blocks: 21 }
     ; "100 headers with max 10 with slow transactions"
@@ -75,6 +80,7 @@ struct Input {
     Config{
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     }
     => is less_or_equal_than Count{ headers: 10, consensus: 10, transactions: 10, executes: 1, blocks: 21 }
     ; "50 headers with max 10 with slow executes"
@@ -88,10 +94,42 @@ struct Input {
     Config{
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     }
     => is less_or_equal_than Count{ headers: 10, consensus: 10, transactions: 10, executes: 1, blocks: 21 }
     ; "50 headers with max 10 size and max 10 requests"
 )]
+#[test_case(
+    Input {
+        executes: Duration::from_millis(10),
+        ..Default::default()
+    },
+    State::new(None, 100),
+    Config{
+        block_stream_buffer_size: 10,
+        header_batch_size: 5,
+        max_uncommitted_blocks: Some(15),
+        ..Default::default()
+    }
+    => is less_or_equal_than Count{ headers: 10, consensus: 10, transactions: 15, executes: 1, blocks: 15 }
+    ; "100 headers with max 15 uncommitted blocks and slow executes"
+)]
+#[test_case(
+    Input {
+        executes: Duration::from_millis(10),
+        executor_capacity: Some(5),
+        ..Default::default()
+    },
+    State::new(None, 100),
+    Config{
+        block_stream_buffer_size: 10,
+        header_batch_size: 5,
+        executor_poll_interval: Duration::from_millis(1),
+        ..Default::default()
+    }
+    => is less_or_equal_than Count{ headers: 1, consensus: 2, transactions: 9, executes: 1, blocks: 9 }
+    ; "100 headers with the saturated executor and slow executes"
+)]
 #[tokio::test(flavor = "multi_thread")]
 async fn test_back_pressure(input: Input, state: State, params: Config) -> Count {
     let counts = SharedCounts::new(Default::default());
@@ -101,18 +139,15 @@ async fn test_back_pressure(input: Input, state: State, params: Config) -> Count
         counts.clone(),
         [input.headers, input.transactions],
     ));
-    let executor = Arc::new(PressureBlockImporter::new(counts.clone(), input.executes));
+    let mut executor = PressureBlockImporter::new(counts.clone(), input.executes);
+    if let Some(capacity) = input.executor_capacity {
+        executor = executor.with_capacity(capacity);
+    }
+    let executor = Arc::new(executor);
     let consensus = Arc::new(PressureConsensus::new(counts.clone(), input.consensus));
     let notify = Arc::new(Notify::new());
 
-    let import = Import {
-        state,
-        notify,
-        params,
-        p2p,
-        executor,
-        consensus,
-    };
+    let import = Import::new(state, notify, params, p2p, executor, consensus);
 
     import.notify.notify_one();
     let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
@@ -2,6 +2,7 @@
 #![allow(missing_docs)]
 
 mod counts;
+mod fault_injector;
 mod pressure_block_importer;
 mod pressure_consensus;
 mod pressure_peer_to_peer;
@@ -29,6 +30,11 @@ pub use counts::{
 };
 use fuel_core_types::services::p2p::PeerId;
 
+pub use fault_injector::{
+    Fault,
+    FaultInjector,
+    FaultSchedule,
+};
 pub use pressure_block_importer::PressureBlockImporter;
 pub use pressure_consensus::PressureConsensus;
 pub use pressure_peer_to_peer::PressurePeerToPeer;
@@ -48,6 +54,7 @@ pub fn empty_header<I: Into<BlockHeight>>(i: I) -> SealedBlockHeader {
         );
     let root = transaction_tree.root().into();
     header.set_transaction_root(root);
+    header.recalculate_metadata();
 
     let consensus = Consensus::default();
     Sealed {
@@ -0,0 +1,222 @@
+use crate::ports::{
+    BlockImporterPort,
+    ImportTimings,
+    PeerReportReason,
+    PeerToPeerPort,
+};
+use fuel_core_services::stream::BoxStream;
+use fuel_core_types::{
+    blockchain::{
+        SealedBlock,
+        SealedBlockHeader,
+    },
+    fuel_types::{
+        BlockHeight,
+        Bytes32,
+    },
+    services::p2p::{
+        PeerId,
+        SourcePeer,
+        Transactions,
+    },
+};
+use rand::{
+    rngs::StdRng,
+    Rng,
+    SeedableRng,
+};
+use std::{
+    ops::Range,
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// The fault injected into a call of the wrapped port.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// The call is delayed before it reaches the wrapped port.
+    Delay(Duration),
+    /// The call fails without reaching the wrapped port.
+    Fail,
+}
+
+/// Decides the fault of each call of the wrapped port.
+#[derive(Clone, Debug)]
+pub enum FaultSchedule {
+    /// The n-th call gets the n-th fault, the schedule is repeated.
+    /// `None` passes the call through.
+    Fixed(Vec<Option<Fault>>),
+    /// Each call gets the `fault` with the `probability`.
+    Random {
+        probability: f64,
+        fault: Fault,
+        seed: u64,
+    },
+}
+
+/// Wraps the [`PeerToPeerPort`] or the [`BlockImporterPort`] and injects the faults
+/// into the calls fetching or executing the blocks according to the schedule.
+/// The rest of the calls are passed through.
+pub struct FaultInjector<T> {
+    inner: T,
+    schedule: FaultSchedule,
+    rng: Mutex<StdRng>,
+    calls: AtomicUsize,
+    injected: AtomicUsize,
+}
+
+impl<T> FaultInjector<T> {
+    pub fn new(inner: T, schedule: FaultSchedule) -> Self {
+        let seed = match &schedule {
+            FaultSchedule::Fixed(_) => 0,
+            FaultSchedule::Random { seed, .. } => *seed,
+        };
+        Self {
+            inner,
+            schedule,
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+            calls: AtomicUsize::new(0),
+            injected: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of the calls that got a fault.
+    pub fn injected(&self) -> usize {
+        self.injected.load(Ordering::SeqCst)
+    }
+
+    fn next_fault(&self) -> Option<Fault> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+        match &self.schedule {
+            FaultSchedule::Fixed(faults) if faults.is_empty() => None,
+            FaultSchedule::Fixed(faults) => faults[call % faults.len()],
+            FaultSchedule::Random {
+                probability, fault, ..
+            } => self
+                .rng
+                .lock()
+                .unwrap()
+                .gen_bool(*probability)
+                .then_some(*fault),
+        }
+    }
+
+    async fn inject(&self) -> anyhow::Result<()> {
+        let Some(fault) = self.next_fault() else {
+            return Ok(())
+        };
+        self.injected.fetch_add(1, Ordering::SeqCst);
+        match fault {
+            Fault::Delay(delay) => {
+                tokio::time::sleep(delay).await;
+                Ok(())
+            }
+            Fault::Fail => Err(anyhow::anyhow!("Injected fault")),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> PeerToPeerPort for FaultInjector<T>
+where
+    T: PeerToPeerPort + Send + Sync,
+{
+    fn height_stream(&self) -> BoxStream<BlockHeight> {
+        self.inner.height_stream()
+    }
+
+    async fn get_sealed_block_headers(
+        &self,
+        block_height_range: Range<u32>,
+    ) -> anyhow::Result<SourcePeer<Option<Vec<SealedBlockHeader>>>> {
+        self.inject().await?;
+        self.inner
+            .get_sealed_block_headers(block_height_range)
+            .await
+    }
+
+    async fn get_sealed_block_headers_from_peer(
+        &self,
+        block_height_range: SourcePeer<Range<u32>>,
+    ) -> anyhow::Result<Option<Vec<SealedBlockHeader>>> {
+        self.inject().await?;
+        self.inner
+            .get_sealed_block_headers_from_peer(block_height_range)
+            .await
+    }
+
+    async fn peers_with_height(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Vec<PeerId>> {
+        self.inner.peers_with_height(height).await
+    }
+
+    async fn get_transactions(
+        &self,
+        block_ids: SourcePeer<Range<u32>>,
+    ) -> anyhow::Result<Option<Vec<Transactions>>> {
+        self.inject().await?;
+        self.inner.get_transactions(block_ids).await
+    }
+
+    async fn chain_config_hash(&self, peer: PeerId) -> anyhow::Result<Option<Bytes32>> {
+        self.inner.chain_config_hash(peer).await
+    }
+
+    fn report_peer(&self, peer: PeerId, report: PeerReportReason) -> anyhow::Result<()> {
+        self.inner.report_peer(peer, report)
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> BlockImporterPort for FaultInjector<T>
+where
+    T: BlockImporterPort + Send + Sync,
+{
+    fn committed_height_stream(&self) -> BoxStream<BlockHeight> {
+        self.inner.committed_height_stream()
+    }
+
+    async fn execute_and_commit(&self, block: SealedBlock) -> anyhow::Result<()> {
+        self.inject().await?;
+        self.inner.execute_and_commit(block).await
+    }
+
+    fn can_accept(&self) -> bool {
+        self.inner.can_accept()
+    }
+
+    fn record_import_timings(
+        &self,
+        height: BlockHeight,
+        timings: ImportTimings,
+    ) -> anyhow::Result<()> {
+        self.inner.record_import_timings(height, timings)
+    }
+
+    fn local_sealed_header(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Option<SealedBlockHeader>> {
+        self.inner.local_sealed_header(height)
+    }
+
+    fn local_transactions(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Option<Transactions>> {
+        self.inner.local_transactions(height)
+    }
+
+    async fn store_sealed_header(&self, header: SealedBlockHeader) -> anyhow::Result<()> {
+        self.inject().await?;
+        self.inner.store_sealed_header(header).await
+    }
+}
@@ -2,17 +2,27 @@ use crate::{
     import::test_helpers::SharedCounts,
     ports::{
         BlockImporterPort,
+        ImportTimings,
         MockBlockImporterPort,
     },
 };
 use fuel_core_services::stream::BoxStream;
 use fuel_core_types::{
-    blockchain::SealedBlock,
+    blockchain::{
+        SealedBlock,
+        SealedBlockHeader,
+    },
     fuel_types::BlockHeight,
+    services::p2p::Transactions,
 };
 use std::time::Duration;
 
-pub struct PressureBlockImporter(MockBlockImporterPort, Duration, SharedCounts);
+pub struct PressureBlockImporter(
+    MockBlockImporterPort,
+    Duration,
+    SharedCounts,
+    Option<usize>,
+);
 
 #[async_trait::async_trait]
 impl BlockImporterPort for PressureBlockImporter {
@@ -29,12 +39,53 @@ impl BlockImporterPort for PressureBlockImporter {
         });
         self.0.execute_and_commit(block).await
     }
+
+    fn can_accept(&self) -> bool {
+        match self.3 {
+            Some(capacity) => self.2.apply(|c| c.now.blocks < capacity),
+            None => self.0.can_accept(),
+        }
+    }
+
+    fn record_import_timings(
+        &self,
+        height: BlockHeight,
+        timings: ImportTimings,
+    ) -> anyhow::Result<()> {
+        self.0.record_import_timings(height, timings)
+    }
+
+    fn local_sealed_header(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Option<SealedBlockHeader>> {
+        self.0.local_sealed_header(height)
+    }
+
+    fn local_transactions(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Option<Transactions>> {
+        self.0.local_transactions(height)
+    }
+
+    async fn store_sealed_header(&self, header: SealedBlockHeader) -> anyhow::Result<()> {
+        self.0.store_sealed_header(header).await
+    }
 }
 
 impl PressureBlockImporter {
     pub fn new(counts: SharedCounts, delays: Duration) -> Self {
         let mut mock = MockBlockImporterPort::default();
+        mock.expect_can_accept().returning(|| true);
         mock.expect_execute_and_commit().returning(move |_| Ok(()));
-        Self(mock, delays, counts)
+        Self(mock, delays, counts, None)
+    }
+
+    /// Reports the saturation while there are `capacity` or more blocks
+    /// downloaded but not yet executed.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.3 = Some(capacity);
+        self
     }
 }
@@ -25,6 +25,10 @@ impl ConsensusPort for PressureConsensus {
         self.2.apply(|c| c.dec_consensus());
         self.0.await_da_height(da_height).await
     }
+
+    fn da_tip(&self) -> anyhow::Result<Option<DaBlockHeight>> {
+        self.0.da_tip()
+    }
 }
 
 impl PressureConsensus {
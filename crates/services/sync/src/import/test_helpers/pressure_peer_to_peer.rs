@@ -13,7 +13,10 @@ use crate::{
 use fuel_core_services::stream::BoxStream;
 use fuel_core_types::{
     blockchain::SealedBlockHeader,
-    fuel_types::BlockHeight,
+    fuel_types::{
+        BlockHeight,
+        Bytes32,
+    },
     services::p2p::{
         PeerId,
         SourcePeer,
@@ -50,6 +53,22 @@ impl PeerToPeerPort for PressurePeerToPeer {
         self.p2p.get_sealed_block_headers(block_height_range).await
     }
 
+    async fn get_sealed_block_headers_from_peer(
+        &self,
+        block_height_range: SourcePeer<Range<u32>>,
+    ) -> anyhow::Result<Option<Vec<SealedBlockHeader>>> {
+        self.p2p
+            .get_sealed_block_headers_from_peer(block_height_range)
+            .await
+    }
+
+    async fn peers_with_height(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Vec<PeerId>> {
+        self.p2p.peers_with_height(height).await
+    }
+
     async fn get_transactions(
         &self,
         block_ids: SourcePeer<Range<u32>>,
@@ -63,6 +82,10 @@ impl PeerToPeerPort for PressurePeerToPeer {
         self.p2p.get_transactions(block_ids).await
     }
 
+    async fn chain_config_hash(&self, peer: PeerId) -> anyhow::Result<Option<Bytes32>> {
+        self.p2p.chain_config_hash(peer).await
+    }
+
     fn report_peer(
         &self,
         _peer: PeerId,
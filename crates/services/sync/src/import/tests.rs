@@ -5,15 +5,24 @@ use crate::{
     import::test_helpers::{
         empty_header,
         random_peer,
+        Fault,
+        FaultInjector,
+        FaultSchedule,
     },
     ports::{
         MockBlockImporterPort,
         MockConsensusPort,
+        MockDiskMonitor,
         MockPeerToPeerPort,
+        MockReplayExecutor,
+        MockTransactionChecker,
         PeerReportReason,
     },
 };
-use fuel_core_types::services::p2p::Transactions;
+use fuel_core_types::{
+    blockchain::primitives::BlockId,
+    services::p2p::Transactions,
+};
 
 use super::*;
 
@@ -53,6 +62,7 @@ async fn test_import_0_to_5() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
     let mocks = Mocks {
         consensus_port,
@@ -99,6 +109,7 @@ async fn test_import_3_to_5() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
     let mocks = Mocks {
         consensus_port,
@@ -165,6 +176,7 @@ async fn test_import_0_to_499() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size,
+        ..Default::default()
     };
     let mocks = Mocks {
         consensus_port,
@@ -217,13 +229,81 @@ async fn import__signature_fails_on_header_5_only() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(4, None), false), res);
+    assert_eq!((State::new(4, None).with_failed(5..=5), false), res);
+}
+
+#[tokio::test]
+async fn import__failure_near_the_max_height_marks_the_rest_of_the_range_failed() {
+    // given
+    let failing_height = u32::MAX - 2;
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(3)
+        .returning(move |h| Ok(**h.entity.height() != failing_height));
+    consensus_port
+        .expect_await_da_height()
+        .times(1)
+        .returning(|_| Ok(()));
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .returning(|range| {
+            let peer = random_peer();
+            let headers = Some(range.map(empty_header).collect());
+            let headers = peer.bind(headers);
+            Ok(headers)
+        });
+    p2p.expect_get_transactions()
+        .times(1)
+        .returning(|block_ids| {
+            let data = block_ids.data;
+            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+
+    let state = State::new(u32::MAX - 5, u32::MAX - 1).into();
+    let mocks = Mocks {
+        consensus_port,
+        p2p,
+        executor: DefaultMocks::times([2]),
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+
+    // when
+    let (state, success) = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert!(!success);
+    assert_eq!(
+        state,
+        State::new(u32::MAX - 3, None).with_failed(u32::MAX - 2..=u32::MAX - 1)
+    );
+    assert_eq!(failed_ranges(&state), vec![failing_height..=u32::MAX - 1]);
+}
+
+#[test]
+fn incomplete_range__does_not_overflow_at_the_max_height() {
+    let range = u32::MAX - 1..=u32::MAX;
+
+    assert_eq!(incomplete_range(&range, 0).unwrap(), Some(range.clone()));
+    assert_eq!(
+        incomplete_range(&range, 1).unwrap(),
+        Some(u32::MAX..=u32::MAX)
+    );
+    assert_eq!(incomplete_range(&range, 2).unwrap(), None);
+    assert_eq!(incomplete_range(&(0..=u32::MAX), usize::MAX).unwrap(), None);
 }
 
 #[tokio::test]
@@ -265,13 +345,14 @@ async fn import__signature_fails_on_header_4_only() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(3, None), false), res);
+    assert_eq!((State::new(3, None).with_failed(4..=5), false), res);
 }
 
 #[tokio::test]
@@ -296,13 +377,14 @@ async fn import__header_not_found() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(3, None), false), res);
+    assert_eq!((State::new(3, None).with_failed(4..=5), false), res);
 }
 
 #[tokio::test]
@@ -327,13 +409,14 @@ async fn import__header_response_incomplete() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(3, None), false), res);
+    assert_eq!((State::new(3, None).with_failed(4..=5), false), res);
 }
 
 #[tokio::test]
@@ -366,13 +449,14 @@ async fn import__header_5_not_found() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(4, None), false), res);
+    assert_eq!((State::new(4, None).with_failed(5..=5), false), res);
 }
 
 #[tokio::test]
@@ -398,13 +482,14 @@ async fn import__header_4_not_found() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(3, None), false), res);
+    assert_eq!((State::new(3, None).with_failed(4..=5), false), res);
 }
 
 #[tokio::test]
@@ -442,13 +527,14 @@ async fn import__transactions_not_found() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(3, None), false), res);
+    assert_eq!((State::new(3, None).with_failed(4..=5), false), res);
 }
 
 #[tokio::test]
@@ -496,13 +582,14 @@ async fn import__transactions_not_found_for_header_4() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(3, None), false), res);
+    assert_eq!((State::new(3, None).with_failed(4..=5), false), res);
 }
 
 #[tokio::test]
@@ -527,10 +614,13 @@ async fn import__transactions_not_found_for_header_5() {
             let headers = peer.bind(headers);
             Ok(headers)
         });
-    p2p.expect_get_transactions().times(1).returning(move |_| {
-        let v = vec![Transactions::default()];
-        Ok(Some(v))
-    });
+    // The follow-up request for the missing block is not served either.
+    p2p.expect_get_transactions()
+        .times(2)
+        .returning(move |block_ids| {
+            let v = vec![Transactions::default()];
+            Ok((block_ids.data.start == 4).then_some(v))
+        });
 
     let state = State::new(3, 5).into();
     let mocks = Mocks {
@@ -541,13 +631,14 @@ async fn import__transactions_not_found_for_header_5() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(4, None), false), res);
+    assert_eq!((State::new(4, None).with_failed(5..=5), false), res);
 }
 
 #[tokio::test]
@@ -568,204 +659,205 @@ async fn import__p2p_error() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(3, None), false), res);
+    assert_eq!((State::new(3, None).with_failed(4..=5), false), res);
 }
 
 #[tokio::test]
-async fn import__p2p_error_on_4_transactions() {
+async fn import__set_config_applies_to_the_next_range() {
     // given
-    let mut consensus_port = MockConsensusPort::default();
-    consensus_port
-        .expect_check_sealed_header()
-        .times(2)
-        .returning(|_| Ok(true));
-    consensus_port
-        .expect_await_da_height()
-        .times(1)
-        .returning(|_| Ok(()));
-
+    let requested = Arc::new(std::sync::Mutex::new(vec![]));
     let mut p2p = MockPeerToPeerPort::default();
-    p2p.expect_get_sealed_block_headers()
-        .times(1)
-        .returning(|range| {
-            let peer = random_peer();
+    p2p.expect_get_sealed_block_headers().returning({
+        let requested = requested.clone();
+        move |range| {
+            requested.lock().unwrap().push(range.len());
             let headers = Some(range.map(empty_header).collect());
-            let headers = peer.bind(headers);
-            Ok(headers)
-        });
-    p2p.expect_get_transactions()
-        .times(1)
-        .returning(|_| Err(anyhow::anyhow!("Some network error")));
+            Ok(random_peer().bind(headers))
+        }
+    });
+    p2p.expect_get_transactions().returning(|block_ids| {
+        let data = block_ids.data;
+        let v = data.into_iter().map(|_| Transactions::default()).collect();
+        Ok(Some(v))
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let consensus: MockConsensusPort = DefaultMocks::times([30, 4]);
+    let executor: MockBlockImporterPort = DefaultMocks::times([30]);
 
-    let state = State::new(3, 5).into();
+    let state: SharedMutex<State> = State::new(None, 9).into();
+    let notify = Arc::new(Notify::new());
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 5,
+        ..Default::default()
+    };
+    let import = Import::new(
+        state.clone(),
+        notify,
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(consensus),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+    import.notify.notify_one();
+    import.import(&mut watcher).await.unwrap();
+
+    // when
+    import.set_config(Config {
+        header_batch_size: 10,
+        ..import.config()
+    });
+    state.apply(|s| s.observe(29));
+    import.notify.notify_one();
+    import.import(&mut watcher).await.unwrap();
+
+    // then
+    assert_eq!(*requested.lock().unwrap(), vec![5, 5, 10, 10]);
+    assert_eq!(state.apply(|s| s.committed_height()), Some(29));
+}
+
+#[tokio::test]
+async fn import__stops_confirmation_lag_blocks_short_of_the_tip() {
+    // given
+    let state = State::new(3, 10).into();
     let mocks = Mocks {
-        p2p,
-        consensus_port,
-        executor: DefaultMocks::times([0]),
+        consensus_port: DefaultMocks::times([4, 1]),
+        p2p: DefaultMocks::times([1]),
+        executor: DefaultMocks::times([4]),
     };
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        confirmation_lag: 3,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(3, None), false), res);
+    assert_eq!((State::new(7, 10), true), res);
 }
 
 #[tokio::test]
-async fn import__consensus_error_on_4() {
+async fn import__confirmation_lag_above_the_tip_imports_nothing() {
     // given
-    let mut consensus_port = MockConsensusPort::default();
-    consensus_port
-        .expect_check_sealed_header()
-        .times(1)
-        .returning(|h| {
-            if **h.entity.height() == 4 {
-                Err(anyhow::anyhow!("Some consensus error"))
-            } else {
-                Ok(true)
-            }
-        });
-    consensus_port
-        .expect_await_da_height()
-        .times(0)
-        .returning(|_| Ok(()));
-
-    let mut p2p = MockPeerToPeerPort::default();
-    p2p.expect_get_sealed_block_headers()
-        .times(1)
-        .returning(|range| {
-            let peer = random_peer();
-            let headers = Some(range.map(empty_header).collect());
-            let headers = peer.bind(headers);
-            Ok(headers)
-        });
-    p2p.expect_get_transactions().times(0);
-
-    let state = State::new(3, 5).into();
+    let state = State::new(None, 2).into();
     let mocks = Mocks {
-        consensus_port,
-        p2p,
+        consensus_port: DefaultMocks::times([0]),
+        p2p: MockPeerToPeerPort::default(),
         executor: DefaultMocks::times([0]),
     };
     let params = Config {
-        block_stream_buffer_size: 10,
-        header_batch_size: 10,
+        confirmation_lag: 5,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(3, None), false), res);
+    assert_eq!((State::new(None, 2), true), res);
 }
 
 #[tokio::test]
-async fn import__consensus_error_on_5() {
+async fn import__huge_range_is_imported_in_capped_iterations() {
     // given
+    let max_range: u32 = 100;
     let mut consensus_port = MockConsensusPort::default();
     consensus_port
         .expect_check_sealed_header()
-        .times(2)
-        .returning(|h| {
-            if **h.entity.height() == 5 {
-                Err(anyhow::anyhow!("Some consensus error"))
-            } else {
-                Ok(true)
-            }
-        });
+        .returning(|_| Ok(true));
     consensus_port
         .expect_await_da_height()
-        .times(1)
         .returning(|_| Ok(()));
-
     let mut p2p = MockPeerToPeerPort::default();
-    p2p.expect_get_sealed_block_headers()
-        .times(1)
-        .returning(|range| {
-            let peer = random_peer();
-            let headers = Some(range.map(empty_header).collect());
-            let headers = peer.bind(headers);
-            Ok(headers)
-        });
-    p2p.expect_get_transactions()
-        .times(1)
-        .returning(|block_ids| {
-            let data = block_ids.data;
-            let v = data.into_iter().map(|_| Transactions::default()).collect();
-            Ok(Some(v))
-        });
+    p2p.expect_get_sealed_block_headers().returning(|range| {
+        let headers = Some(range.map(empty_header).collect());
+        Ok(random_peer().bind(headers))
+    });
+    p2p.expect_get_transactions().returning(|block_ids| {
+        let data = block_ids.data;
+        let v = data.into_iter().map(|_| Transactions::default()).collect();
+        Ok(Some(v))
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    executor.expect_execute_and_commit().returning(|_| Ok(()));
 
-    let state = State::new(3, 5).into();
-    let mocks = Mocks {
-        consensus_port,
-        p2p,
-        executor: DefaultMocks::times([1]),
-    };
     let params = Config {
         block_stream_buffer_size: 10,
-        header_batch_size: 10,
+        header_batch_size: 30,
+        max_range_per_iteration: Some(usize::try_from(max_range).unwrap()),
+        ..Default::default()
     };
-
-    // when
-    let res = test_import_inner(state, mocks, None, params).await;
-
-    // then
-    assert_eq!((State::new(4, None), false), res);
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 10_000_000)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(consensus_port),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    for iteration in 1..=3u32 {
+        // when
+        let res = import.import_inner(&watcher).await;
+
+        // then
+        assert!(res.is_ok());
+        let committed = import.state.apply(|s| s.committed_height());
+        assert_eq!(committed, Some(iteration * max_range - 1));
+        // The import is notified to continue with the rest of the range.
+        assert!(import.notify.notified().now_or_never().is_some());
+    }
 }
 
 #[tokio::test]
-async fn import__execution_error_on_header_4() {
+async fn import__headers_only_stores_headers_without_transactions() {
     // given
     let mut consensus_port = MockConsensusPort::default();
     consensus_port
         .expect_check_sealed_header()
-        .times(2)
+        .times(3)
         .returning(|_| Ok(true));
     consensus_port
         .expect_await_da_height()
         .times(1)
         .returning(|_| Ok(()));
-
     let mut p2p = MockPeerToPeerPort::default();
     p2p.expect_get_sealed_block_headers()
         .times(1)
         .returning(|range| {
-            let peer = random_peer();
             let headers = Some(range.map(empty_header).collect());
-            let headers = peer.bind(headers);
-            Ok(headers)
-        });
-    p2p.expect_get_transactions()
-        .times(1)
-        .returning(|block_ids| {
-            let data = block_ids.data;
-            let v = data.into_iter().map(|_| Transactions::default()).collect();
-            Ok(Some(v))
+            Ok(random_peer().bind(headers))
         });
-
+    p2p.expect_get_transactions().times(0);
+    let stored = Arc::new(std::sync::Mutex::new(vec![]));
     let mut executor = MockBlockImporterPort::default();
-    executor
-        .expect_execute_and_commit()
-        .times(1)
-        .returning(|h| {
-            if **h.entity.header().height() == 4 {
-                Err(anyhow::anyhow!("Some execution error"))
-            } else {
-                Ok(())
-            }
-        });
+    executor.expect_can_accept().returning(|| true);
+    executor.expect_execute_and_commit().times(0);
+    executor.expect_store_sealed_header().times(3).returning({
+        let stored = stored.clone();
+        move |header| {
+            stored.lock().unwrap().push(**header.entity.height());
+            Ok(())
+        }
+    });
 
-    let state = State::new(3, 5).into();
+    let state = State::new(3, 6).into();
     let mocks = Mocks {
         consensus_port,
         p2p,
@@ -774,202 +866,2944 @@ async fn import__execution_error_on_header_4() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        headers_only: true,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(3, None), false), res);
+    assert_eq!((State::new(6, None), true), res);
+    assert_eq!(*stored.lock().unwrap(), vec![4, 5, 6]);
 }
 
 #[tokio::test]
-async fn import__execution_error_on_header_5() {
+async fn import__warmup_fetches_headers_before_the_first_execution() {
     // given
-    let mut consensus_port = MockConsensusPort::default();
-    consensus_port
-        .expect_check_sealed_header()
-        .times(2)
-        .returning(|_| Ok(true));
-    consensus_port
-        .expect_await_da_height()
-        .times(1)
-        .returning(|_| Ok(()));
-
+    let warmup_headers = 3;
+    let events = Arc::new(std::sync::Mutex::new(vec![]));
     let mut p2p = MockPeerToPeerPort::default();
-    p2p.expect_get_sealed_block_headers()
-        .times(1)
-        .returning(|range| {
-            let peer = random_peer();
+    p2p.expect_get_sealed_block_headers().times(5).returning({
+        let events = events.clone();
+        move |range| {
+            events.lock().unwrap().push("headers");
             let headers = Some(range.map(empty_header).collect());
-            let headers = peer.bind(headers);
-            Ok(headers)
-        });
+            Ok(random_peer().bind(headers))
+        }
+    });
     p2p.expect_get_transactions()
-        .times(1)
+        .times(5)
         .returning(|block_ids| {
-            let data = block_ids.data;
-            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            let v = block_ids.data.map(|_| Transactions::default()).collect();
             Ok(Some(v))
         });
-
     let mut executor = MockBlockImporterPort::default();
-    executor
-        .expect_execute_and_commit()
-        .times(2)
-        .returning(|h| {
-            if **h.entity.header().height() == 5 {
-                Err(anyhow::anyhow!("Some execution error"))
-            } else {
-                Ok(())
-            }
-        });
+    executor.expect_can_accept().returning(|| true);
+    executor.expect_execute_and_commit().times(5).returning({
+        let events = events.clone();
+        move |_| {
+            events.lock().unwrap().push("execute");
+            Ok(())
+        }
+    });
 
-    let state = State::new(3, 5).into();
+    let state = State::new(0, 5).into();
     let mocks = Mocks {
-        consensus_port,
+        consensus_port: DefaultMocks::times([5, 5]),
         p2p,
         executor,
     };
     let params = Config {
-        block_stream_buffer_size: 10,
-        header_batch_size: 10,
+        block_stream_buffer_size: 1,
+        header_batch_size: 1,
+        warmup_headers,
+        ..Default::default()
     };
 
     // when
     let res = test_import_inner(state, mocks, None, params).await;
 
     // then
-    assert_eq!((State::new(4, None), false), res);
+    assert_eq!((State::new(5, None), true), res);
+    let events = events.lock().unwrap();
+    let first_execution = events.iter().position(|e| *e == "execute").unwrap();
+    assert_eq!(first_execution, warmup_headers);
 }
 
 #[tokio::test]
-async fn signature_always_fails() {
+async fn import__strided_fetch_order_requests_sparse_headers_first() {
     // given
-    let mut consensus_port = MockConsensusPort::default();
-    consensus_port
-        .expect_check_sealed_header()
-        .times(1)
-        .returning(|_| Ok(false));
-    consensus_port.expect_await_da_height().times(0);
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 1,
+        fetch_order: FetchOrder::Strided { stride: 3 },
+        ..Default::default()
+    };
+
+    // when
+    let (requested, committed) = import_with_fetch_order(params).await;
+
+    // then
+    assert_eq!(requested, vec![1, 4, 2, 5, 3, 6]);
+    assert_eq!(committed, vec![1, 2, 3, 4, 5, 6]);
+}
+
+#[tokio::test]
+async fn import__strided_fetch_order_stays_within_the_uncommitted_blocks_window() {
+    // given
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 1,
+        max_uncommitted_blocks: Some(4),
+        fetch_order: FetchOrder::Strided { stride: 2 },
+        ..Default::default()
+    };
+
+    // when
+    let (requested, committed) = import_with_fetch_order(params).await;
+
+    // then
+    assert_eq!(requested, vec![1, 3, 2, 4, 5, 6]);
+    assert_eq!(committed, vec![1, 2, 3, 4, 5, 6]);
+}
+
+/// Imports the heights from 1 to 6 with the `params` and returns the starts of
+/// the requested header batches and the committed heights in their order.
+async fn import_with_fetch_order(params: Config) -> (Vec<u32>, Vec<u32>) {
+    let requested = Arc::new(std::sync::Mutex::new(vec![]));
+    let committed = Arc::new(std::sync::Mutex::new(vec![]));
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().times(6).returning({
+        let requested = requested.clone();
+        move |range| {
+            requested.lock().unwrap().push(range.start);
+            let headers = Some(range.map(empty_header).collect());
+            Ok(random_peer().bind(headers))
+        }
+    });
+    p2p.expect_get_transactions()
+        .times(6)
+        .returning(|block_ids| {
+            let v = block_ids.data.map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    executor.expect_execute_and_commit().times(6).returning({
+        let committed = committed.clone();
+        move |block| {
+            committed
+                .lock()
+                .unwrap()
+                .push(**block.entity.header().height());
+            Ok(())
+        }
+    });
+
+    let state = State::new(0, 6).into();
+    let mocks = Mocks {
+        consensus_port: DefaultMocks::times([6, 6]),
+        p2p,
+        executor,
+    };
+
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    assert_eq!((State::new(6, None), true), res);
+    let requested = requested.lock().unwrap().clone();
+    let committed = committed.lock().unwrap().clone();
+    (requested, committed)
+}
+
+#[tokio::test]
+async fn import__circuit_breaker_trips_pauses_and_retries_after_cooldown() {
+    use std::sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    };
+
+    // given
+    let cooldown = Duration::from_secs(60);
+    let requests = Arc::new(AtomicUsize::new(0));
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning({
+        let requests = requests.clone();
+        move |_| {
+            requests.fetch_add(1, Ordering::SeqCst);
+            Ok(random_peer().bind(None))
+        }
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    let clock = crate::clock::MockClock::new();
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        circuit_breaker: Some(CircuitBreaker {
+            max_consecutive_failures: 2,
+            cooldown,
+        }),
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(3, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(MockConsensusPort::default()),
+    )
+    .with_clock(Arc::new(clock.clone()));
+    let mut events = import.subscribe_circuit_breaker();
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+
+    // when
+    for _ in 0..2 {
+        import.reset_failed();
+        assert!(import.import(&mut watcher).await.is_err());
+    }
+
+    // then
+    assert_eq!(
+        events.try_recv().unwrap(),
+        CircuitBreakerEvent::Tripped { failures: 2 }
+    );
+    import.reset_failed();
+    let retry = import.import(&mut watcher);
+    futures::pin_mut!(retry);
+    let paused = tokio::time::timeout(Duration::from_millis(100), &mut retry).await;
+    assert!(paused.is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+    clock.advance(cooldown);
+    assert!(retry.await.is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+    assert_eq!(events.try_recv().unwrap(), CircuitBreakerEvent::HalfOpened);
+    assert_eq!(
+        events.try_recv().unwrap(),
+        CircuitBreakerEvent::Tripped { failures: 2 }
+    );
+}
+
+#[tokio::test]
+async fn import__header_retry_excludes_the_failed_peer() {
+    // given
+    let bad_peer: PeerId = vec![1].into();
+    let good_peer: PeerId = vec![2].into();
+    let mut seq = mockall::Sequence::new();
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning({
+            let bad_peer = bad_peer.clone();
+            move |_| Ok(bad_peer.clone().bind(None))
+        });
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning({
+            let bad_peer = bad_peer.clone();
+            move |range| {
+                Ok(bad_peer
+                    .clone()
+                    .bind(Some(range.map(empty_header).collect())))
+            }
+        });
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning({
+            let good_peer = good_peer.clone();
+            move |range| {
+                Ok(good_peer
+                    .clone()
+                    .bind(Some(range.map(empty_header).collect())))
+            }
+        });
+    p2p.expect_get_transactions()
+        .times(1)
+        .withf(move |block_ids| block_ids.peer_id == good_peer)
+        .returning(|block_ids| {
+            let data = block_ids.data;
+            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+
+    let state = State::new(3, 5).into();
+    let mocks = Mocks {
+        p2p,
+        consensus_port: DefaultMocks::times([2, 1]),
+        executor: DefaultMocks::times([2]),
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        retry_policy: RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::ZERO,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(5, None), true), res);
+}
+
+#[test_case::test_case(false; "headers")]
+#[test_case::test_case(true; "transactions")]
+#[tokio::test(start_paused = true)]
+async fn import__retry_policy_gives_up_after_max_attempts(headers_provided: bool) {
+    // given
+    let max_attempts = 3;
+    let (header_attempts, transaction_attempts) = if headers_provided {
+        (1, max_attempts)
+    } else {
+        (max_attempts, 0)
+    };
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .returning(|_| Ok(()));
+
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(header_attempts)
+        .returning(move |range| {
+            let headers = headers_provided.then(|| range.map(empty_header).collect());
+            Ok(random_peer().bind(headers))
+        });
+    p2p.expect_get_transactions()
+        .times(transaction_attempts)
+        .returning(|_| Ok(None));
 
     let state = State::new(3, 5).into();
     let mocks = Mocks {
+        p2p,
         consensus_port,
-        p2p: DefaultMocks::times([0]),
         executor: DefaultMocks::times([0]),
     };
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        retry_policy: RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(2),
+            jitter: false,
+        },
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(3, None).with_failed(4..=5), false), res);
+}
+
+#[test]
+fn retry_policy__delay_doubles_up_to_the_max_delay() {
+    let policy = RetryPolicy {
+        max_attempts: 5,
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(300),
+        jitter: false,
+    };
+
+    assert_eq!(policy.delay(0), Duration::from_millis(100));
+    assert_eq!(policy.delay(1), Duration::from_millis(200));
+    assert_eq!(policy.delay(2), Duration::from_millis(300));
+    assert_eq!(policy.delay(100), Duration::from_millis(300));
+}
+
+#[test]
+fn peer_reporter__reports_the_peer_once_it_reaches_the_strike_threshold() {
+    // given
+    let reports = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_report_peer().returning({
+        let reports = reports.clone();
+        move |_, _| {
+            reports.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Ok(())
+        }
+    });
+    let params = Config {
+        peer_strikes: Some(PeerStrikes {
+            strike_threshold: 3,
+            window: Duration::from_secs(60),
+        }),
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, None)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(MockBlockImporterPort::default()),
+        Arc::new(MockConsensusPort::default()),
+    );
+    let reporter = import.peer_reporter();
+    let peer = random_peer();
+    let report = || {
+        reporter.report(
+            peer.clone(),
+            PeerReportReason::MissingBlockHeaders,
+            1u32.into(),
+        )
+    };
+
+    // when
+    report();
+    report();
+
+    // then
+    assert_eq!(reports.load(std::sync::atomic::Ordering::Relaxed), 0);
+
+    // when
+    report();
+
+    // then
+    assert_eq!(reports.load(std::sync::atomic::Ordering::Relaxed), 1);
+}
+
+#[tokio::test]
+async fn import__peer_over_bandwidth_budget_is_skipped() {
+    // given
+    let busy_peer: PeerId = vec![1].into();
+    let other_peer: PeerId = vec![2].into();
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(4)
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .times(2)
+        .returning(|_| Ok(()));
+
+    let mut seq = mockall::Sequence::new();
+    let mut p2p = MockPeerToPeerPort::default();
+    for peer in [busy_peer.clone(), busy_peer.clone(), other_peer.clone()] {
+        p2p.expect_get_sealed_block_headers()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |range| {
+                let headers = Some(range.map(empty_header).collect());
+                Ok(peer.clone().bind(headers))
+            });
+    }
+    let served_by = Arc::new(std::sync::Mutex::new(vec![]));
+    p2p.expect_get_transactions().times(2).returning({
+        let served_by = served_by.clone();
+        move |block_ids| {
+            served_by.lock().unwrap().push(block_ids.peer_id.clone());
+            let data = block_ids.data;
+            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        }
+    });
+
+    let state = State::new(None, 3).into();
+    let mocks = Mocks {
+        consensus_port,
+        p2p,
+        executor: DefaultMocks::times([4]),
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 2,
+        retry_policy: RetryPolicy {
+            max_attempts: 2,
+            base_delay: Duration::ZERO,
+            ..Default::default()
+        },
+        // Any batch of headers uses up the budget of its peer.
+        max_bytes_per_peer_per_window: Some(1),
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(3, None), true), res);
+    assert_eq!(*served_by.lock().unwrap(), vec![busy_peer, other_peer]);
+}
+
+#[tokio::test]
+async fn import__refuses_headers_from_the_peer_with_incompatible_chain_config() {
+    // given
+    let local_chain_config_hash = Bytes32::new([1; 32]);
+    let advertised_chain_config_hash = Bytes32::new([2; 32]);
+
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port.expect_check_sealed_header().times(0);
+    consensus_port.expect_await_da_height().times(0);
+
+    let peer = random_peer();
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().times(1).returning({
+        let peer = peer.clone();
+        move |range| {
+            let headers = Some(range.map(empty_header).collect());
+            Ok(peer.clone().bind(headers))
+        }
+    });
+    p2p.expect_chain_config_hash()
+        .times(1)
+        .with(mockall::predicate::eq(peer.clone()))
+        .returning(move |_| Ok(Some(advertised_chain_config_hash)));
+    p2p.expect_get_transactions().times(0);
+    p2p.expect_report_peer()
+        .times(1)
+        .with(
+            mockall::predicate::eq(peer.clone()),
+            mockall::predicate::eq(PeerReportReason::IncompatibleChainConfig),
+        )
+        .returning(|_, _| Ok(()));
+
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        chain_config_hash: Some(local_chain_config_hash),
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(3, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(MockBlockImporterPort::times([0])),
+        Arc::new(consensus_port),
+    );
+    let mut events = import.subscribe_peer_reports();
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    // when
+    let res = import.import_inner(&watcher).await;
+
+    // then
+    assert!(res.is_err());
+    assert_eq!(import.state.apply(|s| s.clone()), State::new(3, None).with_failed(4..=5));
+    let expected = PeerReportEvent {
+        peer_id: peer,
+        reason: PeerReportReason::IncompatibleChainConfig,
+        height: 4.into(),
+    };
+    assert_eq!(events.try_recv().unwrap(), expected);
+    assert!(events.try_recv().is_err());
+}
+
+/// The name of a span with the names of its ancestors.
+type SpanWithAncestors = (&'static str, Vec<&'static str>);
+
+/// Captures the names of the ancestors of each new span.
+#[derive(Clone, Default)]
+struct SpanAncestors(Arc<std::sync::Mutex<Vec<SpanWithAncestors>>>);
+
+impl<S> tracing_subscriber::Layer<S> for SpanAncestors
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    fn on_new_span(
+        &self,
+        _: &tracing::span::Attributes<'_>,
+        id: &tracing::span::Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let span = ctx.span(id).expect("The new span exists");
+        let ancestors = span.scope().skip(1).map(|span| span.name()).collect();
+        self.0.lock().unwrap().push((span.name(), ancestors));
+    }
+}
+
+#[tokio::test]
+async fn import__spans_of_the_spawned_tasks_are_linked_to_the_import_span() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    // given
+    let spans = SpanAncestors::default();
+    let subscriber = tracing_subscriber::registry().with(spans.clone());
+    let _guard = tracing::subscriber::set_default(subscriber);
+    let mocks = Mocks {
+        consensus_port: DefaultMocks::times([2, 1]),
+        p2p: DefaultMocks::times([1]),
+        executor: DefaultMocks::times([2]),
+    };
+    let state = State::new(3, 5).into();
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(5, None), true), res);
+    let spans = spans.0.lock().unwrap();
+    let (_, ancestors) = spans
+        .iter()
+        .find(|(name, _)| *name == "get_blocks")
+        .expect("The blocks are fetched in the spawned task");
+    assert_eq!(
+        ancestors,
+        &vec!["consensus_and_transactions", "launch_stream", "import"]
+    );
+}
+
+#[tokio::test]
+async fn import__local_headers_are_served_without_network_request() {
+    // given
+    let connected_peer: PeerId = vec![1].into();
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().times(0);
+    p2p.expect_peers_with_height().returning({
+        let peers = vec![connected_peer.clone()];
+        move |_| Ok(peers.clone())
+    });
+    p2p.expect_get_transactions()
+        .times(1)
+        .returning(move |block_ids| {
+            assert_eq!(block_ids.peer_id, connected_peer);
+            let data = block_ids.data;
+            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+    let mut executor: MockBlockImporterPort = DefaultMocks::times([2]);
+    executor
+        .expect_local_sealed_header()
+        .times(2)
+        .returning(|height| Ok(Some(empty_header(height))));
+
+    let state = State::new(3, 5).into();
+    let mocks = Mocks {
+        consensus_port: DefaultMocks::times([2, 1]),
+        p2p,
+        executor,
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        local_headers_first: true,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(5, None), true), res);
+}
+
+#[tokio::test]
+async fn import__local_transactions_are_served_without_network_request() {
+    // given
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .returning(|range| {
+            let peer = random_peer();
+            let headers = Some(range.map(empty_header).collect());
+            Ok(peer.bind(headers))
+        });
+    p2p.expect_get_transactions().times(0);
+    let mut executor: MockBlockImporterPort = DefaultMocks::times([2]);
+    executor
+        .expect_local_transactions()
+        .times(2)
+        .returning(|_| Ok(Some(Transactions::default())));
+
+    let state = State::new(3, 5).into();
+    let mocks = Mocks {
+        consensus_port: DefaultMocks::times([2, 1]),
+        p2p,
+        executor,
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        local_transactions_first: true,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(5, None), true), res);
+}
+
+#[tokio::test]
+async fn import__missing_local_header_falls_back_to_the_network() {
+    // given
+    let mut executor: MockBlockImporterPort = DefaultMocks::times([2]);
+    executor
+        .expect_local_sealed_header()
+        .returning(|height| Ok((*height == 4).then(|| empty_header(height))));
+
+    let state = State::new(3, 5).into();
+    let mocks = Mocks {
+        consensus_port: DefaultMocks::times([2, 1]),
+        p2p: DefaultMocks::times([1]),
+        executor,
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        local_headers_first: true,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(5, None), true), res);
+}
+
+#[tokio::test]
+async fn import__p2p_error_on_4_transactions() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(2)
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .returning(|range| {
+            let peer = random_peer();
+            let headers = Some(range.map(empty_header).collect());
+            let headers = peer.bind(headers);
+            Ok(headers)
+        });
+    p2p.expect_get_transactions()
+        .times(1)
+        .returning(|_| Err(anyhow::anyhow!("Some network error")));
+
+    let state = State::new(3, 5).into();
+    let mocks = Mocks {
+        p2p,
+        consensus_port,
+        executor: DefaultMocks::times([0]),
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(3, None).with_failed(4..=5), false), res);
+}
+
+#[tokio::test]
+async fn import__consensus_error_on_4() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(1)
+        .returning(|h| {
+            if **h.entity.height() == 4 {
+                Err(anyhow::anyhow!("Some consensus error"))
+            } else {
+                Ok(true)
+            }
+        });
+    consensus_port
+        .expect_await_da_height()
+        .times(0)
+        .returning(|_| Ok(()));
+
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .returning(|range| {
+            let peer = random_peer();
+            let headers = Some(range.map(empty_header).collect());
+            let headers = peer.bind(headers);
+            Ok(headers)
+        });
+    p2p.expect_get_transactions().times(0);
+
+    let state = State::new(3, 5).into();
+    let mocks = Mocks {
+        consensus_port,
+        p2p,
+        executor: DefaultMocks::times([0]),
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(3, None).with_failed(4..=5), false), res);
+}
+
+#[tokio::test]
+async fn import__consensus_error_on_5() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(2)
+        .returning(|h| {
+            if **h.entity.height() == 5 {
+                Err(anyhow::anyhow!("Some consensus error"))
+            } else {
+                Ok(true)
+            }
+        });
+    consensus_port
+        .expect_await_da_height()
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .returning(|range| {
+            let peer = random_peer();
+            let headers = Some(range.map(empty_header).collect());
+            let headers = peer.bind(headers);
+            Ok(headers)
+        });
+    p2p.expect_get_transactions()
+        .times(1)
+        .returning(|block_ids| {
+            let data = block_ids.data;
+            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+
+    let state = State::new(3, 5).into();
+    let mocks = Mocks {
+        consensus_port,
+        p2p,
+        executor: DefaultMocks::times([1]),
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(4, None).with_failed(5..=5), false), res);
+}
+
+#[tokio::test]
+async fn import__consensus_rejection_broadcasts_peer_report_event() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(1)
+        .returning(|_| Ok(false));
+    consensus_port.expect_await_da_height().times(0);
+
+    let peer = random_peer();
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().times(1).returning({
+        let peer = peer.clone();
+        move |range| {
+            let headers = Some(range.map(empty_header).collect());
+            Ok(peer.clone().bind(headers))
+        }
+    });
+    p2p.expect_get_transactions().times(0);
+    p2p.expect_report_peer()
+        .times(1)
+        .with(
+            mockall::predicate::always(),
+            mockall::predicate::eq(PeerReportReason::BadBlockHeader),
+        )
+        .returning(|_, _| Ok(()));
+
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(3, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(MockBlockImporterPort::times([0])),
+        Arc::new(consensus_port),
+    );
+    let mut events = import.subscribe_peer_reports();
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    // when
+    let _ = import.import_inner(&watcher).await;
+
+    // then
+    let expected = PeerReportEvent {
+        peer_id: peer,
+        reason: PeerReportReason::BadBlockHeader,
+        height: 4.into(),
+    };
+    assert_eq!(events.try_recv().unwrap(), expected);
+    assert!(events.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn import__execution_error_on_header_4() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(2)
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .returning(|range| {
+            let peer = random_peer();
+            let headers = Some(range.map(empty_header).collect());
+            let headers = peer.bind(headers);
+            Ok(headers)
+        });
+    p2p.expect_get_transactions()
+        .times(1)
+        .returning(|block_ids| {
+            let data = block_ids.data;
+            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+
+    let mut executor = MockBlockImporterPort::default();
+
+    executor.expect_can_accept().returning(|| true);
+    executor
+        .expect_execute_and_commit()
+        .times(1)
+        .returning(|h| {
+            if **h.entity.header().height() == 4 {
+                Err(anyhow::anyhow!("Some execution error"))
+            } else {
+                Ok(())
+            }
+        });
+
+    let state = State::new(3, 5).into();
+    let mocks = Mocks {
+        consensus_port,
+        p2p,
+        executor,
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(3, None).with_failed(4..=5), false), res);
+}
+
+#[tokio::test]
+async fn import__execution_error_on_header_5() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(2)
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .returning(|range| {
+            let peer = random_peer();
+            let headers = Some(range.map(empty_header).collect());
+            let headers = peer.bind(headers);
+            Ok(headers)
+        });
+    p2p.expect_get_transactions()
+        .times(1)
+        .returning(|block_ids| {
+            let data = block_ids.data;
+            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+
+    let mut executor = MockBlockImporterPort::default();
+
+    executor.expect_can_accept().returning(|| true);
+    executor
+        .expect_execute_and_commit()
+        .times(2)
+        .returning(|h| {
+            if **h.entity.header().height() == 5 {
+                Err(anyhow::anyhow!("Some execution error"))
+            } else {
+                Ok(())
+            }
+        });
+
+    let state = State::new(3, 5).into();
+    let mocks = Mocks {
+        consensus_port,
+        p2p,
+        executor,
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(4, None).with_failed(5..=5), false), res);
+}
+
+#[tokio::test]
+async fn signature_always_fails() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(1)
+        .returning(|_| Ok(false));
+    consensus_port.expect_await_da_height().times(0);
+
+    let state = State::new(3, 5).into();
+    let mocks = Mocks {
+        consensus_port,
+        p2p: DefaultMocks::times([0]),
+        executor: DefaultMocks::times([0]),
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(3, None).with_failed(4..=5), false), res);
+}
+
+#[tokio::test]
+async fn import__can_work_in_two_loops() {
+    // given
+    let s = SharedMutex::new(State::new(3, 5));
+    let state = s.clone();
+
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(3)
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .times(2)
+        .returning(|_| Ok(()));
+
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(2)
+        .returning(move |range| {
+            state.apply(|s| s.observe(6));
+            let peer = random_peer();
+            let headers = Some(range.map(empty_header).collect());
+            let headers = peer.bind(headers);
+            Ok(headers)
+        });
+    p2p.expect_get_transactions()
+        .times(2)
+        .returning(|block_ids| {
+            let data = block_ids.data;
+            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+
+    let c = DefaultMocks::times([2]);
+    let mocks = Mocks {
+        consensus_port,
+        p2p,
+        executor: DefaultMocks::times([3]),
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(s, mocks, Some(c), params).await;
+
+    // then
+    assert_eq!((State::new(6, None), true), res);
+}
+
+#[tokio::test]
+async fn import__skips_da_height_when_not_required() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(3)
+        .returning(|_| Ok(true));
+    consensus_port.expect_await_da_height().times(0);
+
+    let state = State::new(3, 6).into();
+    let mocks = Mocks {
+        consensus_port,
+        p2p: DefaultMocks::times([1]),
+        executor: DefaultMocks::times([3]),
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        require_da: false,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(state, mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(6, None), true), res);
+}
+
+#[tokio::test]
+async fn import__nothing_to_process_makes_no_p2p_calls() {
+    // given
+    let state = State::new(5, 5).into();
+    let mocks = Mocks {
+        consensus_port: DefaultMocks::times([0]),
+        p2p: MockPeerToPeerPort::default(),
+        executor: DefaultMocks::times([0]),
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+
+    // when
+    let res = tokio::time::timeout(
+        std::time::Duration::from_secs(1),
+        test_import_inner(state, mocks, None, params),
+    )
+    .await
+    .expect("Import should return promptly");
+
+    // then
+    assert_eq!((State::new(5, None), true), res);
+}
+
+#[tokio::test]
+async fn import__continues_without_external_notify_when_target_advances() {
+    // given
+    let s = SharedMutex::new(State::new(3, 5));
+    let state = s.clone();
+
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(3)
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .times(2)
+        .returning(|_| Ok(()));
+
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(2)
+        .returning(move |range| {
+            state.apply(|s| s.observe(6));
+            let peer = random_peer();
+            let headers = Some(range.map(empty_header).collect());
+            let headers = peer.bind(headers);
+            Ok(headers)
+        });
+    p2p.expect_get_transactions()
+        .times(2)
+        .returning(|block_ids| {
+            let data = block_ids.data;
+            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+    let import = Import::new(
+        s,
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(<MockBlockImporterPort as DefaultMocks>::times([3])),
+        Arc::new(consensus_port),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+
+    // when
+    let continued = tokio::time::timeout(
+        std::time::Duration::from_secs(1),
+        import.import(&mut watcher),
+    )
+    .await
+    .expect("Import should continue without an external notify")
+    .unwrap();
+    import.import_inner(&watcher).await.unwrap();
+
+    // then
+    assert!(continued);
+    assert_eq!(import.state.apply(|s| s.clone()), State::new(6, None));
+}
+
+/// Hangs on the first execution and commits all blocks after it.
+struct HangingOnceImporter(std::sync::atomic::AtomicBool);
+
+#[async_trait::async_trait]
+impl BlockImporterPort for HangingOnceImporter {
+    fn committed_height_stream(
+        &self,
+    ) -> fuel_core_services::stream::BoxStream<BlockHeight> {
+        use fuel_core_services::stream::IntoBoxStream;
+        futures::stream::pending().into_boxed()
+    }
+
+    async fn execute_and_commit(&self, _: SealedBlock) -> anyhow::Result<()> {
+        if !self.0.swap(true, std::sync::atomic::Ordering::SeqCst) {
+            futures::future::pending::<()>().await;
+        }
+        Ok(())
+    }
+
+    fn can_accept(&self) -> bool {
+        true
+    }
+
+    fn record_import_timings(
+        &self,
+        _: BlockHeight,
+        _: ImportTimings,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn local_sealed_header(
+        &self,
+        _: BlockHeight,
+    ) -> anyhow::Result<Option<SealedBlockHeader>> {
+        Ok(None)
+    }
+
+    fn local_transactions(&self, _: BlockHeight) -> anyhow::Result<Option<Transactions>> {
+        Ok(None)
+    }
+
+    async fn store_sealed_header(&self, _: SealedBlockHeader) -> anyhow::Result<()> {
+        unreachable!("The blocks are always imported with their transactions")
+    }
+}
+
+#[tokio::test]
+async fn import__execution_timeout_fails_the_height_and_sync_continues() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .times(4)
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .times(2)
+        .returning(|_| Ok(()));
+
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(2)
+        .returning(|range| {
+            let peer = random_peer();
+            let headers = Some(range.map(empty_header).collect());
+            let headers = peer.bind(headers);
+            Ok(headers)
+        });
+    p2p.expect_get_transactions()
+        .times(2)
+        .returning(|block_ids| {
+            let data = block_ids.data;
+            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+    p2p.expect_report_peer()
+        .times(1)
+        .with(
+            mockall::predicate::always(),
+            mockall::predicate::eq(PeerReportReason::SuccessfulBlockImport),
+        )
+        .returning(|_, _| Ok(()));
+
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        execution_timeout: Some(std::time::Duration::from_millis(100)),
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(3, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(HangingOnceImporter(Default::default())),
+        Arc::new(consensus_port),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    // when
+    let res = import.import_inner(&watcher).await;
+
+    // then
+    assert!(res.is_err());
+    assert_eq!(import.state.apply(|s| s.clone()), State::new(3, None).with_failed(4..=5));
+
+    // when
+    import.state.apply(|s| s.observe(5));
+    import.import_inner(&watcher).await.unwrap();
+
+    // then
+    assert_eq!(
+        import.state.apply(|s| s.clone()),
+        State::new(5, None).with_failed(4..=5)
+    );
+}
+
+/// Goes silent on the first request of transactions starting at the given height
+/// and serves all other requests.
+struct SilentOncePeers(u32, std::sync::atomic::AtomicBool);
+
+#[async_trait::async_trait]
+impl PeerToPeerPort for SilentOncePeers {
+    fn height_stream(&self) -> fuel_core_services::stream::BoxStream<BlockHeight> {
+        use fuel_core_services::stream::IntoBoxStream;
+        futures::stream::pending().into_boxed()
+    }
+
+    async fn get_sealed_block_headers(
+        &self,
+        block_height_range: Range<u32>,
+    ) -> anyhow::Result<SourcePeer<Option<Vec<SealedBlockHeader>>>> {
+        let headers = Some(block_height_range.map(empty_header).collect());
+        Ok(random_peer().bind(headers))
+    }
+
+    async fn get_sealed_block_headers_from_peer(
+        &self,
+        block_height_range: SourcePeer<Range<u32>>,
+    ) -> anyhow::Result<Option<Vec<SealedBlockHeader>>> {
+        Ok(Some(block_height_range.data.map(empty_header).collect()))
+    }
+
+    async fn peers_with_height(&self, _: BlockHeight) -> anyhow::Result<Vec<PeerId>> {
+        Ok(vec![])
+    }
+
+    async fn get_transactions(
+        &self,
+        block_ids: SourcePeer<Range<u32>>,
+    ) -> anyhow::Result<Option<Vec<Transactions>>> {
+        let data = block_ids.data;
+        if data.start == self.0 && !self.1.swap(true, std::sync::atomic::Ordering::SeqCst)
+        {
+            futures::future::pending::<()>().await;
+        }
+        Ok(Some(data.map(|_| Transactions::default()).collect()))
+    }
+
+    async fn chain_config_hash(&self, _: PeerId) -> anyhow::Result<Option<Bytes32>> {
+        Ok(None)
+    }
+
+    fn report_peer(&self, _: PeerId, _: PeerReportReason) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn import__stalled_stream_restarts_from_the_last_committed_height() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .returning(|_| Ok(()));
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    executor.expect_execute_and_commit().returning(|_| Ok(()));
+
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 2,
+        stream_stall_timeout: Some(std::time::Duration::from_secs(10)),
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(SilentOncePeers(2, Default::default())),
+        Arc::new(executor),
+        Arc::new(consensus_port),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    // when
+    let res = import.import_inner(&watcher).await;
+
+    // then
+    assert!(res.is_ok());
+    assert_eq!(import.state.apply(|s| s.clone()), State::new(5, None));
+}
+
+/// Takes the given time to execute each block and keeps the recorded timings.
+struct SlowImporter(
+    Duration,
+    std::sync::Mutex<Vec<(BlockHeight, ImportTimings)>>,
+);
+
+#[async_trait::async_trait]
+impl BlockImporterPort for SlowImporter {
+    fn committed_height_stream(
+        &self,
+    ) -> fuel_core_services::stream::BoxStream<BlockHeight> {
+        use fuel_core_services::stream::IntoBoxStream;
+        futures::stream::pending().into_boxed()
+    }
+
+    async fn execute_and_commit(&self, _: SealedBlock) -> anyhow::Result<()> {
+        tokio::time::sleep(self.0).await;
+        Ok(())
+    }
+
+    fn can_accept(&self) -> bool {
+        true
+    }
+
+    fn record_import_timings(
+        &self,
+        height: BlockHeight,
+        timings: ImportTimings,
+    ) -> anyhow::Result<()> {
+        self.1.lock().unwrap().push((height, timings));
+        Ok(())
+    }
+
+    fn local_sealed_header(
+        &self,
+        _: BlockHeight,
+    ) -> anyhow::Result<Option<SealedBlockHeader>> {
+        Ok(None)
+    }
+
+    fn local_transactions(&self, _: BlockHeight) -> anyhow::Result<Option<Transactions>> {
+        Ok(None)
+    }
+
+    async fn store_sealed_header(&self, _: SealedBlockHeader) -> anyhow::Result<()> {
+        unreachable!("The blocks are always imported with their transactions")
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn import__records_the_timings_of_each_committed_block() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .returning(|_| Ok(()));
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning(|range| {
+        let headers = Some(range.map(empty_header).collect());
+        Ok(random_peer().bind(headers))
+    });
+    p2p.expect_get_transactions().returning(|block_ids| {
+        let data = block_ids.data;
+        let v = data.into_iter().map(|_| Transactions::default()).collect();
+        Ok(Some(v))
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+
+    let execution_time = Duration::from_millis(100);
+    let executor = Arc::new(SlowImporter(execution_time, Default::default()));
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 2,
+        record_import_timings: true,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 3)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        executor.clone(),
+        Arc::new(consensus_port),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    // when
+    import.import_inner(&watcher).await.unwrap();
+
+    // then
+    let recorded = executor.1.lock().unwrap().clone();
+    let heights: Vec<_> = recorded.iter().map(|(height, _)| **height).collect();
+    assert_eq!(heights, vec![0, 1, 2, 3]);
+    for (_, timings) in recorded {
+        assert_eq!(timings.execute, execution_time);
+        // The network mock responds immediately, and the time is paused.
+        assert_eq!(timings.fetch, Duration::ZERO);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn import__stops_at_the_deadline_with_the_partial_summary() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .returning(|_| Ok(()));
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning(|range| {
+        let headers = Some(range.map(empty_header).collect());
+        Ok(random_peer().bind(headers))
+    });
+    p2p.expect_get_transactions().returning(|block_ids| {
+        let data = block_ids.data;
+        let v = data.into_iter().map(|_| Transactions::default()).collect();
+        Ok(Some(v))
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+
+    let execution_time = Duration::from_millis(100);
+    let max_import_duration = Duration::from_millis(250);
+    let executor = Arc::new(SlowImporter(execution_time, Default::default()));
+    let params = Config {
+        block_stream_buffer_size: 1,
+        header_batch_size: 1,
+        max_import_duration: Some(max_import_duration),
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 9)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        executor,
+        Arc::new(consensus_port),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+    let started_at = tokio::time::Instant::now();
+
+    // when
+    let res = import.import_inner(&watcher).await;
+
+    // then
+    assert!(res.is_ok());
+    let elapsed = started_at.elapsed();
+    assert!(elapsed >= max_import_duration);
+    // Only the batch in flight at the deadline is committed after it.
+    assert!(elapsed <= max_import_duration + execution_time);
+    let summary = import.last_summary().expect("The stream was launched");
+    assert!(summary.deadline_reached);
+    assert_eq!(summary.range, 0..=9);
+    assert!(summary.committed > 0 && summary.committed < 10);
+    let committed_height = import.state.apply(|s| s.committed_height());
+    let committed = u32::try_from(summary.committed).unwrap();
+    assert_eq!(committed_height, Some(committed - 1));
+    // The rest of the range is left for the next iteration, not failed.
+    assert!(import.state.apply(|s| s.failed_heights().is_empty()));
+}
+
+#[tokio::test(start_paused = true)]
+async fn import__converges_with_the_injected_faults() {
+    // given
+    let consensus_port: MockConsensusPort = DefaultMocks::times([5, 1]);
+    let mut p2p: MockPeerToPeerPort = DefaultMocks::times([1]);
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    // The first request of the headers fails, its retry is delayed.
+    let p2p = Arc::new(FaultInjector::new(
+        p2p,
+        FaultSchedule::Fixed(vec![
+            Some(Fault::Fail),
+            Some(Fault::Delay(Duration::from_millis(50))),
+            None,
+        ]),
+    ));
+    let executor: MockBlockImporterPort = DefaultMocks::times([5]);
+    let executor = Arc::new(FaultInjector::new(
+        executor,
+        FaultSchedule::Fixed(vec![Some(Fault::Delay(Duration::from_millis(10)))]),
+    ));
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        retry_policy: RetryPolicy {
+            max_attempts: 3,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 4)),
+        Arc::new(Notify::new()),
+        params,
+        p2p.clone(),
+        executor.clone(),
+        Arc::new(consensus_port),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    // when
+    let res = import.import_inner(&watcher).await;
+
+    // then
+    assert!(res.is_ok());
+    assert_eq!(import.state.apply(|s| s.clone()), State::new(4, None));
+    assert_eq!(p2p.injected(), 2);
+    assert_eq!(executor.injected(), 5);
+}
+
+/// Takes the given time to sync each DA height.
+struct SlowDaConsensus(Duration);
+
+#[async_trait::async_trait]
+impl ConsensusPort for SlowDaConsensus {
+    fn check_sealed_header(&self, _: &SealedBlockHeader) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+
+    async fn await_da_height(
+        &self,
+        _: &fuel_core_types::blockchain::primitives::DaBlockHeight,
+    ) -> anyhow::Result<()> {
+        tokio::time::sleep(self.0).await;
+        Ok(())
+    }
+
+    fn da_tip(
+        &self,
+    ) -> anyhow::Result<Option<fuel_core_types::blockchain::primitives::DaBlockHeight>>
+    {
+        Ok(None)
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn import__records_the_delay_in_the_timing_of_the_stage_it_happened() {
+    // given
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning(|range| {
+        let headers = Some(range.map(empty_header).collect());
+        Ok(random_peer().bind(headers))
+    });
+    p2p.expect_get_transactions().returning(|block_ids| {
+        let data = block_ids.data;
+        let v = data.into_iter().map(|_| Transactions::default()).collect();
+        Ok(Some(v))
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+
+    let da_delay = Duration::from_millis(300);
+    let executor = Arc::new(SlowImporter(Duration::ZERO, Default::default()));
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 2,
+        record_import_timings: true,
+        require_da: true,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 3)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        executor.clone(),
+        Arc::new(SlowDaConsensus(da_delay)),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    // when
+    import.import_inner(&watcher).await.unwrap();
+
+    // then
+    let recorded = executor.1.lock().unwrap().clone();
+    assert_eq!(recorded.len(), 4);
+    for (_, timings) in recorded {
+        assert_eq!(timings.da_wait, da_delay);
+        assert_eq!(timings.header_fetch, Duration::ZERO);
+        assert_eq!(timings.consensus_check, Duration::ZERO);
+        assert_eq!(timings.transaction_fetch, Duration::ZERO);
+        assert_eq!(timings.execute, Duration::ZERO);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn import__cancelled_height_is_never_executed() {
+    // given
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning(|range| {
+        let headers = Some(range.map(empty_header).collect());
+        Ok(random_peer().bind(headers))
+    });
+    p2p.expect_get_transactions().returning(|block_ids| {
+        let data = block_ids.data;
+        let v = data.into_iter().map(|_| Transactions::default()).collect();
+        Ok(Some(v))
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    executor
+        .expect_execute_and_commit()
+        .times(2)
+        .returning(|block| {
+            let height = **block.entity.header().height();
+            assert!(height < 2, "The cancelled height {height} is executed");
+            Ok(())
+        });
+
+    // The download of each batch waits for the DA height long enough to cancel it.
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 2,
+        require_da: true,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 3)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(SlowDaConsensus(Duration::from_secs(1))),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    // when
+    let cancel = async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        import.cancel_height(2u32.into());
+    };
+    let (result, _) = tokio::join!(import.import_inner(&watcher), cancel);
+
+    // then
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn import__cancel_height_between_streams_is_ignored() {
+    // given
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning(|range| {
+        let headers = Some(range.map(empty_header).collect());
+        Ok(random_peer().bind(headers))
+    });
+    p2p.expect_get_transactions().returning(|block_ids| {
+        let v = block_ids.data.map(|_| Transactions::default()).collect();
+        Ok(Some(v))
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    executor
+        .expect_execute_and_commit()
+        .times(3)
+        .returning(|_| Ok(()));
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 2,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 2)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(MockConsensusPort::times([3, 2])),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+    import.cancel_height(2u32.into());
+
+    // when
+    let result = import.import_inner(&watcher).await;
+
+    // then
+    assert!(result.is_ok());
+    assert_eq!(import.state.apply(|s| s.committed_height()), Some(2));
+}
+
+#[tokio::test]
+async fn import__header_far_ahead_of_the_da_tip_is_rejected_without_waiting() {
+    // given
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_da_tip()
+        .returning(|| Ok(Some(10u64.into())));
+    consensus_port.expect_await_da_height().times(0);
+
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning(|range| {
+        let headers = range
+            .map(|height| {
+                let mut header = empty_header(height);
+                header.entity.set_da_height(1000u64.into());
+                header
+            })
+            .collect();
+        Ok(random_peer().bind(Some(headers)))
+    });
+    p2p.expect_get_transactions().times(0);
+    p2p.expect_report_peer()
+        .withf(|_, reason| *reason == PeerReportReason::BadBlockHeader)
+        .times(1)
+        .returning(|_, _| Ok(()));
+
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    executor.expect_execute_and_commit().times(0);
+
+    let params = Config {
+        max_da_lookahead: Some(100),
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 3)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(consensus_port),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    // when
+    let result = import.import_inner(&watcher).await;
+
+    // then
+    assert!(result.is_err());
+}
+
+fn header_with_transactions(
+    height: u32,
+    transactions: &[fuel_core_types::fuel_tx::Transaction],
+) -> SealedBlockHeader {
+    let mut header = empty_header(height);
+    let mut root =
+        fuel_core_types::fuel_merkle::binary::root_calculator::MerkleRootCalculator::new(
+        );
+    for tx in transactions {
+        root.push(tx.to_bytes().as_ref());
+    }
+    let mut application = *header.entity.application();
+    application.generated.transactions_count = transactions.len() as u64;
+    application.generated.transactions_root = root.root().into();
+    header.entity.set_application_header(application);
+    header
+}
+
+#[tokio::test]
+async fn import__malformed_transaction_is_rejected_before_the_execution() {
+    // given
+    let transactions =
+        vec![
+            fuel_core_types::fuel_tx::TransactionBuilder::script(vec![], vec![])
+                .finalize_as_transaction(),
+        ];
+    let peer = random_peer();
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().times(1).returning({
+        let peer = peer.clone();
+        let transactions = transactions.clone();
+        move |range| {
+            let headers = range
+                .map(|height| header_with_transactions(height, &transactions))
+                .collect();
+            Ok(peer.clone().bind(Some(headers)))
+        }
+    });
+    p2p.expect_get_transactions().times(1).returning({
+        let transactions = transactions.clone();
+        move |block_ids| {
+            let v = block_ids
+                .data
+                .map(|_| Transactions(transactions.clone()))
+                .collect();
+            Ok(Some(v))
+        }
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    // The transaction of the block 5 is malformed.
+    let mut checker = MockTransactionChecker::default();
+    checker.expect_check_transaction().returning(|height, _| {
+        if *height == 5 {
+            Err(anyhow::anyhow!("Malformed transaction"))
+        } else {
+            Ok(())
+        }
+    });
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(3, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(<MockBlockImporterPort as DefaultMocks>::times([1])),
+        Arc::new(<MockConsensusPort as DefaultMocks>::times([2, 1])),
+    )
+    .with_transaction_checker(Arc::new(checker));
+    let mut events = import.subscribe_peer_reports();
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    // when
+    let result = import.import_inner(&watcher).await;
+
+    // then
+    assert!(result.is_err());
+    assert_eq!(import.state.apply(|s| s.committed_height()), Some(4));
+    let expected = PeerReportEvent {
+        peer_id: peer,
+        reason: PeerReportReason::InvalidTransactions,
+        height: 5.into(),
+    };
+    assert_eq!(events.try_recv().unwrap(), expected);
+}
+
+#[tokio::test]
+async fn import__buffered_tx_bytes_stay_under_the_limit() {
+    // given
+    let transactions = vec![fuel_core_types::fuel_tx::TransactionBuilder::script(
+        vec![],
+        vec![0; 1_000],
+    )
+    .finalize_as_transaction()];
+    let block_size: usize = transactions.iter().map(|tx| tx.size()).sum();
+    let batch_size = 2;
+    let limit = 4 * block_size;
+    // The currently buffered and the maximum buffered size.
+    let buffered = Arc::new(std::sync::Mutex::new((0usize, 0usize)));
+
+    let mut consensus_port = MockConsensusPort::default();
+    consensus_port
+        .expect_check_sealed_header()
+        .returning(|_| Ok(true));
+    consensus_port
+        .expect_await_da_height()
+        .returning(|_| Ok(()));
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning({
+        let transactions = transactions.clone();
+        move |range| {
+            let headers = range
+                .map(|height| header_with_transactions(height, &transactions))
+                .collect();
+            Ok(random_peer().bind(Some(headers)))
+        }
+    });
+    p2p.expect_get_transactions().returning({
+        let transactions = transactions.clone();
+        let buffered = buffered.clone();
+        move |block_ids| {
+            let data = block_ids.data;
+            let mut buffered = buffered.lock().unwrap();
+            buffered.0 += data.len() * block_size;
+            buffered.1 = buffered.1.max(buffered.0);
+            let v = data.map(|_| Transactions(transactions.clone())).collect();
+            Ok(Some(v))
+        }
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    executor.expect_execute_and_commit().returning({
+        let buffered = buffered.clone();
+        move |_| {
+            buffered.lock().unwrap().0 -= block_size;
+            Ok(())
+        }
+    });
+
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: batch_size,
+        max_buffered_tx_bytes: Some(limit),
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 19)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(consensus_port),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    // when
+    import.import_inner(&watcher).await.unwrap();
+
+    // then
+    assert_eq!(import.state.apply(|s| s.clone()), State::new(19, None));
+    let (_, max_buffered) = *buffered.lock().unwrap();
+    // The size of the batch is unknown before it is fetched,
+    // so the limit can be exceeded by at most one batch.
+    assert!(max_buffered < limit + batch_size * block_size);
+}
+
+async fn test_import_inner(
+    state: SharedMutex<State>,
+    mocks: Mocks,
+    count: Option<Count>,
+    params: Config,
+) -> (State, bool) {
+    let notify = Arc::new(Notify::new());
+    let Mocks {
+        consensus_port,
+        mut p2p,
+        executor,
+    } = mocks;
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let p2p = Arc::new(p2p);
+
+    let executor = Arc::new(executor);
+    let consensus = Arc::new(consensus_port);
+
+    let import = Import::new(state, notify, params, p2p, executor, consensus);
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+    let received_notify_signal = match count {
+        Some(Count(count)) => {
+            let mut r = false;
+            for _ in 0..count {
+                import.notify.notify_one();
+                r = import.import(&mut watcher).await.is_ok();
+                if !r {
+                    break
+                }
+            }
+            r
+        }
+        None => {
+            import.notify.notify_one();
+            import.import(&mut watcher).await.is_ok()
+        }
+    };
+    let final_state = import.state.apply(|s| s.clone());
+    (final_state, received_notify_signal)
+}
+
+#[tokio::test]
+async fn import__on_committed_is_called_once_per_committed_block() {
+    // given
+    let committed = Arc::new(std::sync::Mutex::new(vec![]));
+    let on_committed: OnCommitted = {
+        let committed = committed.clone();
+        Arc::new(move |block: &SealedBlock| {
+            committed
+                .lock()
+                .unwrap()
+                .push(**block.entity.header().height());
+        })
+    };
+    let mut p2p: MockPeerToPeerPort = DefaultMocks::times([1]);
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(3, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(<MockBlockImporterPort as DefaultMocks>::times([2])),
+        Arc::new(<MockConsensusPort as DefaultMocks>::times([2, 1])),
+    )
+    .with_on_committed(on_committed);
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+
+    // when
+    import.notify_one();
+    import.import(&mut watcher).await.unwrap();
+
+    // then
+    assert_eq!(*committed.lock().unwrap(), vec![4, 5]);
+}
+
+#[tokio::test]
+async fn import__accumulator_receives_heights_in_order_without_gaps() {
+    // given
+    let accumulated = Arc::new(std::sync::Mutex::new(vec![]));
+    let accumulator: Accumulator = {
+        let accumulated = accumulated.clone();
+        Arc::new(move |height: BlockHeight, _: BlockId| {
+            accumulated.lock().unwrap().push(*height);
+        })
+    };
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning(|range| {
+        let headers = Some(range.map(empty_header).collect());
+        Ok(random_peer().bind(headers))
+    });
+    p2p.expect_get_transactions().returning(|block_ids| {
+        let data = block_ids.data;
+        let v = data.into_iter().map(|_| Transactions::default()).collect();
+        Ok(Some(v))
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    // The downloads of the pipelined batches complete out of order.
+    let p2p = FaultInjector::new(
+        p2p,
+        FaultSchedule::Random {
+            probability: 0.5,
+            fault: Fault::Delay(Duration::from_millis(10)),
+            seed: 7,
+        },
+    );
+    let mut consensus = MockConsensusPort::default();
+    consensus
+        .expect_check_sealed_header()
+        .returning(|_| Ok(true));
+    consensus.expect_await_da_height().returning(|_| Ok(()));
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    executor.expect_execute_and_commit().returning(|_| Ok(()));
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 3,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 29)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(consensus),
+    )
+    .with_accumulator(accumulator);
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+
+    // when
+    import.import_inner(&watcher).await.unwrap();
+
+    // then
+    assert_eq!(*accumulated.lock().unwrap(), (0..=29).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn import__on_caught_up_is_called_once_at_the_tip() {
+    // given
+    let caught_up = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let on_caught_up: OnCaughtUp = {
+        let caught_up = caught_up.clone();
+        Arc::new(move || {
+            caught_up.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        })
+    };
+    let mut p2p: MockPeerToPeerPort = DefaultMocks::times([1]);
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(3, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(<MockBlockImporterPort as DefaultMocks>::times([2])),
+        Arc::new(<MockConsensusPort as DefaultMocks>::times([2, 1])),
+    )
+    .with_on_caught_up(on_caught_up);
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+
+    // when
+    import.notify_one();
+    import.import(&mut watcher).await.unwrap();
+
+    // then
+    assert_eq!(caught_up.load(std::sync::atomic::Ordering::Relaxed), 1);
+}
+
+#[tokio::test]
+async fn import__range_overlapping_with_the_range_in_flight_is_rejected() {
+    // given
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().never();
+    let params = Config {
+        overlap_policy: OverlapPolicy::Reject,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(3, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(<MockBlockImporterPort as DefaultMocks>::times([0])),
+        Arc::new(<MockConsensusPort as DefaultMocks>::times([0])),
+    );
+    import.in_flight.apply(|in_flight| *in_flight = Some(4..=4));
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+
+    // when
+    import.notify_one();
+    let result = import.import(&mut watcher).await;
+
+    // then
+    assert!(result.is_err());
+    assert_eq!(import.state.apply(|s| s.clone()), State::new(3, 5));
+}
+
+#[tokio::test]
+async fn import__range_overlapping_with_the_range_in_flight_is_clamped() {
+    // given
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .withf(|range| *range == (5..6))
+        .returning(|range| {
+            let peer = random_peer();
+            let headers = Some(range.map(empty_header).collect());
+            Ok(peer.bind(headers))
+        });
+    p2p.expect_get_transactions()
+        .times(1)
+        .returning(|block_ids| {
+            let v = block_ids.data.map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let params = Config {
+        overlap_policy: OverlapPolicy::Clamp,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(3, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(<MockBlockImporterPort as DefaultMocks>::times([1])),
+        Arc::new(<MockConsensusPort as DefaultMocks>::times([1, 1])),
+    );
+    import.in_flight.apply(|in_flight| *in_flight = Some(4..=4));
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+
+    // when
+    import.notify_one();
+    import.import(&mut watcher).await.unwrap();
+
+    // then
+    assert_eq!(import.state.apply(|s| s.clone()), State::new(5, None));
+}
+
+/// Accepts the headers with the proof equal to their id.
+struct ProofVerifier {
+    proofs: HashMap<BlockHeight, BlockId>,
+}
+
+impl FinalityVerifier for ProofVerifier {
+    fn verify_finality(&self, header: &SealedBlockHeader) -> anyhow::Result<bool> {
+        let proof = self.proofs.get(header.entity.height());
+        Ok(proof == Some(&header.entity.id()))
+    }
+}
+
+#[test_case::test_case(true => State::new(5, None); "supplied")]
+#[test_case::test_case(false => State::new(4, None).with_failed(5..=5); "withheld")]
+#[tokio::test]
+async fn import__header_without_the_finality_proof_is_rejected(
+    proof_supplied: bool,
+) -> State {
+    // given
+    let mut proofs = HashMap::new();
+    proofs.insert(4u32.into(), empty_header(4u32).entity.id());
+    if proof_supplied {
+        proofs.insert(5u32.into(), empty_header(5u32).entity.id());
+    }
+    let mut p2p: MockPeerToPeerPort = DefaultMocks::times([1]);
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+    let committed_blocks = if proof_supplied { 2 } else { 1 };
+    let executor: MockBlockImporterPort = DefaultMocks::times([committed_blocks]);
+    let import = Import::new(
+        SharedMutex::new(State::new(3, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(<MockConsensusPort as DefaultMocks>::times([2, 1])),
+    )
+    .with_finality_verifier(Arc::new(ProofVerifier { proofs }));
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+
+    // when
+    import.notify_one();
+    let result = import.import(&mut watcher).await;
+
+    // then
+    assert_eq!(result.is_ok(), proof_supplied);
+    import.state.apply(|s| s.clone())
+}
+
+/// Always chooses the same peer.
+struct FixedPeerSelector(PeerId);
+
+impl PeerSelector for FixedPeerSelector {
+    fn choose(&self, candidates: &[PeerId], _: BlockHeight) -> PeerId {
+        assert!(candidates.contains(&self.0));
+        self.0.clone()
+    }
+}
+
+#[tokio::test]
+async fn import__headers_are_requested_from_the_selected_peer() {
+    // given
+    let chosen_peer: PeerId = vec![1].into();
+    let other_peer: PeerId = vec![2].into();
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_peers_with_height().returning({
+        let peers = vec![other_peer, chosen_peer.clone()];
+        move |_| Ok(peers.clone())
+    });
+    p2p.expect_get_sealed_block_headers_from_peer()
+        .times(2)
+        .withf({
+            let chosen_peer = chosen_peer.clone();
+            move |range| range.peer_id == chosen_peer
+        })
+        .returning(|range| Ok(Some(range.data.map(empty_header).collect())));
+    p2p.expect_get_transactions()
+        .times(2)
+        .withf({
+            let chosen_peer = chosen_peer.clone();
+            move |block_ids| block_ids.peer_id == chosen_peer
+        })
+        .returning(|block_ids| {
+            let v = block_ids.data.map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 1,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(3, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(<MockBlockImporterPort as DefaultMocks>::times([2])),
+        Arc::new(<MockConsensusPort as DefaultMocks>::times([2, 2])),
+    )
+    .with_peer_selector(Arc::new(FixedPeerSelector(chosen_peer)));
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+
+    // when
+    import.notify_one();
+    let res = import.import(&mut watcher).await;
+
+    // then
+    assert!(res.is_ok());
+    assert_eq!(import.state.apply(|s| s.clone()), State::new(5, None));
+}
+
+/// Commits blocks only when the test allows it.
+struct GatedBlockImporter(Arc<tokio::sync::Semaphore>);
+
+#[async_trait::async_trait]
+impl BlockImporterPort for GatedBlockImporter {
+    fn committed_height_stream(
+        &self,
+    ) -> fuel_core_services::stream::BoxStream<BlockHeight> {
+        use fuel_core_services::stream::IntoBoxStream;
+        futures::stream::pending().into_boxed()
+    }
+
+    async fn execute_and_commit(&self, _: SealedBlock) -> anyhow::Result<()> {
+        self.0.acquire().await?.forget();
+        Ok(())
+    }
+
+    fn can_accept(&self) -> bool {
+        true
+    }
+
+    fn record_import_timings(
+        &self,
+        _: BlockHeight,
+        _: ImportTimings,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn local_sealed_header(
+        &self,
+        _: BlockHeight,
+    ) -> anyhow::Result<Option<SealedBlockHeader>> {
+        Ok(None)
+    }
+
+    fn local_transactions(&self, _: BlockHeight) -> anyhow::Result<Option<Transactions>> {
+        Ok(None)
+    }
+
+    async fn store_sealed_header(&self, _: SealedBlockHeader) -> anyhow::Result<()> {
+        unreachable!("The blocks are always imported with their transactions")
+    }
+}
+
+#[tokio::test]
+async fn wait_until_height__resolves_when_target_height_is_committed() {
+    // given
+    let gate = Arc::new(tokio::sync::Semaphore::new(0));
+    let mut p2p: MockPeerToPeerPort = DefaultMocks::times([1]);
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+    let import = Arc::new(Import::new(
+        SharedMutex::new(State::new(None, 9)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(GatedBlockImporter(gate.clone())),
+        Arc::new(<MockConsensusPort as DefaultMocks>::times([10, 1])),
+    ));
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut import_watcher: StateWatcher = shutdown.clone().into();
+    let mut wait_watcher: StateWatcher = shutdown.into();
+    tokio::spawn({
+        let import = import.clone();
+        async move {
+            import.notify_one();
+            let _ = import.import(&mut import_watcher).await;
+        }
+    });
+    let waiter = tokio::spawn({
+        let import = import.clone();
+        async move {
+            import
+                .wait_until_height(5u32.into(), &mut wait_watcher)
+                .await
+        }
+    });
+
+    // when
+    gate.add_permits(5);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    // then
+    assert!(!waiter.is_finished());
+    assert_eq!(import.state.apply(|s| s.committed_height()), Some(4));
+
+    // when
+    gate.add_permits(1);
+
+    // then
+    let reached = tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+        .await
+        .expect("Should resolve after the height is committed")
+        .unwrap()
+        .unwrap();
+    assert!(reached);
+    assert_eq!(import.state.apply(|s| s.committed_height()), Some(5));
+}
+
+#[tokio::test]
+async fn inflight__counts_the_stalled_execution() {
+    // given
+    let gate = Arc::new(tokio::sync::Semaphore::new(0));
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning(|range| {
+        let headers = Some(range.map(empty_header).collect());
+        Ok(random_peer().bind(headers))
+    });
+    p2p.expect_get_transactions().returning(|block_ids| {
+        let data = block_ids.data;
+        let v = data.into_iter().map(|_| Transactions::default()).collect();
+        Ok(Some(v))
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let mut consensus = MockConsensusPort::default();
+    consensus
+        .expect_check_sealed_header()
+        .returning(|_| Ok(true));
+    consensus.expect_await_da_height().returning(|_| Ok(()));
+    let params = Config {
+        block_stream_buffer_size: 2,
+        header_batch_size: 2,
+        ..Default::default()
+    };
+    let import = Arc::new(Import::new(
+        SharedMutex::new(State::new(None, 19)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(GatedBlockImporter(gate.clone())),
+        Arc::new(consensus),
+    ));
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher: StateWatcher = shutdown.into();
+    tokio::spawn({
+        let import = import.clone();
+        async move {
+            import.notify_one();
+            let _ = import.import(&mut watcher).await;
+        }
+    });
+
+    // when
+    gate.add_permits(3);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // then
+    let inflight = import.inflight();
+    assert_eq!(import.state.apply(|s| s.committed_height()), Some(2));
+    assert_eq!(inflight.executions, 1);
+    assert!(inflight.header_fetches <= params.block_stream_buffer_size);
+    assert!(inflight.transaction_fetches <= params.block_stream_buffer_size);
+}
+
+#[tokio::test]
+async fn import__peer_missing_transactions_stops_being_chosen_after_the_threshold() {
+    // given
+    let headers_only_peer = PeerId::from(vec![1]);
+    let full_peer = PeerId::from(vec![2]);
+    let requested = Arc::new(std::sync::Mutex::new(vec![]));
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning({
+        let headers_only_peer = headers_only_peer.clone();
+        move |range| {
+            let headers = Some(range.map(empty_header).collect());
+            Ok(headers_only_peer.clone().bind(headers))
+        }
+    });
+    p2p.expect_peers_with_height().returning({
+        let peers = vec![headers_only_peer.clone(), full_peer.clone()];
+        move |_| Ok(peers.clone())
+    });
+    p2p.expect_get_transactions().returning({
+        let requested = requested.clone();
+        let headers_only_peer = headers_only_peer.clone();
+        move |block_ids| {
+            requested.lock().unwrap().push(block_ids.peer_id.clone());
+            if block_ids.peer_id == headers_only_peer {
+                return Ok(None)
+            }
+            let data = block_ids.data;
+            let v = data.into_iter().map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        }
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let mut consensus = MockConsensusPort::default();
+    consensus
+        .expect_check_sealed_header()
+        .returning(|_| Ok(true));
+    consensus.expect_await_da_height().returning(|_| Ok(()));
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    executor.expect_execute_and_commit().returning(|_| Ok(()));
+    let params = Config {
+        block_stream_buffer_size: 1,
+        header_batch_size: 2,
+        missing_transactions_threshold: Some(2),
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(consensus),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+
+    // when
+    for _ in 0..3 {
+        import.reset_failed();
+        import.notify_one();
+        let _ = import.import(&mut watcher).await;
+    }
+
+    // then
+    assert_eq!(
+        *requested.lock().unwrap(),
+        vec![
+            headers_only_peer.clone(),
+            headers_only_peer,
+            full_peer.clone(),
+            full_peer.clone(),
+            full_peer,
+        ]
+    );
+    assert_eq!(import.state.apply(|s| s.committed_height()), Some(5));
+}
+
+#[tokio::test]
+async fn import__refuses_the_reorg_deeper_than_the_limit() {
+    // given
+    let max_reorg_depth = 4;
+    let tip = 9;
+    let fork_point = 4;
+
+    // when
+    let (result, committed) =
+        import_from_fork(fork_point, tip, max_reorg_depth, true).await;
+
+    // then
+    let err = result.expect_err("The deep reorg should be refused");
+    assert_eq!(
+        err.downcast_ref::<ReorgTooDeep>(),
+        Some(&ReorgTooDeep {
+            diverged_at: 5,
+            tip: 9,
+            max_reorg_depth: 4,
+        })
+    );
+    assert_eq!(committed, Some(9));
+}
+
+#[tokio::test]
+async fn import__accepts_the_reorg_within_the_limit() {
+    // given
+    let max_reorg_depth = 4;
+    let tip = 9;
+    let fork_point = 5;
+
+    // when
+    let (result, committed) =
+        import_from_fork(fork_point, tip, max_reorg_depth, true).await;
+
+    // then
+    assert!(result.is_ok());
+    assert_eq!(committed, Some(15));
+}
+
+#[tokio::test]
+async fn import__skips_the_reorg_check_when_the_serving_peer_fails_to_respond() {
+    // given
+    let max_reorg_depth = 4;
+    let tip = 9;
+    let fork_point = 4;
+
+    // when
+    let (result, committed) =
+        import_from_fork(fork_point, tip, max_reorg_depth, false).await;
+
+    // then
+    assert!(result.is_ok());
+    assert_eq!(committed, Some(15));
+}
+
+/// Imports the heights up to 15 from the network, which chain forks from the local one
+/// with the committed `tip` at the `fork_point`. The serving peer answers the request
+/// for the header at the deepest allowed fork point only if `serves_fork_point`.
+/// Returns the result of the import and the committed height after it.
+async fn import_from_fork(
+    fork_point: u32,
+    tip: u32,
+    max_reorg_depth: u32,
+    serves_fork_point: bool,
+) -> (anyhow::Result<()>, Option<u32>) {
+    let forked_header = move |height: u32| {
+        let mut header = empty_header(height);
+        if height > fork_point {
+            header.entity.set_previous_root([1; 32].into());
+            header.entity.recalculate_metadata();
+        }
+        header
+    };
+    let serving_peer = random_peer();
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning({
+        let serving_peer = serving_peer.clone();
+        move |range| {
+            let headers = Some(range.map(forked_header).collect());
+            Ok(serving_peer.clone().bind(headers))
+        }
+    });
+    p2p.expect_get_sealed_block_headers_from_peer()
+        .withf(move |range| range.peer_id == serving_peer)
+        .returning(move |range| {
+            if serves_fork_point {
+                Ok(Some(range.data.map(forked_header).collect()))
+            } else {
+                Err(anyhow::anyhow!("Some network error"))
+            }
+        });
+    p2p.expect_get_transactions().returning(|block_ids| {
+        let data = block_ids.data;
+        let v = data.into_iter().map(|_| Transactions::default()).collect();
+        Ok(Some(v))
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let mut consensus = MockConsensusPort::default();
+    consensus
+        .expect_check_sealed_header()
+        .returning(|_| Ok(true));
+    consensus.expect_await_da_height().returning(|_| Ok(()));
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    executor
+        .expect_local_sealed_header()
+        .returning(|height| Ok(Some(empty_header(height))));
+    executor.expect_execute_and_commit().returning(|_| Ok(()));
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        max_reorg_depth: Some(max_reorg_depth),
+        ..Default::default()
     };
+    let import = Import::new(
+        SharedMutex::new(State::new(tip, 15)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(consensus),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
 
-    // when
-    let res = test_import_inner(state, mocks, None, params).await;
+    let result = import.import_inner(&watcher).await;
 
-    // then
-    assert_eq!((State::new(3, None), false), res);
+    let committed = import.state.apply(|s| s.committed_height());
+    (result, committed)
 }
 
-#[tokio::test]
-async fn import__can_work_in_two_loops() {
-    // given
-    let s = SharedMutex::new(State::new(3, 5));
-    let state = s.clone();
-
-    let mut consensus_port = MockConsensusPort::default();
-    consensus_port
-        .expect_check_sealed_header()
-        .times(3)
-        .returning(|_| Ok(true));
-    consensus_port
-        .expect_await_da_height()
-        .times(2)
-        .returning(|_| Ok(()));
-
+/// Imports 10 blocks with the `fetch_delay` of each network request
+/// and the `execution_time` of each block.
+async fn throughput_with(fetch_delay: Duration, execution_time: Duration) -> Throughput {
     let mut p2p = MockPeerToPeerPort::default();
-    p2p.expect_get_sealed_block_headers()
-        .times(2)
-        .returning(move |range| {
-            state.apply(|s| s.observe(6));
-            let peer = random_peer();
-            let headers = Some(range.map(empty_header).collect());
-            let headers = peer.bind(headers);
-            Ok(headers)
-        });
-    p2p.expect_get_transactions()
-        .times(2)
-        .returning(|block_ids| {
-            let data = block_ids.data;
-            let v = data.into_iter().map(|_| Transactions::default()).collect();
-            Ok(Some(v))
-        });
-
-    let c = DefaultMocks::times([2]);
-    let mocks = Mocks {
-        consensus_port,
+    p2p.expect_get_sealed_block_headers().returning(|range| {
+        let headers = Some(range.map(empty_header).collect());
+        Ok(random_peer().bind(headers))
+    });
+    p2p.expect_get_transactions().returning(|block_ids| {
+        let data = block_ids.data;
+        let v = data.into_iter().map(|_| Transactions::default()).collect();
+        Ok(Some(v))
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let p2p = FaultInjector::new(
         p2p,
-        executor: DefaultMocks::times([3]),
-    };
+        FaultSchedule::Fixed(vec![Some(Fault::Delay(fetch_delay))]),
+    );
+    let mut consensus = MockConsensusPort::default();
+    consensus
+        .expect_check_sealed_header()
+        .returning(|_| Ok(true));
+    consensus.expect_await_da_height().returning(|_| Ok(()));
     let params = Config {
-        block_stream_buffer_size: 10,
-        header_batch_size: 10,
+        block_stream_buffer_size: 1,
+        header_batch_size: 5,
+        record_throughput: true,
+        ..Default::default()
     };
+    let import = Import::new(
+        SharedMutex::new(State::new(None, 9)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(SlowImporter(execution_time, Default::default())),
+        Arc::new(consensus),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+    import.import_inner(&watcher).await.unwrap();
+    assert_eq!(import.state.apply(|s| s.committed_height()), Some(9));
+    import.throughput()
+}
+
+#[tokio::test(start_paused = true)]
+async fn throughput__slow_executor_lowers_only_the_execute_throughput() {
+    // given
+    let fetch_delay = Duration::from_millis(1);
+    let execution_time = Duration::from_millis(100);
 
     // when
-    let res = test_import_inner(s, mocks, Some(c), params).await;
+    let throughput = throughput_with(fetch_delay, execution_time).await;
 
     // then
-    assert_eq!((State::new(6, None), true), res);
+    assert!(throughput.execute.blocks_per_second < 20.0);
+    assert!(throughput.fetch.blocks_per_second >= 1000.0);
 }
 
-async fn test_import_inner(
-    state: SharedMutex<State>,
-    mocks: Mocks,
-    count: Option<Count>,
-    params: Config,
-) -> (State, bool) {
-    let notify = Arc::new(Notify::new());
-    let Mocks {
-        consensus_port,
-        mut p2p,
-        executor,
-    } = mocks;
-    p2p.expect_report_peer().returning(|_, _| Ok(()));
-    let p2p = Arc::new(p2p);
+#[tokio::test(start_paused = true)]
+async fn throughput__slow_network_lowers_only_the_fetch_throughput() {
+    // given
+    let fetch_delay = Duration::from_millis(100);
+    let execution_time = Duration::from_millis(1);
 
-    let executor = Arc::new(executor);
-    let consensus = Arc::new(consensus_port);
+    // when
+    let throughput = throughput_with(fetch_delay, execution_time).await;
 
-    let import = Import {
-        state,
-        notify,
-        params,
-        p2p,
-        executor,
-        consensus,
+    // then
+    assert!(throughput.fetch.blocks_per_second < 50.0);
+    assert!(throughput.execute.blocks_per_second >= 500.0);
+}
+
+#[test_case::test_case(false, None => ImportStrategy::Blocks; "blocks")]
+#[test_case::test_case(
+    false, Some(Duration::from_secs(1)) => ImportStrategy::WatchedBlocks;
+    "watched blocks"
+)]
+#[test_case::test_case(true, None => ImportStrategy::HeadersOnly; "headers only")]
+#[test_case::test_case(
+    true, Some(Duration::from_secs(1)) => ImportStrategy::WatchedHeadersOnly;
+    "watched headers only"
+)]
+fn active_strategy__matches_the_config(
+    headers_only: bool,
+    stream_stall_timeout: Option<Duration>,
+) -> ImportStrategy {
+    // given
+    let params = Config {
+        headers_only,
+        stream_stall_timeout,
+        ..Default::default()
     };
-    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
-    let mut watcher = shutdown.into();
-    let received_notify_signal = match count {
-        Some(Count(count)) => {
-            let mut r = false;
-            for _ in 0..count {
-                import.notify.notify_one();
-                r = import.import(&mut watcher).await.is_ok();
-                if !r {
-                    break
-                }
-            }
-            r
-        }
-        None => {
-            import.notify.notify_one();
-            import.import(&mut watcher).await.is_ok()
-        }
+    let import = Import::new(
+        SharedMutex::new(State::new(None, None)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(MockPeerToPeerPort::default()),
+        Arc::new(MockBlockImporterPort::default()),
+        Arc::new(MockConsensusPort::default()),
+    );
+
+    // when
+    import.active_strategy()
+}
+
+#[test_case::test_case(None, 1 => Pipelining::Pipelined; "no threshold near the tip")]
+#[test_case::test_case(None, 1000 => Pipelining::Pipelined; "no threshold far from the tip")]
+#[test_case::test_case(Some(100), 1 => Pipelining::Sequential; "near the tip")]
+#[test_case::test_case(Some(100), 100 => Pipelining::Sequential; "at the threshold")]
+#[test_case::test_case(Some(100), 101 => Pipelining::Pipelined; "beyond the threshold")]
+fn pipelining__switches_at_the_threshold(
+    pipeline_threshold: Option<usize>,
+    gap: usize,
+) -> Pipelining {
+    // given
+    let params = Config {
+        pipeline_threshold,
+        ..Default::default()
     };
-    let final_state = import.state.apply(|s| s.clone());
-    (final_state, received_notify_signal)
+
+    // when
+    Pipelining::for_gap(&params, gap)
 }
 
 #[tokio::test]
@@ -1015,6 +3849,20 @@ async fn import__bad_block_header_sends_peer_report() {
         .await;
 }
 
+#[tokio::test]
+async fn import__malformed_header_is_skipped_and_sends_peer_report() {
+    // Given
+    let mut header = empty_header(0);
+    header.entity.set_transaction_root(Default::default());
+
+    PeerReportTestBuilder::new()
+        // When
+        .with_get_sealed_block_headers(Some(vec![header]))
+        // Then
+        .run_with_expected_reports([PeerReportReason::BadBlockHeader])
+        .await;
+}
+
 #[tokio::test]
 async fn import__missing_transactions_sends_peer_report() {
     // Given
@@ -1026,11 +3874,23 @@ async fn import__missing_transactions_sends_peer_report() {
         .await;
 }
 
+#[tokio::test]
+async fn import__execution_failure_does_not_send_good_peer_report() {
+    // Given
+    PeerReportTestBuilder::new()
+        // When
+        .with_execute_and_commit(false)
+        // Then
+        .run_with_expected_reports([])
+        .await;
+}
+
 struct PeerReportTestBuilder {
     shared_peer_id: Vec<u8>,
     get_sealed_headers: Option<Option<Vec<SealedBlockHeader>>>,
     get_transactions: Option<Option<Vec<Transactions>>>,
     check_sealed_header: Option<bool>,
+    execute_and_commit: Option<bool>,
     block_count: u32,
     debug: bool,
 }
@@ -1042,6 +3902,7 @@ impl PeerReportTestBuilder {
             get_sealed_headers: None,
             get_transactions: None,
             check_sealed_header: None,
+            execute_and_commit: None,
             block_count: 1,
             debug: false,
         }
@@ -1074,6 +3935,11 @@ impl PeerReportTestBuilder {
         self
     }
 
+    pub fn with_execute_and_commit(mut self, execute_and_commit: bool) -> Self {
+        self.execute_and_commit = Some(execute_and_commit);
+        self
+    }
+
     pub fn times(mut self, block_count: u32) -> Self {
         self.block_count = block_count;
         self
@@ -1101,16 +3967,10 @@ impl PeerReportTestBuilder {
         let params = Config {
             block_stream_buffer_size: 10,
             header_batch_size: 10,
+            ..Default::default()
         };
 
-        let import = Import {
-            state,
-            notify,
-            params,
-            p2p,
-            executor,
-            consensus,
-        };
+        let import = Import::new(state, notify, params, p2p, executor, consensus);
         let (_tx, shutdown) =
             tokio::sync::watch::channel(fuel_core_services::State::Started);
         let mut watcher = shutdown.into();
@@ -1173,8 +4033,16 @@ impl PeerReportTestBuilder {
 
     fn executor(&self) -> Arc<MockBlockImporterPort> {
         let mut executor = MockBlockImporterPort::default();
+        executor.expect_can_accept().returning(|| true);
 
-        executor.expect_execute_and_commit().returning(|_| Ok(()));
+        let execute_and_commit = self.execute_and_commit.unwrap_or(true);
+        executor.expect_execute_and_commit().returning(move |_| {
+            if execute_and_commit {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("Some execution error"))
+            }
+        });
 
         Arc::new(executor)
     }
@@ -1286,6 +4154,7 @@ impl DefaultMocks for MockPeerToPeerPort {
 impl DefaultMocks for MockBlockImporterPort {
     fn times<T: IntoIterator<Item = usize> + Clone>(t: T) -> Self {
         let mut executor = MockBlockImporterPort::default();
+        executor.expect_can_accept().returning(|| true);
         let t = t.into_iter().next().unwrap();
 
         executor
@@ -1295,3 +4164,258 @@ impl DefaultMocks for MockBlockImporterPort {
         executor
     }
 }
+
+#[tokio::test]
+async fn import__failed_heights_are_preserved_across_the_restart() {
+    struct InMemoryFailedHeights(std::sync::Mutex<Vec<RangeInclusive<u32>>>);
+
+    impl FailedHeightsStore for InMemoryFailedHeights {
+        fn load_failed_heights(&self) -> anyhow::Result<Vec<RangeInclusive<u32>>> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+
+        fn store_failed_heights(
+            &self,
+            failed: Vec<RangeInclusive<u32>>,
+        ) -> anyhow::Result<()> {
+            *self.0.lock().unwrap() = failed;
+            Ok(())
+        }
+    }
+
+    // given
+    let store = Arc::new(InMemoryFailedHeights(Default::default()));
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .returning(|_| Ok(random_peer().bind(None)));
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        persist_failed_heights: true,
+        ..Default::default()
+    };
+    let import = Import::new(
+        SharedMutex::new(State::new(3, 5)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(MockConsensusPort::default()),
+    )
+    .with_failed_heights_store(store.clone());
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+    assert!(import.import(&mut watcher).await.is_err());
+    assert_eq!(import.state.apply(|s| failed_ranges(s)), vec![4..=5]);
+
+    // when
+    let restarted = Import::new(
+        SharedMutex::new(State::new(3, None)),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(MockPeerToPeerPort::default()),
+        Arc::new(MockBlockImporterPort::default()),
+        Arc::new(MockConsensusPort::default()),
+    )
+    .with_failed_heights_store(store);
+
+    // then
+    assert_eq!(restarted.state.apply(|s| failed_ranges(s)), vec![4..=5]);
+}
+
+fn failed_ranges(state: &State) -> Vec<RangeInclusive<u32>> {
+    state.failed_heights().ranges().collect()
+}
+
+/// The root of the state after the block with the `block_id` on top of the `prev_root`.
+fn chained_root(prev_root: Bytes32, block_id: BlockId) -> Bytes32 {
+    fuel_core_types::fuel_crypto::Hasher::default()
+        .chain(prev_root)
+        .chain(block_id)
+        .digest()
+}
+
+#[tokio::test]
+async fn replay_range__returns_the_roots_of_the_committed_chain() {
+    // given
+    let canonical_roots = (1..=3u32)
+        .scan(Bytes32::zeroed(), |root, height| {
+            *root = chained_root(*root, empty_header(height).entity.id());
+            Some((BlockHeight::from(height), *root))
+        })
+        .collect::<Vec<_>>();
+    // The last block is not in the local storage and is fetched from the network.
+    let mut executor = MockBlockImporterPort::default();
+    executor
+        .expect_local_sealed_header()
+        .returning(|height| Ok((*height < 3).then(|| empty_header(height))));
+    executor
+        .expect_local_transactions()
+        .returning(|_| Ok(Some(Transactions::default())));
+    executor.expect_execute_and_commit().times(0);
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers()
+        .times(1)
+        .returning(|range| {
+            assert_eq!(range, 3..4);
+            Ok(random_peer().bind(Some(range.map(empty_header).collect())))
+        });
+    p2p.expect_get_transactions()
+        .times(1)
+        .returning(|block_ids| {
+            let v = block_ids.data.map(|_| Transactions::default()).collect();
+            Ok(Some(v))
+        });
+    let mut consensus = MockConsensusPort::default();
+    consensus
+        .expect_check_sealed_header()
+        .times(1)
+        .returning(|_| Ok(true));
+    let mut replay_executor = MockReplayExecutor::default();
+    let mut root = Bytes32::zeroed();
+    replay_executor
+        .expect_replay()
+        .times(3)
+        .returning(move |block| {
+            root = chained_root(root, block.entity.id());
+            Ok(root)
+        });
+    let import = Import::new(
+        SharedMutex::new(State::new(3, None)),
+        Arc::new(Notify::new()),
+        Config::default(),
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(consensus),
+    );
+
+    // when
+    let roots = import.replay_range(1..=3, &replay_executor).await.unwrap();
+
+    // then
+    assert_eq!(roots, canonical_roots);
+    assert_eq!(import.state.apply(|s| s.committed_height()), Some(3));
+}
+
+#[tokio::test]
+async fn import__truncated_transactions_are_resumed_from_the_same_peer() {
+    // given
+    let transactions = vec![
+        fuel_core_types::fuel_tx::TransactionBuilder::script(vec![], vec![])
+            .finalize_as_transaction(),
+        fuel_core_types::fuel_tx::TransactionBuilder::script(vec![], vec![1])
+            .finalize_as_transaction(),
+    ];
+    let peer = random_peer();
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().times(1).returning({
+        let peer = peer.clone();
+        let transactions = transactions.clone();
+        move |range| {
+            let headers = range
+                .map(|height| header_with_transactions(height, &transactions))
+                .collect();
+            Ok(peer.clone().bind(Some(headers)))
+        }
+    });
+    let requested = Arc::new(std::sync::Mutex::new(vec![]));
+    p2p.expect_get_transactions().times(2).returning({
+        let transactions = transactions.clone();
+        let requested = requested.clone();
+        move |block_ids| {
+            let range = block_ids.data;
+            requested.lock().unwrap().push(range.clone());
+            let mut v = range
+                .map(|_| Transactions(transactions.clone()))
+                .collect::<Vec<_>>();
+            // The first response is cut in the middle of the block 2.
+            if requested.lock().unwrap().len() == 1 {
+                v.truncate(3);
+                v[2].0.truncate(1);
+            }
+            Ok(Some(v))
+        }
+    });
+    let mocks = Mocks {
+        consensus_port: DefaultMocks::times([5, 1]),
+        p2p,
+        executor: DefaultMocks::times([5]),
+    };
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        ..Default::default()
+    };
+
+    // when
+    let res = test_import_inner(State::new(None, 4).into(), mocks, None, params).await;
+
+    // then
+    assert_eq!((State::new(4, None), true), res);
+    assert_eq!(*requested.lock().unwrap(), vec![0..5, 2..5]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn import__commits_pause_while_the_disk_space_is_low_and_resume_after() {
+    // given
+    let free_space = Arc::new(std::sync::atomic::AtomicU64::new(10));
+    let mut disk_monitor = MockDiskMonitor::default();
+    disk_monitor.expect_free_disk_space().returning({
+        let free_space = free_space.clone();
+        move || Ok(free_space.load(Ordering::SeqCst))
+    });
+    let mut p2p: MockPeerToPeerPort = DefaultMocks::times([1]);
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 10,
+        min_free_disk_space: Some(100),
+        ..Default::default()
+    };
+    let import = Arc::new(
+        Import::new(
+            SharedMutex::new(State::new(None, 4)),
+            Arc::new(Notify::new()),
+            params,
+            Arc::new(p2p),
+            Arc::new(<MockBlockImporterPort as DefaultMocks>::times([5])),
+            Arc::new(<MockConsensusPort as DefaultMocks>::times([5, 1])),
+        )
+        .with_disk_monitor(Arc::new(disk_monitor)),
+    );
+    let mut events = import.subscribe_disk_space();
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let watcher: StateWatcher = shutdown.into();
+    let handle = tokio::spawn({
+        let import = import.clone();
+        async move { import.import_inner(&watcher).await }
+    });
+
+    // when
+    let paused = events.recv().await.unwrap();
+    tokio::time::sleep(Duration::from_secs(10)).await;
+
+    // then
+    assert_eq!(
+        paused,
+        DiskSpaceEvent::Paused {
+            free_space: 10,
+            min_free_space: 100,
+        }
+    );
+    assert!(!handle.is_finished());
+    assert_eq!(import.state.apply(|s| s.committed_height()), None);
+
+    // when
+    free_space.store(1_000, Ordering::SeqCst);
+    let resumed = events.recv().await.unwrap();
+    handle.await.unwrap().unwrap();
+
+    // then
+    assert_eq!(resumed, DiskSpaceEvent::Resumed);
+    assert_eq!(import.state.apply(|s| s.committed_height()), Some(4));
+}
@@ -6,8 +6,12 @@
 //! # Sync Service
 //! Responsible for syncing the blockchain from the network.
 
+pub mod clock;
 pub mod import;
+pub mod multi_target;
+pub mod peer_selector;
 pub mod ports;
+pub mod replay;
 pub mod service;
 pub mod state;
 pub mod sync;
@@ -15,7 +19,5 @@ mod tracing_helpers;
 
 pub use import::Config;
 
-use rand as _;
-
 #[cfg(test)]
 fuel_core_trace::enable_tracing!();
@@ -0,0 +1,200 @@
+//! # Multi Target
+//! Commits each imported block to the primary database and its read replicas,
+//! so a high-availability setup can serve the replicas without a separate sync.
+
+use crate::ports::{
+    BlockImporterPort,
+    ImportTimings,
+};
+use fuel_core_services::stream::BoxStream;
+use fuel_core_types::{
+    blockchain::{
+        block::Block,
+        consensus::Sealed,
+        SealedBlock,
+        SealedBlockHeader,
+    },
+    fuel_types::BlockHeight,
+    services::p2p::Transactions,
+};
+use std::future::Future;
+
+#[cfg(test)]
+mod tests;
+
+/// The behavior on the failure of a replica to commit a block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReplicaFailurePolicy {
+    /// The failure is logged and the import continues.
+    #[default]
+    Ignore,
+    /// The import of the block fails, even though the primary has committed it.
+    /// The retry of the block commits it only to the replicas that miss it.
+    Fail,
+}
+
+/// Wraps the [`BlockImporterPort`] of the primary database and fans the commits
+/// of the blocks out to the replicas.
+///
+/// The replicas are committed only after the primary succeeds, and the result of
+/// the commit is the result of the primary, unless the [`ReplicaFailurePolicy`]
+/// says otherwise. A replica that missed some blocks catches up by committing them
+/// from the primary before the next block. All other requests are served by the primary.
+///
+/// The replicas must start from the same committed height as the primary.
+pub struct MultiTargetImporter<B> {
+    primary: B,
+    replicas: Vec<B>,
+    policy: ReplicaFailurePolicy,
+}
+
+impl<B> MultiTargetImporter<B> {
+    /// Creates the importer committing to the `primary` and the `replicas`.
+    pub fn new(primary: B, replicas: Vec<B>, policy: ReplicaFailurePolicy) -> Self {
+        Self {
+            primary,
+            replicas,
+            policy,
+        }
+    }
+}
+
+impl<B> MultiTargetImporter<B>
+where
+    B: BlockImporterPort + Send + Sync,
+{
+    /// Applies the `commit` to all replicas concurrently and
+    /// handles their failures according to the policy.
+    async fn commit_to_replicas<'a, F, Fut>(&'a self, commit: F) -> anyhow::Result<()>
+    where
+        F: FnMut(&'a B) -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        let results = futures::future::join_all(self.replicas.iter().map(commit)).await;
+        for (index, result) in results.into_iter().enumerate() {
+            let Err(e) = result else { continue };
+            match self.policy {
+                ReplicaFailurePolicy::Ignore => {
+                    tracing::warn!("The replica {} failed to commit: {:?}", index, e);
+                }
+                ReplicaFailurePolicy::Fail => {
+                    return Err(e.context(format!("The replica {index} failed to commit")))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Commits the `block` to the `replica`. The blocks the replica missed are
+    /// committed first from the primary, and the block the replica already has
+    /// is skipped.
+    async fn catch_up_and_commit(
+        &self,
+        replica: &B,
+        block: SealedBlock,
+    ) -> anyhow::Result<()> {
+        let height = *block.entity.header().height();
+        if replica.local_sealed_header(height)?.is_some() {
+            return Ok(())
+        }
+        let mut missed = vec![];
+        let mut next = height.pred();
+        while let Some(prev) = next {
+            if replica.local_sealed_header(prev)?.is_some() {
+                break
+            }
+            missed.push(prev);
+            next = prev.pred();
+        }
+        for missed_height in missed.into_iter().rev() {
+            tracing::info!("Catching up the replica at height {}", *missed_height);
+            let missed_block = self.primary_block(missed_height)?;
+            replica.execute_and_commit(missed_block).await?;
+        }
+        replica.execute_and_commit(block).await
+    }
+
+    /// Builds the committed block of the primary at the `height`.
+    fn primary_block(&self, height: BlockHeight) -> anyhow::Result<SealedBlock> {
+        let header = self.primary.local_sealed_header(height)?;
+        let transactions = self.primary.local_transactions(height)?;
+        let (Some(Sealed { entity, consensus }), Some(Transactions(transactions))) =
+            (header, transactions)
+        else {
+            return Err(anyhow::anyhow!(
+                "The primary has no block at height {}",
+                *height
+            ))
+        };
+        let block = Block::try_from_executed(entity, transactions).ok_or_else(|| {
+            anyhow::anyhow!(
+                "The transactions of the primary don't match the header {}",
+                *height
+            )
+        })?;
+        Ok(SealedBlock {
+            entity: block,
+            consensus,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl<B> BlockImporterPort for MultiTargetImporter<B>
+where
+    B: BlockImporterPort + Send + Sync,
+{
+    fn committed_height_stream(&self) -> BoxStream<BlockHeight> {
+        self.primary.committed_height_stream()
+    }
+
+    /// The block already committed to the primary, e.g. the retry of the block
+    /// failed by a replica, is committed only to the replicas.
+    async fn execute_and_commit(&self, block: SealedBlock) -> anyhow::Result<()> {
+        let height = *block.entity.header().height();
+        let committed = self
+            .primary
+            .local_sealed_header(height)?
+            .map_or(false, |header| header.entity.id() == block.entity.header().id());
+        if !committed {
+            self.primary.execute_and_commit(block.clone()).await?;
+        }
+        self.commit_to_replicas(|replica| {
+            self.catch_up_and_commit(replica, block.clone())
+        })
+        .await
+    }
+
+    /// The replicas keep pace with the primary, so the import waits for the slowest one.
+    fn can_accept(&self) -> bool {
+        self.primary.can_accept() && self.replicas.iter().all(|r| r.can_accept())
+    }
+
+    fn record_import_timings(
+        &self,
+        height: BlockHeight,
+        timings: ImportTimings,
+    ) -> anyhow::Result<()> {
+        self.primary.record_import_timings(height, timings)
+    }
+
+    fn local_sealed_header(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Option<SealedBlockHeader>> {
+        self.primary.local_sealed_header(height)
+    }
+
+    fn local_transactions(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Option<Transactions>> {
+        self.primary.local_transactions(height)
+    }
+
+    async fn store_sealed_header(&self, header: SealedBlockHeader) -> anyhow::Result<()> {
+        self.primary.store_sealed_header(header.clone()).await?;
+        self.commit_to_replicas(|replica| replica.store_sealed_header(header.clone()))
+            .await
+    }
+}
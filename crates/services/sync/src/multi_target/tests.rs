@@ -0,0 +1,218 @@
+#![allow(non_snake_case)]
+
+use super::*;
+use crate::import::test_helpers::empty_header;
+use std::sync::{
+    atomic::{
+        AtomicBool,
+        Ordering,
+    },
+    Mutex,
+};
+
+/// Keeps the committed blocks, or fails each commit while it `fails`.
+#[derive(Default)]
+struct Target {
+    committed: Mutex<Vec<SealedBlock>>,
+    fails: AtomicBool,
+}
+
+impl Target {
+    fn failing() -> Self {
+        Self {
+            fails: AtomicBool::new(true),
+            ..Default::default()
+        }
+    }
+
+    fn committed_heights(&self) -> Vec<u32> {
+        self.committed
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|block| **block.entity.header().height())
+            .collect()
+    }
+
+    fn committed_block(&self, height: BlockHeight) -> Option<SealedBlock> {
+        self.committed
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|block| *block.entity.header().height() == height)
+            .cloned()
+    }
+}
+
+#[async_trait::async_trait]
+impl BlockImporterPort for Target {
+    fn committed_height_stream(&self) -> BoxStream<BlockHeight> {
+        unreachable!("The test doesn't subscribe to the heights")
+    }
+
+    async fn execute_and_commit(&self, block: SealedBlock) -> anyhow::Result<()> {
+        if self.fails.load(Ordering::SeqCst) {
+            return Err(anyhow::anyhow!("The target is down"))
+        }
+        let height = *block.entity.header().height();
+        if self.committed_block(height).is_some() {
+            return Err(anyhow::anyhow!("The block {} is committed", *height))
+        }
+        self.committed.lock().unwrap().push(block);
+        Ok(())
+    }
+
+    fn can_accept(&self) -> bool {
+        true
+    }
+
+    fn record_import_timings(
+        &self,
+        _: BlockHeight,
+        _: ImportTimings,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn local_sealed_header(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Option<SealedBlockHeader>> {
+        Ok(self.committed_block(height).map(|block| Sealed {
+            entity: block.entity.header().clone(),
+            consensus: block.consensus,
+        }))
+    }
+
+    fn local_transactions(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Option<Transactions>> {
+        Ok(self
+            .committed_block(height)
+            .map(|block| Transactions(block.entity.transactions().to_vec())))
+    }
+
+    async fn store_sealed_header(&self, _: SealedBlockHeader) -> anyhow::Result<()> {
+        unreachable!("The blocks are always imported with their transactions")
+    }
+}
+
+fn block(height: u32) -> SealedBlock {
+    let header = empty_header(height);
+    SealedBlock {
+        entity: Block::try_from_executed(header.entity, vec![])
+            .expect("The block without transactions is valid"),
+        consensus: header.consensus,
+    }
+}
+
+#[tokio::test]
+async fn execute_and_commit__commits_each_block_to_all_targets() {
+    // given
+    let primary = Target::default();
+    let replica = Target::default();
+    let importer =
+        MultiTargetImporter::new(primary, vec![replica], ReplicaFailurePolicy::Fail);
+
+    // when
+    for height in 0..3 {
+        importer.execute_and_commit(block(height)).await.unwrap();
+    }
+
+    // then
+    assert_eq!(importer.primary.committed_heights(), vec![0, 1, 2]);
+    assert_eq!(importer.replicas[0].committed_heights(), vec![0, 1, 2]);
+}
+
+#[tokio::test]
+async fn execute_and_commit__replica_failure_is_ignored_by_ignore_policy() {
+    // given
+    let primary = Target::default();
+    let replica = Target::failing();
+    let importer =
+        MultiTargetImporter::new(primary, vec![replica], ReplicaFailurePolicy::Ignore);
+
+    // when
+    let result = importer.execute_and_commit(block(0)).await;
+
+    // then
+    assert!(result.is_ok());
+    assert_eq!(importer.primary.committed_heights(), vec![0]);
+}
+
+#[tokio::test]
+async fn execute_and_commit__replica_failure_fails_the_commit_by_fail_policy() {
+    // given
+    let primary = Target::default();
+    let replica = Target::failing();
+    let importer =
+        MultiTargetImporter::new(primary, vec![replica], ReplicaFailurePolicy::Fail);
+
+    // when
+    let result = importer.execute_and_commit(block(0)).await;
+
+    // then
+    assert!(result.is_err());
+    assert_eq!(importer.primary.committed_heights(), vec![0]);
+}
+
+#[tokio::test]
+async fn execute_and_commit__primary_failure_skips_the_replicas() {
+    // given
+    let primary = Target::failing();
+    let replica = Target::default();
+    let importer =
+        MultiTargetImporter::new(primary, vec![replica], ReplicaFailurePolicy::Ignore);
+
+    // when
+    let result = importer.execute_and_commit(block(0)).await;
+
+    // then
+    assert!(result.is_err());
+    assert!(importer.replicas[0].committed_heights().is_empty());
+}
+
+#[tokio::test]
+async fn execute_and_commit__replica_catches_up_after_missing_blocks_by_ignore_policy() {
+    // given
+    let importer = MultiTargetImporter::new(
+        Target::default(),
+        vec![Target::default()],
+        ReplicaFailurePolicy::Ignore,
+    );
+    importer.execute_and_commit(block(0)).await.unwrap();
+    importer.replicas[0].fails.store(true, Ordering::SeqCst);
+    importer.execute_and_commit(block(1)).await.unwrap();
+    importer.execute_and_commit(block(2)).await.unwrap();
+    importer.replicas[0].fails.store(false, Ordering::SeqCst);
+
+    // when
+    let result = importer.execute_and_commit(block(3)).await;
+
+    // then
+    assert!(result.is_ok());
+    assert_eq!(importer.primary.committed_heights(), vec![0, 1, 2, 3]);
+    assert_eq!(importer.replicas[0].committed_heights(), vec![0, 1, 2, 3]);
+}
+
+#[tokio::test]
+async fn execute_and_commit__retry_after_replica_failure_commits_only_the_replica() {
+    // given
+    let importer = MultiTargetImporter::new(
+        Target::default(),
+        vec![Target::default()],
+        ReplicaFailurePolicy::Fail,
+    );
+    importer.replicas[0].fails.store(true, Ordering::SeqCst);
+    assert!(importer.execute_and_commit(block(0)).await.is_err());
+    importer.replicas[0].fails.store(false, Ordering::SeqCst);
+
+    // when
+    let result = importer.execute_and_commit(block(0)).await;
+
+    // then
+    assert!(result.is_ok());
+    assert_eq!(importer.primary.committed_heights(), vec![0]);
+    assert_eq!(importer.replicas[0].committed_heights(), vec![0]);
+}
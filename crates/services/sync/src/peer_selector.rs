@@ -0,0 +1,41 @@
+//! # Peer Selector
+//! The policy of the choice of the peer the headers are requested from.
+//! Embedders can inject their own policy, e.g. a latency-aware one,
+//! via [`crate::service::new_service`]. Without a selector, the network
+//! chooses the peer of each request.
+
+use fuel_core_types::{
+    fuel_types::BlockHeight,
+    services::p2p::PeerId,
+};
+use std::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Chooses the peer of each request of the headers.
+pub trait PeerSelector: Send + Sync {
+    /// Returns one of the `candidates` to request the headers up to the `height` from.
+    /// The `candidates` are never empty.
+    fn choose(&self, candidates: &[PeerId], height: BlockHeight) -> PeerId;
+}
+
+#[derive(Debug, Default)]
+/// The selector that chooses the candidates in turn.
+pub struct RoundRobin {
+    next: AtomicUsize,
+}
+
+impl PeerSelector for RoundRobin {
+    fn choose(&self, candidates: &[PeerId], _: BlockHeight) -> PeerId {
+        let index = self
+            .next
+            .fetch_add(1, Ordering::Relaxed)
+            .checked_rem(candidates.len())
+            .expect("The candidates are never empty");
+        candidates[index].clone()
+    }
+}
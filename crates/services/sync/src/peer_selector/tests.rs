@@ -0,0 +1,30 @@
+#![allow(non_snake_case)]
+
+use super::*;
+
+#[test]
+fn round_robin__chooses_candidates_in_turn() {
+    // given
+    let selector = RoundRobin::default();
+    let candidates = vec![
+        PeerId::from(vec![1]),
+        PeerId::from(vec![2]),
+        PeerId::from(vec![3]),
+    ];
+
+    // when
+    let chosen = (0..4)
+        .map(|_| selector.choose(&candidates, 0u32.into()))
+        .collect::<Vec<_>>();
+
+    // then
+    assert_eq!(
+        chosen,
+        vec![
+            candidates[0].clone(),
+            candidates[1].clone(),
+            candidates[2].clone(),
+            candidates[0].clone(),
+        ]
+    );
+}
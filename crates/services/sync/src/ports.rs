@@ -7,14 +7,24 @@ use fuel_core_types::{
         SealedBlock,
         SealedBlockHeader,
     },
-    fuel_types::BlockHeight,
+    fuel_tx::Transaction,
+    fuel_types::{
+        BlockHeight,
+        Bytes32,
+    },
     services::p2p::{
         PeerId,
         SourcePeer,
         Transactions,
     },
 };
-use std::ops::Range;
+use std::{
+    ops::{
+        Range,
+        RangeInclusive,
+    },
+    time::Duration,
+};
 
 /// Possible reasons to report a peer
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -32,6 +42,8 @@ pub enum PeerReportReason {
     MissingTransactions,
     /// Received invalid transactions
     InvalidTransactions,
+    /// Advertised a chain config different from the local one
+    IncompatibleChainConfig,
 }
 
 #[cfg_attr(any(test, feature = "benchmarking"), mockall::automock)]
@@ -47,6 +59,16 @@ pub trait PeerToPeerPort {
         block_height_range: Range<u32>,
     ) -> anyhow::Result<SourcePeer<Option<Vec<SealedBlockHeader>>>>;
 
+    /// Request a range of sealed block headers from the source peer.
+    async fn get_sealed_block_headers_from_peer(
+        &self,
+        block_height_range: SourcePeer<Range<u32>>,
+    ) -> anyhow::Result<Option<Vec<SealedBlockHeader>>>;
+
+    /// Returns the connected peers that advertise the `height`.
+    async fn peers_with_height(&self, height: BlockHeight)
+        -> anyhow::Result<Vec<PeerId>>;
+
     /// Request transactions from the network for the given block
     /// and source peer.
     async fn get_transactions(
@@ -54,6 +76,10 @@ pub trait PeerToPeerPort {
         block_ids: SourcePeer<Range<u32>>,
     ) -> anyhow::Result<Option<Vec<Transactions>>>;
 
+    /// Returns the hash of the chain config advertised by the `peer`,
+    /// or `None` if the peer doesn't advertise it.
+    async fn chain_config_hash(&self, peer: PeerId) -> anyhow::Result<Option<Bytes32>>;
+
     /// Report a peer for some reason to modify their reputation.
     fn report_peer(&self, peer: PeerId, report: PeerReportReason) -> anyhow::Result<()>;
 }
@@ -66,6 +92,82 @@ pub trait ConsensusPort {
     fn check_sealed_header(&self, header: &SealedBlockHeader) -> anyhow::Result<bool>;
     /// await for this DA height to be sync'd.
     async fn await_da_height(&self, da_height: &DaBlockHeight) -> anyhow::Result<()>;
+    /// Returns the latest DA height synced by the node,
+    /// or `None` if the node doesn't follow the DA layer.
+    fn da_tip(&self) -> anyhow::Result<Option<DaBlockHeight>>;
+}
+
+/// Port for the verification of the headers against the external finality evidence,
+/// e.g. the proof of a light client of the bridged chain.
+#[cfg_attr(any(test, feature = "benchmarking"), mockall::automock)]
+pub trait FinalityVerifier: Send + Sync {
+    /// Returns `true` if the external finality evidence of the `header` is valid.
+    /// The headers without the evidence are not final and must be rejected.
+    fn verify_finality(&self, header: &SealedBlockHeader) -> anyhow::Result<bool>;
+}
+
+/// Port for the structural checks of the downloaded transactions before the execution
+/// of their block, e.g. against the consensus parameters of the chain.
+#[cfg_attr(any(test, feature = "benchmarking"), mockall::automock)]
+pub trait TransactionChecker: Send + Sync {
+    /// Returns an error if the `transaction` of the block at the `height` is malformed.
+    fn check_transaction(
+        &self,
+        height: BlockHeight,
+        transaction: &Transaction,
+    ) -> anyhow::Result<()>;
+}
+
+/// Port for the persistence of the heights that failed to import,
+/// so they are not lost on the restart of the node.
+#[cfg_attr(any(test, feature = "benchmarking"), mockall::automock)]
+pub trait FailedHeightsStore: Send + Sync {
+    /// Returns the ranges of the persisted failed heights.
+    fn load_failed_heights(&self) -> anyhow::Result<Vec<RangeInclusive<u32>>>;
+
+    /// Replaces the persisted failed heights with the `failed` ranges.
+    /// No ranges clear them.
+    fn store_failed_heights(
+        &self,
+        failed: Vec<RangeInclusive<u32>>,
+    ) -> anyhow::Result<()>;
+}
+
+/// Port for the free space of the disk with the database.
+#[cfg_attr(any(test, feature = "benchmarking"), mockall::automock)]
+pub trait DiskMonitor: Send + Sync {
+    /// Returns the free space of the disk in bytes.
+    fn free_disk_space(&self) -> anyhow::Result<u64>;
+}
+
+/// The root of the state after the execution of a block.
+pub type StateRoot = Bytes32;
+
+/// Port for the execution of the blocks on a throwaway copy of the state,
+/// used to check that the execution is deterministic.
+#[cfg_attr(any(test, feature = "benchmarking"), mockall::automock)]
+pub trait ReplayExecutor: Send + Sync {
+    /// Executes the `block` on top of the state left by the previously replayed blocks
+    /// without committing it to the main database, and returns the root of the state.
+    fn replay(&self, block: SealedBlock) -> anyhow::Result<StateRoot>;
+}
+
+/// The time spent to import a block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ImportTimings {
+    /// The total time of the task fetching the batch with the block, including
+    /// all the stages below except the execution.
+    pub fetch: Duration,
+    /// The time to execute and commit the block.
+    pub execute: Duration,
+    /// The time to fetch the headers of the batch with the block.
+    pub header_fetch: Duration,
+    /// The time to check the consensus of the headers of the batch with the block.
+    pub consensus_check: Duration,
+    /// The time to wait until the DA height of the batch with the block is synced.
+    pub da_wait: Duration,
+    /// The time to fetch the transactions of the batch with the block.
+    pub transaction_fetch: Duration,
 }
 
 #[cfg_attr(any(test, feature = "benchmarking"), mockall::automock)]
@@ -78,4 +180,30 @@ pub trait BlockImporterPort {
     /// Execute the given sealed block
     /// and commit it to the database.
     async fn execute_and_commit(&self, block: SealedBlock) -> anyhow::Result<()>;
+
+    /// Returns `false` if the executor is overloaded and can't accept more blocks
+    /// right now. The import doesn't fetch new blocks until it returns `true`.
+    fn can_accept(&self) -> bool;
+
+    /// Store the timings of the import of the committed block at the `height`.
+    fn record_import_timings(
+        &self,
+        height: BlockHeight,
+        timings: ImportTimings,
+    ) -> anyhow::Result<()>;
+
+    /// Returns the sealed header at the `height` from the local storage, if any.
+    fn local_sealed_header(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Option<SealedBlockHeader>>;
+
+    /// Returns the transactions of the block at the `height` from the local storage, if any.
+    fn local_transactions(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Option<Transactions>>;
+
+    /// Store the checked sealed header without the transactions of its block.
+    async fn store_sealed_header(&self, header: SealedBlockHeader) -> anyhow::Result<()>;
 }
@@ -0,0 +1,310 @@
+//! # Replay
+//! Records the responses of the network received during the import and
+//! replays them later, so a failed import can be reproduced offline.
+//!
+//! The records are stored as JSON lines, one request with its response per line.
+
+use crate::{
+    ports::{
+        PeerReportReason,
+        PeerToPeerPort,
+    },
+    tracing_helpers::TraceErr,
+};
+use fuel_core_services::{
+    stream::{
+        BoxStream,
+        IntoBoxStream,
+    },
+    SharedMutex,
+};
+use fuel_core_types::{
+    blockchain::SealedBlockHeader,
+    fuel_types::{
+        BlockHeight,
+        Bytes32,
+    },
+    services::p2p::{
+        PeerId,
+        SourcePeer,
+        Transactions,
+    },
+};
+use futures::StreamExt;
+use std::{
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    fs::File,
+    io::{
+        BufRead,
+        BufReader,
+        BufWriter,
+        Write,
+    },
+    ops::Range,
+    path::Path,
+};
+
+#[cfg(test)]
+mod tests;
+
+type HeadersResponse = Result<SourcePeer<Option<Vec<SealedBlockHeader>>>, String>;
+type TransactionsResponse = Result<Option<Vec<Transactions>>, String>;
+
+/// A request to the network with the response to it.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum Record {
+    /// The response to [`PeerToPeerPort::get_sealed_block_headers`].
+    SealedBlockHeaders {
+        /// The requested range of heights.
+        range: Range<u32>,
+        /// The response or the error message.
+        response: HeadersResponse,
+    },
+    /// The response to [`PeerToPeerPort::get_transactions`].
+    Transactions {
+        /// The requested range of heights and the peer.
+        request: SourcePeer<Range<u32>>,
+        /// The response or the error message.
+        response: TransactionsResponse,
+    },
+}
+
+/// Wraps the [`PeerToPeerPort`] and writes every request with its response to a file.
+/// The peer reports and the observed heights are not recorded.
+pub struct RecordingPeerToPeer<P> {
+    inner: P,
+    writer: SharedMutex<BufWriter<File>>,
+}
+
+impl<P> RecordingPeerToPeer<P> {
+    /// Creates the recorder writing to the file at `path`.
+    /// The file is truncated if it already exists.
+    pub fn new(inner: P, path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let file = File::create(path)?;
+        Ok(Self {
+            inner,
+            writer: SharedMutex::new(BufWriter::new(file)),
+        })
+    }
+
+    fn record(&self, record: &Record) {
+        let _ = self
+            .writer
+            .apply(|writer| {
+                serde_json::to_writer(&mut *writer, record)?;
+                writer.write_all(b"\n")?;
+                writer.flush()?;
+                Ok::<_, anyhow::Error>(())
+            })
+            .trace_err("Failed to record the response of the network");
+    }
+}
+
+#[async_trait::async_trait]
+impl<P> PeerToPeerPort for RecordingPeerToPeer<P>
+where
+    P: PeerToPeerPort + Send + Sync,
+{
+    fn height_stream(&self) -> BoxStream<BlockHeight> {
+        self.inner.height_stream()
+    }
+
+    async fn get_sealed_block_headers(
+        &self,
+        block_height_range: Range<u32>,
+    ) -> anyhow::Result<SourcePeer<Option<Vec<SealedBlockHeader>>>> {
+        let response = self
+            .inner
+            .get_sealed_block_headers(block_height_range.clone())
+            .await;
+        self.record(&Record::SealedBlockHeaders {
+            range: block_height_range,
+            response: to_record(&response),
+        });
+        response
+    }
+
+    /// Recorded as the response of the source peer to the range.
+    async fn get_sealed_block_headers_from_peer(
+        &self,
+        block_height_range: SourcePeer<Range<u32>>,
+    ) -> anyhow::Result<Option<Vec<SealedBlockHeader>>> {
+        let response = self
+            .inner
+            .get_sealed_block_headers_from_peer(block_height_range.clone())
+            .await;
+        let SourcePeer { peer_id, data } = block_height_range;
+        self.record(&Record::SealedBlockHeaders {
+            range: data,
+            response: to_record(&response).map(|headers| peer_id.bind(headers)),
+        });
+        response
+    }
+
+    async fn peers_with_height(
+        &self,
+        height: BlockHeight,
+    ) -> anyhow::Result<Vec<PeerId>> {
+        self.inner.peers_with_height(height).await
+    }
+
+    async fn get_transactions(
+        &self,
+        block_ids: SourcePeer<Range<u32>>,
+    ) -> anyhow::Result<Option<Vec<Transactions>>> {
+        let response = self.inner.get_transactions(block_ids.clone()).await;
+        self.record(&Record::Transactions {
+            request: block_ids,
+            response: to_record(&response),
+        });
+        response
+    }
+
+    async fn chain_config_hash(&self, peer: PeerId) -> anyhow::Result<Option<Bytes32>> {
+        self.inner.chain_config_hash(peer).await
+    }
+
+    fn report_peer(&self, peer: PeerId, report: PeerReportReason) -> anyhow::Result<()> {
+        self.inner.report_peer(peer, report)
+    }
+}
+
+fn to_record<T: Clone>(response: &anyhow::Result<T>) -> Result<T, String> {
+    response
+        .as_ref()
+        .map(Clone::clone)
+        .map_err(|e| e.to_string())
+}
+
+/// Serves the recorded responses instead of the network.
+///
+/// The responses are matched by the request, so the order of the concurrent
+/// requests doesn't matter. Repeated requests receive the recorded responses
+/// in the order they were recorded. A request without a recorded response fails.
+pub struct ReplayPeerToPeer {
+    headers: SharedMutex<HashMap<Range<u32>, VecDeque<HeadersResponse>>>,
+    transactions:
+        SharedMutex<HashMap<SourcePeer<Range<u32>>, VecDeque<TransactionsResponse>>>,
+    tip: Option<BlockHeight>,
+}
+
+impl ReplayPeerToPeer {
+    /// Creates the replay from the records.
+    pub fn new(records: impl IntoIterator<Item = Record>) -> Self {
+        let mut headers: HashMap<_, VecDeque<_>> = HashMap::new();
+        let mut transactions: HashMap<_, VecDeque<_>> = HashMap::new();
+        let mut tip = None;
+        for record in records {
+            match record {
+                Record::SealedBlockHeaders { range, response } => {
+                    let last_height = response
+                        .as_ref()
+                        .ok()
+                        .and_then(|headers| headers.data.as_ref())
+                        .and_then(|headers| headers.last())
+                        .map(|header| *header.entity.height());
+                    tip = tip.max(last_height);
+                    headers.entry(range).or_default().push_back(response);
+                }
+                Record::Transactions { request, response } => {
+                    transactions.entry(request).or_default().push_back(response);
+                }
+            }
+        }
+        Self {
+            headers: SharedMutex::new(headers),
+            transactions: SharedMutex::new(transactions),
+            tip,
+        }
+    }
+
+    /// Reads the records from the file created by the [`RecordingPeerToPeer`].
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let file = BufReader::new(File::open(path)?);
+        let records = file
+            .lines()
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect::<anyhow::Result<Vec<Record>>>()?;
+        Ok(Self::new(records))
+    }
+}
+
+#[async_trait::async_trait]
+impl PeerToPeerPort for ReplayPeerToPeer {
+    /// Yields the highest recorded height once.
+    fn height_stream(&self) -> BoxStream<BlockHeight> {
+        futures::stream::iter(self.tip)
+            .chain(futures::stream::pending())
+            .into_boxed()
+    }
+
+    async fn get_sealed_block_headers(
+        &self,
+        block_height_range: Range<u32>,
+    ) -> anyhow::Result<SourcePeer<Option<Vec<SealedBlockHeader>>>> {
+        let response = self.headers.apply(|headers| {
+            headers
+                .get_mut(&block_height_range)
+                .and_then(VecDeque::pop_front)
+        });
+        from_record(response, || {
+            format!("No recorded headers for the range {block_height_range:?}")
+        })
+    }
+
+    /// Serves the headers recorded for the range regardless of the peer.
+    async fn get_sealed_block_headers_from_peer(
+        &self,
+        block_height_range: SourcePeer<Range<u32>>,
+    ) -> anyhow::Result<Option<Vec<SealedBlockHeader>>> {
+        self.get_sealed_block_headers(block_height_range.data)
+            .await
+            .map(|headers| headers.data)
+    }
+
+    /// There are no peers to choose from, so the recorded responses
+    /// are served by the range.
+    async fn peers_with_height(&self, _: BlockHeight) -> anyhow::Result<Vec<PeerId>> {
+        Ok(vec![])
+    }
+
+    async fn get_transactions(
+        &self,
+        block_ids: SourcePeer<Range<u32>>,
+    ) -> anyhow::Result<Option<Vec<Transactions>>> {
+        let response = self.transactions.apply(|transactions| {
+            transactions
+                .get_mut(&block_ids)
+                .and_then(VecDeque::pop_front)
+        });
+        from_record(response, || {
+            format!("No recorded transactions for the request {block_ids:?}")
+        })
+    }
+
+    /// The recorded peers are trusted to be on the same chain.
+    async fn chain_config_hash(&self, _peer: PeerId) -> anyhow::Result<Option<Bytes32>> {
+        Ok(None)
+    }
+
+    fn report_peer(
+        &self,
+        _peer: PeerId,
+        _report: PeerReportReason,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+fn from_record<T>(
+    response: Option<Result<T, String>>,
+    missing: impl FnOnce() -> String,
+) -> anyhow::Result<T> {
+    match response {
+        Some(response) => response.map_err(|e| anyhow::anyhow!(e)),
+        None => Err(anyhow::anyhow!(missing())),
+    }
+}
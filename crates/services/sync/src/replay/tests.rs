@@ -0,0 +1,116 @@
+#![allow(non_snake_case)]
+
+use super::*;
+use crate::{
+    import::{
+        test_helpers::{
+            empty_header,
+            random_peer,
+        },
+        Config,
+        Import,
+    },
+    ports::{
+        MockBlockImporterPort,
+        MockConsensusPort,
+        MockPeerToPeerPort,
+    },
+    state::State,
+};
+use fuel_core_types::blockchain::primitives::BlockId;
+use std::sync::{
+    Arc,
+    Mutex,
+};
+use tokio::sync::Notify;
+
+fn network() -> MockPeerToPeerPort {
+    let mut p2p = MockPeerToPeerPort::default();
+    p2p.expect_get_sealed_block_headers().returning(|range| {
+        let headers = Some(range.map(empty_header).collect());
+        Ok(random_peer().bind(headers))
+    });
+    p2p.expect_get_transactions().returning(|block_ids| {
+        let data = block_ids.data;
+        let v = data.into_iter().map(|_| Transactions::default()).collect();
+        Ok(Some(v))
+    });
+    p2p.expect_report_peer().returning(|_, _| Ok(()));
+    p2p
+}
+
+async fn import<P>(p2p: P, observed: u32) -> (State, Vec<BlockId>)
+where
+    P: PeerToPeerPort + Send + Sync + 'static,
+{
+    let committed = Arc::new(Mutex::new(vec![]));
+    let mut executor = MockBlockImporterPort::default();
+    executor.expect_can_accept().returning(|| true);
+    executor.expect_execute_and_commit().returning({
+        let committed = committed.clone();
+        move |block| {
+            committed.lock().unwrap().push(block.entity.id());
+            Ok(())
+        }
+    });
+    let mut consensus = MockConsensusPort::default();
+    consensus
+        .expect_check_sealed_header()
+        .returning(|_| Ok(true));
+    consensus.expect_await_da_height().returning(|_| Ok(()));
+
+    let state: SharedMutex<State> = State::new(None, observed).into();
+    let params = Config {
+        block_stream_buffer_size: 10,
+        header_batch_size: 4,
+        ..Default::default()
+    };
+    let import = Import::new(
+        state.clone(),
+        Arc::new(Notify::new()),
+        params,
+        Arc::new(p2p),
+        Arc::new(executor),
+        Arc::new(consensus),
+    );
+    let (_tx, shutdown) = tokio::sync::watch::channel(fuel_core_services::State::Started);
+    let mut watcher = shutdown.into();
+    import.notify_one();
+    let _ = import.import(&mut watcher).await;
+
+    let state = state.apply(|s| s.clone());
+    let committed = committed.lock().unwrap().clone();
+    (state, committed)
+}
+
+#[tokio::test]
+async fn replay__reproduces_the_recorded_import() {
+    // given
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("network.jsonl");
+    let recording = RecordingPeerToPeer::new(network(), &path).unwrap();
+    let recorded = import(recording, 9).await;
+
+    // when
+    let replay = ReplayPeerToPeer::open(&path).unwrap();
+    let replayed = import(replay, 9).await;
+
+    // then
+    assert_eq!(recorded.0, State::new(9, None));
+    assert_eq!(recorded.1.len(), 10);
+    assert_eq!(recorded, replayed);
+}
+
+#[tokio::test]
+async fn replay__fails_the_requests_without_records() {
+    // given
+    let replay = ReplayPeerToPeer::new([]);
+
+    // when
+    let headers = replay.get_sealed_block_headers(0..4).await;
+    let transactions = replay.get_transactions(random_peer().bind(0..4)).await;
+
+    // then
+    assert!(headers.is_err());
+    assert!(transactions.is_err());
+}
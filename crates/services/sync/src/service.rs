@@ -6,6 +6,7 @@ use crate::{
         Config,
         Import,
     },
+    peer_selector::PeerSelector,
     ports::{
         self,
         BlockImporterPort,
@@ -36,12 +37,18 @@ use tokio::sync::Notify;
 mod tests;
 
 /// Creates an instance of runnable sync service.
+/// The peers of the headers are chosen by the `peer_selector`, if any,
+/// otherwise by the network. The failed heights are persisted by
+/// the `failed_heights_store`, if any, when the `persist_failed_heights`
+/// of the `params` is enabled.
 pub fn new_service<P, E, C>(
     current_fuel_block_height: BlockHeight,
     p2p: P,
     executor: E,
     consensus: C,
     params: Config,
+    failed_heights_store: Option<Arc<dyn ports::FailedHeightsStore>>,
+    peer_selector: Option<Arc<dyn PeerSelector>>,
 ) -> anyhow::Result<ServiceRunner<SyncTask<P, E, C>>>
 where
     P: ports::PeerToPeerPort + Send + Sync + 'static,
@@ -59,6 +66,8 @@ where
         p2p,
         executor,
         consensus,
+        failed_heights_store,
+        peer_selector,
     )?))
 }
 
@@ -82,6 +91,7 @@ where
     E: BlockImporterPort + Send + Sync + 'static,
     C: ConsensusPort + Send + Sync + 'static,
 {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         height_stream: BoxStream<BlockHeight>,
         committed_height_stream: BoxStream<BlockHeight>,
@@ -90,6 +100,8 @@ where
         p2p: P,
         executor: E,
         consensus: C,
+        failed_heights_store: Option<Arc<dyn ports::FailedHeightsStore>>,
+        peer_selector: Option<Arc<dyn PeerSelector>>,
     ) -> anyhow::Result<Self> {
         let notify = Arc::new(Notify::new());
         let state = SharedMutex::new(state);
@@ -102,7 +114,13 @@ where
             state.clone(),
             notify.clone(),
         );
-        let import = Import::new(state, notify, params, p2p, executor, consensus);
+        let mut import = Import::new(state, notify, params, p2p, executor, consensus);
+        if let Some(failed_heights_store) = failed_heights_store {
+            import = import.with_failed_heights_store(failed_heights_store);
+        }
+        if let Some(peer_selector) = peer_selector {
+            import = import.with_peer_selector(peer_selector);
+        }
         let import_task_handle = ServiceRunner::new(ImportTask(import));
         Ok(Self {
             sync_heights,
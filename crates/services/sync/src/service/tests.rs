@@ -38,6 +38,7 @@ async fn test_new_service() {
         })
         .into_boxed()
     });
+    p2p.expect_peers_with_height().returning(|_| Ok(vec![]));
     p2p.expect_get_sealed_block_headers().returning(|range| {
         let peer = random_peer();
         let headers = Some(range.map(empty_header).collect::<Vec<_>>());
@@ -50,6 +51,7 @@ async fn test_new_service() {
         Ok(Some(v))
     });
     let mut importer = MockBlockImporterPort::default();
+    importer.expect_can_accept().returning(|| true);
     importer
         .expect_committed_height_stream()
         .returning(|| futures::stream::pending::<BlockHeight>().into_boxed());
@@ -66,8 +68,10 @@ async fn test_new_service() {
     let params = Config {
         block_stream_buffer_size: 10,
         header_batch_size: 10,
+        ..Default::default()
     };
-    let s = new_service(4u32.into(), p2p, importer, consensus, params).unwrap();
+    let s =
+        new_service(4u32.into(), p2p, importer, consensus, params, None, None).unwrap();
 
     assert_eq!(
         s.start_and_await().await.unwrap(),
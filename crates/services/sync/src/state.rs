@@ -1,10 +1,14 @@
 //! State of the sync service.
 
+use fuel_core_types::fuel_types::BlockHeight;
 use std::{
     cmp::Ordering,
     ops::RangeInclusive,
 };
 
+pub use range_set::RangeSet;
+
+mod range_set;
 #[cfg(test)]
 mod test;
 
@@ -14,9 +18,12 @@ mod test;
 /// The state takes evidence and produces a status.
 pub struct State {
     status: Status,
+    /// The heights dropped from the processing range by the failures
+    /// that are not committed yet.
+    failed: RangeSet,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 /// Status of the sync service.
 pub enum Status {
     /// The service is not initialized and there is nothing to sync.
@@ -27,6 +34,42 @@ pub enum Status {
     Committed(u32),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+/// The copy of the [`State`] that can be persisted and restored on startup,
+/// so the state doesn't have to be rebuilt from the committed heights.
+pub struct StateSnapshot {
+    /// The status of the sync service.
+    pub status: Status,
+    /// The ranges of the failed heights.
+    pub failed: Vec<RangeInclusive<u32>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+/// The machine-readable report of the [`State`] for the tooling.
+pub struct SyncStateReport {
+    /// The committed heights.
+    pub committed: Vec<RangeInclusive<u32>>,
+    /// The heights dropped from the processing range by the failures.
+    pub failed: Vec<RangeInclusive<u32>>,
+    /// The heights left to process.
+    pub pending: Vec<RangeInclusive<u32>>,
+    /// The highest known height, whatever its status.
+    pub tip: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Status of a single height.
+pub enum HeightStatus {
+    /// The height is committed.
+    Committed,
+    /// The height failed to process and is not pending anymore.
+    Failed,
+    /// The height is waiting to be processed.
+    Pending,
+    /// The height is not observed yet.
+    Unknown,
+}
+
 impl State {
     #[tracing::instrument(skip_all)]
     /// Create a new state from the current committed and observed heights.
@@ -60,7 +103,10 @@ impl State {
             (None, None) => Status::Uninitialized,
         };
         tracing::debug!("Initial status: {:?}", status);
-        Self { status }
+        Self {
+            status,
+            failed: RangeSet::new(),
+        }
     }
 
     #[tracing::instrument]
@@ -78,10 +124,129 @@ impl State {
         }
     }
 
+    /// Get the heights dropped from the processing range by the failures.
+    ///
+    /// Every failure adds the heights it dropped, and they are kept until they are
+    /// committed and trimmed with [`Self::trim_below`], or reset. A failed height
+    /// observed again is pending and failed at the same time.
+    pub fn failed_heights(&self) -> &RangeSet {
+        &self.failed
+    }
+
+    /// Take the snapshot of the state for the persistence.
+    pub fn snapshot(&self) -> StateSnapshot {
+        StateSnapshot {
+            status: self.status.clone(),
+            failed: self.failed.ranges().collect(),
+        }
+    }
+
+    #[tracing::instrument]
+    /// Replace the state with the persisted `snapshot`.
+    pub fn restore(&mut self, snapshot: StateSnapshot) {
+        let StateSnapshot { status, failed } = snapshot;
+        self.apply_status(Some(status));
+        self.failed = failed.into_iter().collect();
+    }
+
+    #[tracing::instrument]
+    /// Replace the failed heights with the persisted `failed` heights.
+    ///
+    /// The heights committed since the `failed` heights were persisted
+    /// are not tracked anymore.
+    pub fn restore_failed(&mut self, failed: RangeSet) {
+        self.failed = failed;
+        if let Some(committed) = self.committed_height() {
+            self.trim_below(committed.saturating_add(1));
+        }
+    }
+
+    /// Build the report of the committed, failed and pending heights.
+    pub fn to_report(&self) -> SyncStateReport {
+        let committed = self
+            .committed_height()
+            .map(|committed| 0..=committed)
+            .into_iter()
+            .collect::<Vec<_>>();
+        let failed = self.failed.ranges().collect::<Vec<_>>();
+        let pending = self.ranges().collect::<Vec<_>>();
+        let tip = committed
+            .iter()
+            .chain(failed.iter())
+            .chain(pending.iter())
+            .map(|range| *range.end())
+            .max();
+        SyncStateReport {
+            committed,
+            failed,
+            pending,
+            tip,
+        }
+    }
+
+    /// Get the ranges of heights left to process.
+    ///
+    /// The blocks are committed in the order of heights, so the heights left to
+    /// process are always one range right above the committed height, and the commits
+    /// only move its start. The failed heights, which can be scattered, are kept in
+    /// the [`RangeSet`] returned by [`Self::failed_heights`].
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<u32>> {
+        self.process_range().into_iter()
+    }
+
+    /// Get the number of heights left to process.
+    pub fn len(&self) -> usize {
+        self.ranges()
+            .map(|range| range.size_hint().0)
+            .fold(0usize, |len, range_len| len.saturating_add(range_len))
+    }
+
+    /// Returns `true` if there are no heights left to process.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the latest committed height, if any.
+    pub fn committed_height(&self) -> Option<u32> {
+        match &self.status {
+            Status::Uninitialized => None,
+            Status::Processing(range) => range.start().checked_sub(1),
+            Status::Committed(height) => Some(*height),
+        }
+    }
+
+    /// Get the status of the `height`.
+    ///
+    /// The failed heights that are observed again are reported as pending.
+    pub fn height_status(&self, height: BlockHeight) -> HeightStatus {
+        let height = *height;
+        if self
+            .committed_height()
+            .map_or(false, |committed| height <= committed)
+        {
+            HeightStatus::Committed
+        } else if self
+            .process_range()
+            .map_or(false, |range| range.contains(&height))
+        {
+            HeightStatus::Pending
+        } else if self.failed.contains(height) {
+            HeightStatus::Failed
+        } else {
+            HeightStatus::Unknown
+        }
+    }
+
     #[tracing::instrument]
     /// Record that a block has been committed.
-    pub fn commit(&mut self, height: u32) {
+    ///
+    /// The commit is idempotent: committing a height that is already
+    /// committed, or lower than it, leaves the state unchanged.
+    /// Returns `true` if the height was newly committed.
+    pub fn commit(&mut self, height: u32) -> bool {
         let new_status = match &self.status {
+            // The height below the processing range is already committed.
+            Status::Processing(range) if height < *range.start() => None,
             // Currently processing a range and recording a commit.
             Status::Processing(range) => match height.cmp(range.end()) {
                 // The commit is less than the end of the range, so the range
@@ -104,7 +269,9 @@ impl State {
                 }
             }
         };
+        let newly_committed = new_status.is_some();
         self.apply_status(new_status);
+        newly_committed
     }
 
     #[tracing::instrument]
@@ -135,6 +302,37 @@ impl State {
         status_change
     }
 
+    #[tracing::instrument]
+    /// Move the failed heights back to the processing range.
+    /// Returns `true` if there were failed heights to reset.
+    pub fn reset_failed(&mut self) -> bool {
+        match std::mem::take(&mut self.failed).last() {
+            Some(last) => {
+                // The failed heights always follow the processing range or
+                // the committed height, so observing the highest one restores
+                // all of them.
+                self.observe(last);
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[tracing::instrument]
+    /// Drop the tracking of the failed heights below the `height`,
+    /// whichever failures dropped them.
+    ///
+    /// Only the committed heights can be dropped, so the `height` is capped right
+    /// above the committed height. The status of the heights at or above the `height`
+    /// is unchanged. Returns `true` if any heights were dropped.
+    pub fn trim_below(&mut self, height: u32) -> bool {
+        let Some(committed) = self.committed_height() else {
+            return false
+        };
+        let watermark = height.min(committed.saturating_add(1));
+        self.failed.remove_below(watermark)
+    }
+
     #[tracing::instrument]
     /// Record that a range of blocks have failed to process.
     pub fn failed_to_process(&mut self, range: RangeInclusive<u32>) {
@@ -185,7 +383,16 @@ impl State {
                         })
                     }),
             });
+        let processing = self.process_range();
+        let status_change = status.is_some();
         self.apply_status(status);
+        if let (true, Some(processing)) = (status_change, processing) {
+            // The heights above the new processing range are dropped.
+            let first_failed = self
+                .process_range()
+                .map_or(*processing.start(), |range| range.end().saturating_add(1));
+            self.failed.insert(first_failed..=*processing.end());
+        }
     }
 
     fn apply_status(&mut self, status: Option<Status>) {
@@ -200,6 +407,13 @@ impl State {
         }
     }
 
+    #[cfg(test)]
+    /// Add the heights dropped by a failure.
+    pub fn with_failed(mut self, failed: RangeInclusive<u32>) -> Self {
+        self.failed.insert(failed);
+        self
+    }
+
     #[cfg(test)]
     /// Get the current observed height.
     pub fn proposed_height(&self) -> Option<&u32> {
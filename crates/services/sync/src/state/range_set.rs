@@ -0,0 +1,103 @@
+//! The compacted set of heights.
+
+use std::{
+    collections::BTreeMap,
+    ops::RangeInclusive,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+/// The set of heights stored as the sorted disjoint ranges.
+///
+/// The overlapping and adjacent ranges are merged on insertion, so the set
+/// takes one entry per gap between its heights instead of one per height.
+pub struct RangeSet {
+    /// The start of each range mapped to its inclusive end.
+    ranges: BTreeMap<u32, u32>,
+}
+
+impl RangeSet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the heights of the `range` to the set.
+    pub fn insert(&mut self, range: RangeInclusive<u32>) {
+        if range.is_empty() {
+            return
+        }
+        let (mut start, mut end) = range.into_inner();
+        // The ranges are disjoint, so going down from the end of the new range,
+        // the ends of the ranges decrease, and the merged ones come first.
+        let merged = self
+            .ranges
+            .range(..=end.saturating_add(1))
+            .rev()
+            .take_while(|(_, merged_end)| **merged_end >= start.saturating_sub(1))
+            .map(|(merged_start, merged_end)| (*merged_start, *merged_end))
+            .collect::<Vec<_>>();
+        for (merged_start, merged_end) in merged {
+            self.ranges.remove(&merged_start);
+            start = start.min(merged_start);
+            end = end.max(merged_end);
+        }
+        self.ranges.insert(start, end);
+    }
+
+    /// Remove the heights below the `height` from the set.
+    /// Returns `true` if any heights were removed.
+    pub fn remove_below(&mut self, height: u32) -> bool {
+        let below = self
+            .ranges
+            .range(..height)
+            .map(|(start, end)| (*start, *end))
+            .collect::<Vec<_>>();
+        for (start, end) in &below {
+            self.ranges.remove(start);
+            if *end >= height {
+                self.ranges.insert(height, *end);
+            }
+        }
+        !below.is_empty()
+    }
+
+    /// Returns `true` if the `height` is in the set.
+    pub fn contains(&self, height: u32) -> bool {
+        self.ranges
+            .range(..=height)
+            .next_back()
+            .map_or(false, |(_, end)| height <= *end)
+    }
+
+    /// Get the disjoint ranges of the set in the ascending order.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<u32>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..=*end)
+    }
+
+    /// Get the number of heights in the set.
+    pub fn len(&self) -> usize {
+        self.ranges()
+            .map(|range| range.size_hint().0)
+            .fold(0usize, |len, range_len| len.saturating_add(range_len))
+    }
+
+    /// Returns `true` if the set has no heights.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Get the highest height of the set, if any.
+    pub fn last(&self) -> Option<u32> {
+        self.ranges.values().next_back().copied()
+    }
+}
+
+impl FromIterator<RangeInclusive<u32>> for RangeSet {
+    fn from_iter<T: IntoIterator<Item = RangeInclusive<u32>>>(iter: T) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(non_snake_case)]
+
 use super::*;
 use test_case::test_case;
 
@@ -113,3 +115,347 @@ fn test_failed(mut state: State, range: RangeInclusive<u32>) -> Status {
     state.failed_to_process(range);
     state.status
 }
+
+#[test_case(State::new(None, 10), 4)]
+#[test_case(State::new(2, 10), 4)]
+#[test_case(State::new(2, 10), 10)]
+#[test_case(State::new(None, None), 4)]
+fn commit__same_height_twice_is_a_no_op(mut state: State, height: u32) {
+    // given
+    assert!(state.commit(height));
+    let status = state.status.clone();
+
+    // when
+    let newly_committed = state.commit(height);
+
+    // then
+    assert!(!newly_committed);
+    assert_eq!(state.status, status);
+}
+
+#[test]
+fn commit__below_the_processing_range_keeps_the_range() {
+    // given
+    let mut state = State::new(5, 10);
+
+    // when
+    let newly_committed = state.commit(2);
+
+    // then
+    assert!(!newly_committed);
+    assert_eq!(state.status, Status::Processing(6..=10));
+}
+
+#[test]
+fn state__stays_compact_after_many_adjacent_commits() {
+    let mut state = State::new(None, 10_000);
+
+    for height in 0..5_000 {
+        state.commit(height);
+        assert_eq!(state.ranges().count(), 1);
+    }
+
+    assert_eq!(state.ranges().collect::<Vec<_>>(), vec![5_000..=10_000]);
+    assert_eq!(state.len(), 5_001);
+}
+
+#[test]
+fn state__stays_compact_after_many_adjacent_failures() {
+    let mut state = State::new(None, 10_000);
+
+    for height in (5_000..=10_000).rev() {
+        state.failed_to_process(height..=height);
+        assert!(state.ranges().count() <= 1);
+    }
+
+    assert_eq!(state.ranges().collect::<Vec<_>>(), vec![0..=4_999]);
+    assert_eq!(state.len(), 5_000);
+    assert_eq!(failed_ranges(&state), vec![5_000..=10_000]);
+    assert_eq!(state.failed_heights().len(), 5_001);
+}
+
+#[test]
+fn failed_to_process__keeps_the_heights_of_the_earlier_failures() {
+    // given
+    let mut state = State::new(10, 30);
+    state.failed_to_process(25..=30);
+    state.commit(14);
+
+    // when
+    state.failed_to_process(18..=20);
+
+    // then
+    assert_eq!(state.process_range(), Some(15..=17));
+    assert_eq!(failed_ranges(&state), vec![18..=30]);
+}
+
+#[test]
+fn failed_to_process__keeps_the_separate_failures_apart() {
+    // given
+    let mut state = State::new(10, 20);
+    state.failed_to_process(15..=20);
+    state.commit(12);
+    state.observe(30);
+
+    // when
+    state.failed_to_process(25..=30);
+
+    // then
+    assert_eq!(failed_ranges(&state), vec![15..=20, 25..=30]);
+    assert_eq!(state.height_status(22.into()), HeightStatus::Pending);
+    assert_eq!(state.height_status(26.into()), HeightStatus::Failed);
+}
+
+#[test]
+fn range_set__merges_the_overlapping_and_adjacent_ranges() {
+    // given
+    let mut set = RangeSet::new();
+
+    // when
+    set.insert(10..=12);
+    set.insert(20..=25);
+    set.insert(13..=14);
+    set.insert(24..=30);
+    set.insert(0..=0);
+
+    // then
+    assert_eq!(
+        set.ranges().collect::<Vec<_>>(),
+        vec![0..=0, 10..=14, 20..=30]
+    );
+    assert_eq!(set.len(), 17);
+    assert_eq!(set.last(), Some(30));
+    assert!(set.contains(14));
+    assert!(!set.contains(15));
+}
+
+#[test]
+fn range_set__stays_compact_after_many_adjacent_insertions() {
+    let mut set = RangeSet::new();
+
+    for height in (0..10_000).filter(|height| height % 2 == 0) {
+        set.insert(height..=height);
+    }
+    for height in (0..10_000).filter(|height| height % 2 == 1) {
+        set.insert(height..=height);
+    }
+
+    assert_eq!(set.ranges().collect::<Vec<_>>(), vec![0..=9_999]);
+    assert_eq!(set.len(), 10_000);
+}
+
+#[test]
+fn range_set__remove_below_splits_the_range_at_the_height() {
+    // given
+    let mut set = [0..=5, 10..=20, 30..=40].into_iter().collect::<RangeSet>();
+
+    // when
+    let removed = set.remove_below(15);
+
+    // then
+    assert!(removed);
+    assert_eq!(set.ranges().collect::<Vec<_>>(), vec![15..=20, 30..=40]);
+    assert!(!set.remove_below(15));
+}
+
+fn failed_ranges(state: &State) -> Vec<RangeInclusive<u32>> {
+    state.failed_heights().ranges().collect()
+}
+
+#[test_case(State::new(None, None) => 0)]
+#[test_case(State::new(10, None) => 0)]
+#[test_case(State::new(None, 10) => 11)]
+#[test_case(State::new(1, 10) => 9)]
+fn test_len(state: State) -> usize {
+    assert_eq!(state.is_empty(), state.ranges().next().is_none());
+    state.len()
+}
+
+#[test_case(State::new(10, 20), 5 => HeightStatus::Committed)]
+#[test_case(State::new(10, 20), 10 => HeightStatus::Committed)]
+#[test_case(State::new(10, 20), 15 => HeightStatus::Pending)]
+#[test_case(State::new(10, 20), 21 => HeightStatus::Unknown)]
+#[test_case(State::new(None, None), 0 => HeightStatus::Unknown)]
+#[test_case(State::new(None, 5), 0 => HeightStatus::Pending)]
+fn test_height_status(state: State, height: u32) -> HeightStatus {
+    state.height_status(height.into())
+}
+
+#[test]
+fn height_status__reports_each_of_the_four_states() {
+    // given
+    let mut state = State::new(10, 20);
+
+    // when
+    state.failed_to_process(15..=16);
+
+    // then
+    assert_eq!(state.height_status(10.into()), HeightStatus::Committed);
+    assert_eq!(state.height_status(12.into()), HeightStatus::Pending);
+    assert_eq!(state.height_status(15.into()), HeightStatus::Failed);
+    assert_eq!(state.height_status(20.into()), HeightStatus::Failed);
+    assert_eq!(state.height_status(21.into()), HeightStatus::Unknown);
+}
+
+#[test]
+fn height_status__failed_height_is_pending_again_after_observation() {
+    // given
+    let mut state = State::new(10, 20);
+    state.failed_to_process(15..=16);
+
+    // when
+    state.observe(20);
+
+    // then
+    assert_eq!(state.height_status(15.into()), HeightStatus::Pending);
+}
+
+#[test]
+fn reset_failed__moves_the_failed_heights_back_to_the_processing_range() {
+    // given
+    let mut state = State::new(10, 20);
+    state.commit(12);
+    state.failed_to_process(15..=17);
+    assert_eq!(state.process_range(), Some(13..=14));
+
+    // when
+    let reset = state.reset_failed();
+
+    // then
+    assert!(reset);
+    assert_eq!(state.process_range(), Some(13..=20));
+    assert_eq!(state.height_status(16.into()), HeightStatus::Pending);
+}
+
+#[test]
+fn reset_failed__without_failures_changes_nothing() {
+    // given
+    let mut state = State::new(10, 20);
+
+    // when
+    let reset = state.reset_failed();
+
+    // then
+    assert!(!reset);
+    assert_eq!(state.process_range(), Some(11..=20));
+}
+
+#[test]
+fn reset_failed__restores_the_heights_after_the_whole_range_failed() {
+    // given
+    let mut state = State::new(10, 20);
+    state.failed_to_process(11..=20);
+    assert_eq!(state.process_range(), None);
+
+    // when
+    state.reset_failed();
+
+    // then
+    assert_eq!(state.process_range(), Some(11..=20));
+}
+
+#[test]
+fn trim_below__drops_the_committed_failed_heights() {
+    // given
+    let mut state = State::new(10, 20);
+    state.failed_to_process(15..=17);
+    state.observe(20);
+    state.commit(16);
+    let statuses_above = |state: &State| {
+        (17..=21)
+            .map(|height| state.height_status(height.into()))
+            .collect::<Vec<_>>()
+    };
+    let before = statuses_above(&state);
+
+    // when
+    let trimmed = state.trim_below(17);
+
+    // then
+    assert!(trimmed);
+    assert_eq!(failed_ranges(&state), vec![17..=20]);
+    assert_eq!(state.height_status(15.into()), HeightStatus::Committed);
+    assert_eq!(statuses_above(&state), before);
+}
+
+#[test]
+fn trim_below__keeps_the_heights_that_are_not_committed() {
+    // given
+    let mut state = State::new(10, 20);
+    state.failed_to_process(15..=16);
+
+    // when
+    let trimmed = state.trim_below(18);
+
+    // then
+    assert!(!trimmed);
+    assert_eq!(failed_ranges(&state), vec![15..=20]);
+    assert_eq!(state.height_status(15.into()), HeightStatus::Failed);
+}
+
+#[test]
+fn trim_below__drops_the_committed_heights_of_every_failure() {
+    // given
+    let mut state = State::new(10, 20);
+    state.failed_to_process(13..=14);
+    state.observe(30);
+    state.failed_to_process(18..=20);
+    state.observe(40);
+    state.failed_to_process(35..=40);
+    assert_eq!(failed_ranges(&state), vec![13..=30, 35..=40]);
+    state.observe(40);
+    state.commit(36);
+
+    // when
+    let trimmed = state.trim_below(37);
+
+    // then
+    assert!(trimmed);
+    assert_eq!(failed_ranges(&state), vec![37..=40]);
+    assert_eq!(state.height_status(20.into()), HeightStatus::Committed);
+    assert_eq!(state.height_status(38.into()), HeightStatus::Pending);
+}
+
+#[test]
+fn restore__reproduces_the_snapshotted_state() {
+    // given
+    let mut state = State::new(10, 30);
+    state.commit(14);
+    state.failed_to_process(20..=22);
+    let snapshot = state.snapshot();
+    let encoded = serde_json::to_string(&snapshot).unwrap();
+    let decoded: StateSnapshot = serde_json::from_str(&encoded).unwrap();
+
+    // when
+    let mut restored = State::new(None, None);
+    restored.restore(decoded);
+
+    // then
+    assert_eq!(restored.process_range(), Some(15..=19));
+    assert_eq!(restored.process_range(), state.process_range());
+    assert_eq!(failed_ranges(&restored), vec![20..=30]);
+    assert_eq!(restored.failed_heights(), state.failed_heights());
+    assert_eq!(restored.committed_height(), state.committed_height());
+}
+
+#[test]
+fn to_report__serializes_the_ranges_of_each_status() {
+    // given
+    let mut state = State::new(10, 30);
+    state.commit(14);
+    state.failed_to_process(20..=22);
+
+    // when
+    let report = serde_json::to_value(state.to_report()).unwrap();
+
+    // then
+    assert_eq!(
+        report,
+        serde_json::json!({
+            "committed": [{ "start": 0, "end": 14 }],
+            "failed": [{ "start": 20, "end": 30 }],
+            "pending": [{ "start": 15, "end": 19 }],
+            "tip": 30,
+        })
+    );
+}
@@ -1,5 +1,14 @@
+use crate::transaction_selector::{
+    FeeOracle,
+    PriorityLane,
+    SelectionMode,
+    TieBreak,
+};
 use fuel_core_chain_config::ChainConfig;
-use std::time::Duration;
+use std::{
+    sync::Arc,
+    time::Duration,
+};
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -19,6 +28,44 @@ pub struct Config {
     pub transaction_ttl: Duration,
     /// The number of allowed active transaction status subscriptions.
     pub number_of_active_subscription: usize,
+    /// The strategy used to select transactions for the next block.
+    pub selection_mode: SelectionMode,
+    /// The maximum time spent on selecting transactions for the next block.
+    /// Once it is elapsed, the block is produced with the transactions selected so far.
+    pub selection_timeout: Option<Duration>,
+    /// The number of the recently included transactions remembered by the pool.
+    /// They are never selected again, even if they are still in the pool.
+    pub recently_included_capacity: usize,
+    /// The part of each block reserved for the prioritized transactions.
+    /// `None` selects all transactions by the `selection_mode`.
+    pub priority_lane: Option<PriorityLane>,
+    /// Re-verifies the signatures and predicates of the transactions during the selection.
+    /// It is required if the pool accepts transactions without the checks.
+    pub verify_signatures: bool,
+    /// Converts the fees paid in the non-base assets for the ordering of the selection.
+    /// `None` treats all fees as paid in the base asset.
+    pub fee_oracle: Option<Arc<dyn FeeOracle>>,
+    /// The maximum number of the distinct contracts called by the transactions
+    /// of one block. `None` means no limit.
+    pub max_contracts_per_block: Option<usize>,
+    /// The maximum gas used by the predicates of the transactions of one block,
+    /// in addition to the limit of the total gas. `None` means no limit.
+    pub max_predicate_gas: Option<u64>,
+    /// The maximum number of the inputs of the transactions of one block,
+    /// in addition to the limit of the total gas. `None` means no limit.
+    pub max_total_inputs: Option<usize>,
+    /// The maximum number of the witnesses of the transactions of one block,
+    /// in addition to the limit of the total gas. `None` means no limit.
+    pub max_total_witnesses: Option<usize>,
+    /// The maximum number of the transactions of one sender in one block.
+    /// The sender is the owner of the first input of the transaction. `None` means no limit.
+    pub max_txs_per_sender: Option<usize>,
+    /// The maximum gas used by the transactions of one sender in one block,
+    /// in addition to the limit of the total gas. `None` means no limit.
+    pub max_gas_per_sender: Option<u64>,
+    /// The order of the includable transactions with the same gas price.
+    /// `None` orders them by the id, higher first.
+    pub tie_break: Option<TieBreak>,
 }
 
 impl Default for Config {
@@ -67,6 +114,19 @@ impl Config {
             metrics,
             transaction_ttl,
             number_of_active_subscription,
+            selection_mode: SelectionMode::default(),
+            selection_timeout: None,
+            recently_included_capacity: max_tx,
+            priority_lane: None,
+            verify_signatures: false,
+            fee_oracle: None,
+            max_contracts_per_block: None,
+            max_predicate_gas: None,
+            max_total_inputs: None,
+            max_total_witnesses: None,
+            max_txs_per_sender: None,
+            max_gas_per_sender: None,
+            tie_break: None,
         }
     }
 }
@@ -1,4 +1,5 @@
 pub mod dependency;
+pub mod lru;
 pub mod price_sort;
 pub mod sort;
 pub mod time_sort;
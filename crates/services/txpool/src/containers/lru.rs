@@ -0,0 +1,69 @@
+use std::{
+    collections::{
+        HashSet,
+        VecDeque,
+    },
+    hash::Hash,
+};
+
+/// The set of the last `capacity` inserted keys.
+/// Once the capacity is reached, the least recently inserted key is evicted.
+#[derive(Debug, Clone)]
+pub struct LruCache<K> {
+    capacity: usize,
+    order: VecDeque<K>,
+    keys: HashSet<K>,
+}
+
+impl<K> LruCache<K>
+where
+    K: Copy + Eq + Hash,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            keys: HashSet::with_capacity(capacity),
+        }
+    }
+
+    /// Inserts the `key` as the most recent one.
+    pub fn insert(&mut self, key: K) {
+        if self.capacity == 0 {
+            return
+        }
+        if !self.keys.insert(key) {
+            self.order.retain(|k| k != &key);
+        }
+        self.order.push_back(key);
+        while self.order.len() > self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.keys.remove(&evicted);
+            }
+        }
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.keys.contains(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_evicts_the_least_recently_inserted_key() {
+        let mut cache = LruCache::new(2);
+
+        cache.insert(1);
+        cache.insert(2);
+        cache.insert(1);
+        cache.insert(3);
+
+        assert!(cache.contains(&1));
+        assert!(!cache.contains(&2));
+        assert!(cache.contains(&3));
+        assert_eq!(cache.order.len(), 2);
+    }
+}
@@ -34,6 +34,14 @@ pub use service::{
     new_service,
     Service,
 };
+pub use transaction_selector::{
+    select_transactions_stream,
+    PriorityLane,
+    Selection,
+    SelectionMode,
+    SkipReason,
+    TieBreak,
+};
 pub use txpool::TxPool;
 
 #[cfg(any(test, feature = "test-helpers"))]
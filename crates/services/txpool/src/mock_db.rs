@@ -62,6 +62,14 @@ impl MockDb {
             .insert(*message.id(), message);
     }
 
+    pub fn insert_contract(&self, contract_id: ContractId) {
+        self.data
+            .lock()
+            .unwrap()
+            .contracts
+            .insert(contract_id, Contract::default());
+    }
+
     pub fn spend_message(&self, id: Nonce) {
         self.data.lock().unwrap().spent_messages.insert(id);
     }
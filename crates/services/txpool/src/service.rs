@@ -4,11 +4,17 @@ use crate::{
         PeerToPeer,
         TxPoolDb,
     },
-    transaction_selector::select_transactions,
+    transaction_selector::{
+        block_fee,
+        called_contracts,
+        select_transactions,
+        Selection,
+    },
     txpool::{
         check_single_tx,
         check_transactions,
     },
+    types::Page,
     Config,
     Error as TxPoolError,
     TxInfo,
@@ -25,6 +31,7 @@ use fuel_core_services::{
 use fuel_core_types::{
     fuel_tx::{
         ConsensusParameters,
+        ContractId,
         Transaction,
         TxId,
         UniqueIdentifier,
@@ -320,8 +327,15 @@ impl<P2P, ViewProvider> SharedState<P2P, ViewProvider> {
 
     pub fn select_transactions(&self, max_gas: u64) -> Vec<ArcPoolTx> {
         let mut guard = self.txpool.lock();
-        let txs = guard.includable();
-        let sorted_txs = select_transactions(txs, max_gas);
+        // The TTL timer prunes the pool only once per `transaction_ttl`, so the pool
+        // may still contain expired transactions. They are never selected.
+        let expired = guard.prune_old_txs();
+        for tx in expired {
+            self.tx_status_sender
+                .send_squeezed_out(tx.id(), Error::TTLReason);
+        }
+        let selection = self.select_includable(&guard, max_gas, None);
+        let sorted_txs = self.evict_never_includable(&mut guard, selection);
 
         for tx in sorted_txs.iter() {
             guard.remove_committed_tx(&tx.id());
@@ -329,6 +343,112 @@ impl<P2P, ViewProvider> SharedState<P2P, ViewProvider> {
         sorted_txs
     }
 
+    /// Returns the total fee of the transactions that the next block with
+    /// the `max_gas` would include. The pool stays unchanged.
+    pub fn estimate_block_fee(&self, max_gas: u64) -> u64 {
+        let guard = self.txpool.lock();
+        let selection = self.select_includable(&guard, max_gas, None);
+        block_fee(&selection.transactions)
+    }
+
+    /// Returns the transactions that the next block with the `max_gas` would include
+    /// if only the transactions calling any of the `contracts` were includable.
+    /// The pool stays unchanged.
+    pub fn includable_for_contracts(
+        &self,
+        contracts: &[ContractId],
+        max_gas: u64,
+    ) -> Vec<ArcPoolTx> {
+        let guard = self.txpool.lock();
+        self.select_includable(&guard, max_gas, Some(contracts))
+            .transactions
+    }
+
+    /// Selects the includable transactions for the block with the `max_gas`
+    /// without changing the pool. If the `contracts` are set, only the transactions
+    /// calling any of them are includable.
+    fn select_includable(
+        &self,
+        guard: &TxPool<ViewProvider>,
+        max_gas: u64,
+        contracts: Option<&[ContractId]>,
+    ) -> Selection {
+        let deadline = self
+            .config
+            .selection_timeout
+            .and_then(|timeout| tokio::time::Instant::now().checked_add(timeout));
+        let txs = guard.includable().filter(|tx| {
+            contracts.map_or(true, |contracts| {
+                called_contracts(tx).any(|contract_id| contracts.contains(contract_id))
+            })
+        });
+        select_transactions(txs, max_gas, deadline, &self.config)
+    }
+
+    /// Removes the transactions the `selection` found never includable from the pool,
+    /// with the transactions depending on them. Returns the selected transactions.
+    fn evict_never_includable(
+        &self,
+        guard: &mut TxPool<ViewProvider>,
+        selection: Selection,
+    ) -> Vec<ArcPoolTx> {
+        // The losers of a double-spend can never be included, as well as
+        // the transactions depending on them.
+        for (tx, winner) in selection.conflicts {
+            for removed in guard.remove_inner(&tx) {
+                self.tx_status_sender
+                    .send_squeezed_out(removed.id(), Error::Conflict(winner));
+            }
+        }
+        for (tx, replacement) in selection.replaced {
+            for removed in guard.remove_inner(&tx) {
+                self.tx_status_sender
+                    .send_squeezed_out(removed.id(), Error::Replaced(replacement));
+            }
+        }
+        for tx in selection.invalid {
+            for removed in guard.remove_inner(&tx) {
+                self.tx_status_sender
+                    .send_squeezed_out(removed.id(), Error::InvalidSignature);
+            }
+        }
+        for (tx, asset_id) in selection.unsupported_fee_asset {
+            for removed in guard.remove_inner(&tx) {
+                self.tx_status_sender.send_squeezed_out(
+                    removed.id(),
+                    Error::UnsupportedFeeAsset(asset_id),
+                );
+            }
+        }
+        // The skipped transactions may fit into the next blocks, so they stay in the pool.
+        for (tx, reason) in &selection.skipped {
+            tracing::debug!("Transaction {} skipped because of {:?}", tx.id(), reason);
+        }
+        selection.transactions
+    }
+
+    /// Returns up to `first` includable transactions following the `after` cursor,
+    /// ordered by the gas price and then by the configured tie-break. Unlike the
+    /// selection for the next block, it doesn't apply the fee oracle, the priority
+    /// lane, or the selection mode.
+    /// If the `after` transaction is not includable anymore, the page is empty.
+    pub fn includable_page(&self, after: Option<TxId>, first: usize) -> Page<ArcPoolTx> {
+        let guard = self.txpool.lock();
+        let mut includable = guard.includable();
+        if let Some(after) = after {
+            // Skips the transactions up to and including the cursor.
+            // If the cursor is not found, the iterator is exhausted.
+            let _ = includable.find(|tx| tx.id() == after);
+        }
+        let mut includable = includable.peekable();
+        let items = includable.by_ref().take(first).collect();
+        let has_next_page = includable.peek().is_some();
+        Page {
+            items,
+            has_next_page,
+        }
+    }
+
     pub fn remove(&self, ids: Vec<TxId>) -> Vec<ArcPoolTx> {
         self.txpool.lock().remove(&self.tx_status_sender, &ids)
     }
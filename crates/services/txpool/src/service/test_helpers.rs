@@ -17,7 +17,9 @@ use fuel_core_types::{
     },
     fuel_tx::{
         Cacheable,
+        ContractId,
         Input,
+        Output,
         Transaction,
         TransactionBuilder,
         Word,
@@ -59,6 +61,34 @@ impl TestContext {
         tx
     }
 
+    /// Creates the script transaction calling the `contract_id` and
+    /// adds the contract to the database.
+    pub fn setup_contract_call_tx(
+        &self,
+        gas_price: Word,
+        contract_id: ContractId,
+    ) -> Transaction {
+        self.mock_db.insert_contract(contract_id);
+        let (_, gas_coin) = self.setup_coin();
+        let mut tx = TransactionBuilder::script(vec![], vec![])
+            .gas_price(gas_price)
+            .script_gas_limit(1000)
+            .add_input(gas_coin)
+            .add_input(Input::contract(
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                contract_id,
+            ))
+            .add_output(Output::contract(1, Default::default(), Default::default()))
+            .finalize_as_transaction();
+
+        tx.precompute(&Default::default())
+            .expect("Should be able to cache");
+        tx
+    }
+
     pub fn setup_coin(&self) -> (Coin, Input) {
         crate::test_helpers::setup_coin(&mut self.rng.borrow_mut(), Some(&self.mock_db))
     }
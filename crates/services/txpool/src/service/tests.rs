@@ -50,6 +50,43 @@ async fn test_find() {
     service.stop_and_await().await.unwrap();
 }
 
+#[tokio::test]
+async fn includable_page_covers_the_includable_set_without_gaps() {
+    let ctx = TestContext::new().await;
+
+    let txs = [10, 50, 20, 40, 30]
+        .into_iter()
+        .map(|gas_price| Arc::new(ctx.setup_script_tx(gas_price)))
+        .collect::<Vec<_>>();
+
+    let service = ctx.service();
+    let out = service.shared.insert(txs).await;
+    assert!(out.iter().all(|result| result.is_ok()), "{out:?}");
+    let expected = service
+        .shared
+        .txpool
+        .lock()
+        .includable()
+        .map(|tx| tx.id())
+        .collect::<Vec<_>>();
+
+    let mut paged = vec![];
+    let mut after = None;
+    loop {
+        let page = service.shared.includable_page(after, 2);
+        assert!(page.items.len() <= 2);
+        paged.extend(page.items.iter().map(|tx| tx.id()));
+        after = page.items.last().map(|tx| tx.id());
+        if !page.has_next_page {
+            break
+        }
+    }
+
+    assert_eq!(expected.len(), 5);
+    assert_eq!(paged, expected);
+    service.stop_and_await().await.unwrap();
+}
+
 #[tokio::test(start_paused = true)]
 async fn test_prune_transactions() {
     const TIMEOUT: u64 = 10;
@@ -186,6 +223,120 @@ async fn test_prune_transactions_the_oldest() {
     service.stop_and_await().await.unwrap();
 }
 
+#[tokio::test(start_paused = true)]
+async fn select_transactions_skips_expired_txs_before_the_ttl_timer_prunes_them() {
+    const TIMEOUT: u64 = 10;
+
+    let config = Config {
+        transaction_ttl: Duration::from_secs(TIMEOUT),
+        ..Default::default()
+    };
+    let ctx = TestContextBuilder::new()
+        .with_config(config)
+        .build_and_start()
+        .await;
+
+    let tx1 = Arc::new(ctx.setup_script_tx(10));
+    let tx2 = Arc::new(ctx.setup_script_tx(20));
+    let service = ctx.service();
+
+    // insert tx1 at time `1`
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let out = service.shared.insert(vec![tx1.clone()]).await;
+    assert!(out[0].is_ok(), "Tx1 should be OK, got err:{out:?}");
+
+    // insert tx2 at time `6`
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    let out = service.shared.insert(vec![tx2.clone()]).await;
+    assert!(out[0].is_ok(), "Tx2 should be OK, got err:{out:?}");
+
+    // time is now `12`, tx1 is expired, but the TTL timer only ticks at `20`
+    tokio::time::sleep(Duration::from_secs(6)).await;
+    let selected = service.shared.select_transactions(u64::MAX);
+
+    let selected: Vec<_> = selected.iter().map(|tx| tx.id()).collect();
+    assert_eq!(selected, vec![tx2.cached_id().unwrap()]);
+    // the status subscriptions share the TTL, so check the pool instead
+    let out = service.shared.find(vec![tx1.id(&Default::default())]);
+    assert!(out[0].is_none(), "Tx1 should be evicted by the selection");
+
+    service.stop_and_await().await.unwrap();
+}
+
+#[tokio::test]
+async fn estimate_block_fee_equals_the_fee_of_the_selected_transactions() {
+    let ctx = TestContextBuilder::new().build_and_start().await;
+
+    let txs = [10, 50, 20, 40, 30]
+        .into_iter()
+        .map(|gas_price| Arc::new(ctx.setup_script_tx(gas_price)))
+        .collect::<Vec<_>>();
+    let service = ctx.service();
+    let out = service.shared.insert(txs.clone()).await;
+    assert!(out.iter().all(|result| result.is_ok()));
+    // All txs have the same gas, so the block fits the three most expensive ones.
+    let tx_gas = service
+        .shared
+        .find_one(txs[0].id(&Default::default()))
+        .unwrap()
+        .tx()
+        .max_gas();
+    let max_gas = tx_gas.saturating_mul(3);
+
+    let estimate = service.shared.estimate_block_fee(max_gas);
+    assert_eq!(service.shared.pending_number(), txs.len());
+    let selected = service.shared.select_transactions(max_gas);
+
+    assert_eq!(selected.len(), 3);
+    let fee = selected
+        .iter()
+        .map(|tx| tx.price().saturating_mul(tx.max_gas()))
+        .fold(0u64, u64::saturating_add);
+    assert_eq!(estimate, fee);
+
+    service.stop_and_await().await.unwrap();
+}
+
+#[tokio::test]
+async fn includable_for_contracts_returns_only_the_transactions_calling_them() {
+    let ctx = TestContextBuilder::new().build_and_start().await;
+
+    let requested = [ContractId::from([1; 32]), ContractId::from([2; 32])];
+    let other = ContractId::from([3; 32]);
+    let calling_first = Arc::new(ctx.setup_contract_call_tx(10, requested[0]));
+    let calling_second = Arc::new(ctx.setup_contract_call_tx(20, requested[1]));
+    let calling_other = Arc::new(ctx.setup_contract_call_tx(30, other));
+    let plain = Arc::new(ctx.setup_script_tx(40));
+    let txs = vec![
+        calling_first.clone(),
+        calling_second.clone(),
+        calling_other,
+        plain,
+    ];
+    let service = ctx.service();
+    let out = service.shared.insert(txs.clone()).await;
+    assert!(out.iter().all(|result| result.is_ok()), "{out:?}");
+
+    let selected = service
+        .shared
+        .includable_for_contracts(&requested, u64::MAX)
+        .iter()
+        .map(|tx| tx.id())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        selected,
+        vec![
+            calling_second.id(&Default::default()),
+            calling_first.id(&Default::default()),
+        ]
+    );
+    // The transactions stay in the pool.
+    assert_eq!(service.shared.pending_number(), txs.len());
+
+    service.stop_and_await().await.unwrap();
+}
+
 #[tokio::test]
 async fn simple_insert_removal_subscription() {
     let ctx = TestContextBuilder::new().build_and_start().await;
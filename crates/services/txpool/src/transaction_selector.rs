@@ -1,31 +1,587 @@
+use crate::Config;
 use fuel_core_types::{
-    fuel_types::Word,
-    services::txpool::ArcPoolTx,
+    fuel_tx::{
+        input::contract::Contract,
+        Address,
+        AssetId,
+        ConsensusParameters,
+        ContractId,
+        Input,
+        TxId,
+        UtxoId,
+    },
+    fuel_types::{
+        Nonce,
+        Word,
+    },
+    fuel_vm::checked_transaction::{
+        CheckPredicateParams,
+        CheckPredicates,
+    },
+    services::txpool::{
+        ArcPoolTx,
+        PoolTransaction,
+    },
+};
+use std::{
+    collections::{
+        BTreeSet,
+        HashMap,
+        HashSet,
+    },
+    fmt,
+    sync::Arc,
+};
+use tokio::time::Instant;
+use tokio_stream::{
+    Stream,
+    StreamExt,
 };
 
 // transaction selection could use a plugin based approach in the
 // future for block producers to customize block building (e.g. alternative priorities besides gas fees)
 
-// Expects sorted by gas price transactions, highest first
+/// The strategy used to select transactions for the next block.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Takes transactions with the highest gas price while they fit into the block.
+    /// It is the fastest strategy.
+    #[default]
+    Greedy,
+    /// Takes the combination of transactions with the highest total fee that fits
+    /// into the block. The gas is bucketed to keep the selection fast, so the result
+    /// is an approximation, but it is never worse than [`SelectionMode::Greedy`].
+    MaxFee,
+    /// Takes transactions with the highest fee per byte while they fit into the block
+    /// and stops once their total fee reaches the `revenue_target`. It minimizes the
+    /// bytes posted to the DA layer for the collected fee. `None` fills the block.
+    MinDaCost {
+        /// The total fee at which the selection stops.
+        revenue_target: Option<u128>,
+    },
+}
+
+/// The order of the includable transactions with the same gas price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// The transaction with the lower id goes first.
+    TxIdAsc,
+    /// The transaction inserted into the pool earlier goes first.
+    /// The transactions inserted at the same instant go by the id, lower first.
+    FirstSeen,
+    /// The transactions are shuffled by the hash of the `seed` and their id,
+    /// so the order is the same for the same `seed`.
+    Random {
+        /// The seed of the shuffle.
+        seed: u64,
+    },
+}
+
+/// The gas of each block reserved for the transactions matching the `predicate`.
+///
+/// The matching transactions have the first claim on the `reserved_gas`, even if
+/// they are outbid by other transactions. The unused part of the reservation is
+/// available to all transactions.
+#[derive(Clone)]
+pub struct PriorityLane {
+    /// Returns `true` for the prioritized transactions.
+    pub predicate: Arc<dyn Fn(&PoolTransaction) -> bool + Send + Sync>,
+    /// The gas reserved for the prioritized transactions.
+    pub reserved_gas: u64,
+}
+
+impl fmt::Debug for PriorityLane {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PriorityLane")
+            .field("reserved_gas", &self.reserved_gas)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Converts the fees paid in the non-base assets into the base asset, so the
+/// transactions paying in different assets are comparable during the selection.
+pub trait FeeOracle: fmt::Debug + Send + Sync {
+    /// Returns the `amount` of the `asset_id` expressed in the base asset.
+    /// `None` if the fees can't be paid in the `asset_id`.
+    fn to_base_asset(&self, asset_id: &AssetId, amount: u64) -> Option<u64>;
+}
+
+/// The number of buckets used to approximate the gas of the block in [`SelectionMode::MaxFee`].
+const MAX_FEE_GAS_RESOLUTION: u64 = 1000;
+
+// The type of the index for the transaction is `u16`, so we need to
+// limit it to `MAX` value minus 1(because of the `Mint` transaction).
+const MAX_TRANSACTIONS: usize = (u16::MAX - 1) as usize;
+
+/// The reason a transaction that fits into the block is left for the next blocks.
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The transaction calls the contracts over the `max_contracts_per_block`.
+    ContractLimit,
+    /// The predicates of the transaction use the gas over the `max_predicate_gas`.
+    PredicateGasLimit,
+    /// The transaction has the inputs over the `max_total_inputs`.
+    InputLimit,
+    /// The transaction has the witnesses over the `max_total_witnesses`.
+    WitnessLimit,
+    /// The sender of the transaction reached the `max_txs_per_sender` or
+    /// the `max_gas_per_sender`.
+    SenderLimit,
+}
+
+/// The result of [`select_transactions`].
+#[derive(Debug, Default)]
+pub struct Selection {
+    /// The transactions selected for the block.
+    pub transactions: Vec<ArcPoolTx>,
+    /// The transactions that spend an input of a transaction with a higher fee,
+    /// with the id of that transaction. They are never selected.
+    pub conflicts: Vec<(ArcPoolTx, TxId)>,
+    /// The transactions replaced by a transaction of the same sender that spends
+    /// some of their inputs with a higher fee, with the id of the replacement.
+    /// They are never selected.
+    pub replaced: Vec<(ArcPoolTx, TxId)>,
+    /// The transactions with invalid signatures or predicates.
+    /// It is empty if the `verify_signatures` of the config is disabled.
+    pub invalid: Vec<ArcPoolTx>,
+    /// The transactions paying the fee in the asset not supported by the `fee_oracle`,
+    /// with that asset. It is empty if the `fee_oracle` of the config is not set.
+    pub unsupported_fee_asset: Vec<(ArcPoolTx, AssetId)>,
+    /// The transactions that were selected by the gas, but skipped by the other
+    /// limits of the block, with the reason. They stay in the pool.
+    pub skipped: Vec<(ArcPoolTx, SkipReason)>,
+}
+
+/// Pulls the candidates from the `includable_txs` stream until it ends or
+/// the `deadline` passes, and selects the transactions from the pulled ones like
+/// [`select_transactions`] with the same `deadline`. The block may be partial
+/// if the stream is slower than the deadline.
+pub async fn select_transactions_stream(
+    includable_txs: impl Stream<Item = ArcPoolTx>,
+    max_gas: u64,
+    deadline: Instant,
+    config: &Config,
+) -> Selection {
+    tokio::pin!(includable_txs);
+    let mut pulled = vec![];
+    while let Ok(Some(tx)) =
+        tokio::time::timeout_at(deadline, includable_txs.next()).await
+    {
+        pulled.push(tx);
+    }
+    select_pulled(
+        pulled.into_iter(),
+        max_gas,
+        Some(deadline),
+        config,
+    )
+}
+
+// Expects sorted by gas price transactions, highest first.
+// If the `deadline` is set, the selection stops pulling new transactions
+// from `includable_txs` once it has passed, even if the block isn't full, and
+// the `SelectionMode::MaxFee` falls back to the greedy selection.
 pub fn select_transactions(
     includable_txs: impl Iterator<Item = ArcPoolTx>,
     max_gas: u64,
+    deadline: Option<Instant>,
+    config: &Config,
+) -> Selection {
+    let includable_txs = includable_txs.take_while(|_| match deadline {
+        Some(deadline) => Instant::now() < deadline,
+        None => true,
+    });
+    select_pulled(includable_txs, max_gas, deadline, config)
+}
+
+/// Selects the transactions from the already pulled `includable_txs`.
+/// The `deadline` only bounds the `SelectionMode::MaxFee` knapsack.
+fn select_pulled(
+    includable_txs: impl Iterator<Item = ArcPoolTx>,
+    max_gas: u64,
+    deadline: Option<Instant>,
+    config: &Config,
+) -> Selection {
+    let (includable_txs, invalid) = if config.verify_signatures {
+        let consensus_params = &config.chain_config.consensus_parameters;
+        includable_txs.partition(|tx| has_valid_signatures(tx, consensus_params))
+    } else {
+        (includable_txs.collect(), vec![])
+    };
+    let (includable_txs, unsupported_fee_asset) = match &config.fee_oracle {
+        Some(oracle) => {
+            let base_asset_id = config.chain_config.consensus_parameters.base_asset_id();
+            sort_by_normalized_price(includable_txs, oracle.as_ref(), base_asset_id)
+        }
+        None => (includable_txs, vec![]),
+    };
+    let (includable_txs, conflicts, replaced) = resolve_conflicts(includable_txs);
+    let includable_txs = includable_txs.into_iter();
+    let mut limits = BlockLimits::new(config);
+    let transactions = match &config.priority_lane {
+        Some(lane) => select_with_priority_lane(
+            includable_txs,
+            max_gas,
+            lane,
+            config.selection_mode,
+            deadline,
+            &mut limits,
+        ),
+        None => select_with_mode(
+            includable_txs,
+            max_gas,
+            config.selection_mode,
+            deadline,
+            &mut limits,
+        ),
+    };
+    let skipped = limits.into_skipped(&transactions);
+    Selection {
+        transactions,
+        conflicts,
+        replaced,
+        invalid,
+        unsupported_fee_asset,
+        skipped,
+    }
+}
+
+/// The contracts called by the `tx`.
+pub(crate) fn called_contracts(tx: &ArcPoolTx) -> impl Iterator<Item = &ContractId> + '_ {
+    tx.inputs().iter().filter_map(|input| match input {
+        Input::Contract(Contract { contract_id, .. }) => Some(contract_id),
+        _ => None,
+    })
+}
+
+/// The gas used by the predicates of the `tx`.
+fn predicate_gas(tx: &ArcPoolTx) -> Word {
+    tx.inputs()
+        .iter()
+        .filter_map(|input| input.predicate_gas_used())
+        .fold(0, Word::saturating_add)
+}
+
+/// The limits of the block besides the gas. The selection checks each candidate
+/// fitting into the gas against them, so the gas of a skipped candidate stays
+/// available to the next candidates.
+#[derive(Clone)]
+struct BlockLimits<'a> {
+    config: &'a Config,
+    contracts: HashSet<ContractId>,
+    predicate_gas: Word,
+    inputs: usize,
+    witnesses: usize,
+    senders: HashMap<Address, (usize, Word)>,
+    skipped: Vec<(ArcPoolTx, SkipReason)>,
+}
+
+impl<'a> BlockLimits<'a> {
+    fn new(config: &'a Config) -> Self {
+        Self {
+            config,
+            contracts: HashSet::new(),
+            predicate_gas: 0,
+            inputs: 0,
+            witnesses: 0,
+            senders: HashMap::new(),
+            skipped: vec![],
+        }
+    }
+
+    /// Takes the `tx` into the block if it doesn't exceed any of the limits.
+    /// Otherwise, remembers it as skipped with the first exceeded limit.
+    fn take(&mut self, tx: &ArcPoolTx) -> bool {
+        match self.check(tx) {
+            Ok(()) => {
+                self.contracts.extend(called_contracts(tx).copied());
+                self.predicate_gas = self.predicate_gas.saturating_add(predicate_gas(tx));
+                self.inputs = self.inputs.saturating_add(tx.inputs().len());
+                self.witnesses = self.witnesses.saturating_add(tx.witnesses().len());
+                if let Some(sender) = sender(tx) {
+                    let (count, gas) = self.senders.entry(sender).or_default();
+                    *count = count.saturating_add(1);
+                    *gas = gas.saturating_add(tx.max_gas());
+                }
+                true
+            }
+            Err(reason) => {
+                self.skipped.push((tx.clone(), reason));
+                false
+            }
+        }
+    }
+
+    fn check(&self, tx: &ArcPoolTx) -> Result<(), SkipReason> {
+        let config = self.config;
+        if let Some(max_contracts) = config.max_contracts_per_block {
+            let new_contracts = called_contracts(tx)
+                .filter(|contract_id| !self.contracts.contains(*contract_id))
+                .collect::<HashSet<_>>();
+            if self.contracts.len().saturating_add(new_contracts.len()) > max_contracts {
+                return Err(SkipReason::ContractLimit)
+            }
+        }
+        if let Some(max_predicate_gas) = config.max_predicate_gas {
+            if !within(self.predicate_gas, predicate_gas(tx), max_predicate_gas) {
+                return Err(SkipReason::PredicateGasLimit)
+            }
+        }
+        if let Some(max_inputs) = config.max_total_inputs {
+            if !within(self.inputs, tx.inputs().len(), max_inputs) {
+                return Err(SkipReason::InputLimit)
+            }
+        }
+        if let Some(max_witnesses) = config.max_total_witnesses {
+            if !within(self.witnesses, tx.witnesses().len(), max_witnesses) {
+                return Err(SkipReason::WitnessLimit)
+            }
+        }
+        // The transactions without an owned input have no sender and are never skipped.
+        if let Some(sender) = sender(tx) {
+            let (count, gas) = self.senders.get(&sender).copied().unwrap_or_default();
+            let within_txs = config
+                .max_txs_per_sender
+                .map_or(true, |max_txs| within(count, 1, max_txs));
+            let within_gas = config
+                .max_gas_per_sender
+                .map_or(true, |max_gas| within(gas, tx.max_gas(), max_gas));
+            if !within_txs || !within_gas {
+                return Err(SkipReason::SenderLimit)
+            }
+        }
+        Ok(())
+    }
+
+    /// The transactions skipped by the limits and not selected by another
+    /// candidate loop, each once with the first reason.
+    fn into_skipped(self, selected: &[ArcPoolTx]) -> Vec<(ArcPoolTx, SkipReason)> {
+        let mut seen = selected.iter().map(|tx| tx.id()).collect::<HashSet<_>>();
+        self.skipped
+            .into_iter()
+            .filter(|(tx, _)| seen.insert(tx.id()))
+            .collect()
+    }
+}
+
+/// Returns `true` if the `used` amount raised by the `amount` stays within the `max`.
+fn within<T>(used: T, amount: T, max: T) -> bool
+where
+    u128: TryFrom<T>,
+{
+    let widen = |value: T| u128::try_from(value).unwrap_or(u128::MAX);
+    widen(used).saturating_add(widen(amount)) <= widen(max)
+}
+
+/// The asset the `tx` pays the fee in, that is the asset of its first coin input.
+/// The transactions spending only messages pay in the `base_asset_id`.
+fn fee_asset(tx: &PoolTransaction, base_asset_id: &AssetId) -> AssetId {
+    tx.inputs()
+        .iter()
+        .find_map(|input| input.asset_id(base_asset_id))
+        .copied()
+        .unwrap_or(*base_asset_id)
+}
+
+/// Sorts the transactions by the gas price converted into the base asset by the `oracle`,
+/// highest first. The transactions with equal prices keep their order.
+/// Returns the transactions paying in the unsupported assets separately.
+fn sort_by_normalized_price(
+    txs: Vec<ArcPoolTx>,
+    oracle: &dyn FeeOracle,
+    base_asset_id: &AssetId,
+) -> (Vec<ArcPoolTx>, Vec<(ArcPoolTx, AssetId)>) {
+    let mut supported = Vec::with_capacity(txs.len());
+    let mut unsupported = vec![];
+    for tx in txs {
+        let asset_id = fee_asset(&tx, base_asset_id);
+        let price = if asset_id == *base_asset_id {
+            Some(tx.price())
+        } else {
+            oracle.to_base_asset(&asset_id, tx.price())
+        };
+        match price {
+            Some(price) => supported.push((price, tx)),
+            None => unsupported.push((tx, asset_id)),
+        }
+    }
+    supported.sort_by_key(|(price, _)| core::cmp::Reverse(*price));
+    let supported = supported.into_iter().map(|(_, tx)| tx).collect();
+    (supported, unsupported)
+}
+
+/// Returns `true` if the signatures and predicates of the `tx` are valid.
+fn has_valid_signatures(
+    tx: &PoolTransaction,
+    consensus_params: &ConsensusParameters,
+) -> bool {
+    let chain_id = &consensus_params.chain_id;
+    let predicate_params = CheckPredicateParams::from(consensus_params);
+    match tx {
+        PoolTransaction::Script(script) => script
+            .clone()
+            .check_signatures(chain_id)
+            .map_or(false, |script| {
+                script.check_predicates(&predicate_params).is_ok()
+            }),
+        PoolTransaction::Create(create) => create
+            .clone()
+            .check_signatures(chain_id)
+            .map_or(false, |create| {
+                create.check_predicates(&predicate_params).is_ok()
+            }),
+    }
+}
+
+/// The input that can be spent only once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SpentInput {
+    Coin(UtxoId),
+    Message(Nonce),
+}
+
+/// The coins and messages spent by the `tx`. The contract inputs aren't spent,
+/// so any number of transactions may use the same contract.
+fn spent_inputs(tx: &ArcPoolTx) -> impl Iterator<Item = SpentInput> + '_ {
+    tx.inputs().iter().filter_map(|input| {
+        input
+            .utxo_id()
+            .filter(|_| input.is_coin())
+            .map(|utxo_id| SpentInput::Coin(*utxo_id))
+            .or_else(|| input.nonce().map(|nonce| SpentInput::Message(*nonce)))
+    })
+}
+
+/// The owners of the inputs of the `tx`.
+fn senders(tx: &ArcPoolTx) -> BTreeSet<Address> {
+    tx.inputs()
+        .iter()
+        .filter_map(|input| input.input_owner().copied())
+        .collect()
+}
+
+/// The sender of the `tx`, that is the owner of its first owned input.
+fn sender(tx: &ArcPoolTx) -> Option<Address> {
+    tx.inputs()
+        .iter()
+        .find_map(|input| input.input_owner().copied())
+}
+
+/// The transactions dropped in favor of the transaction with the id.
+type Losers = Vec<(ArcPoolTx, TxId)>;
+
+/// Drops the transactions spending an input of another transaction with a higher fee,
+/// so a double-spend never gets into the block. The rest keep their order.
+/// Returns the dropped transactions with the id of the transaction they lost to,
+/// split into the conflicts and the replacements by the same sender.
+fn resolve_conflicts(txs: Vec<ArcPoolTx>) -> (Vec<ArcPoolTx>, Losers, Losers) {
+    let mut by_fee = (0..txs.len()).collect::<Vec<_>>();
+    by_fee.sort_by_key(|i| core::cmp::Reverse(tx_fee(&txs[*i])));
+
+    let mut spent_by: HashMap<SpentInput, usize> = HashMap::new();
+    let mut losers: HashMap<usize, usize> = HashMap::new();
+    for i in by_fee {
+        let tx = &txs[i];
+        let winner = spent_inputs(tx).find_map(|input| spent_by.get(&input).copied());
+        match winner {
+            Some(winner) => {
+                losers.insert(i, winner);
+            }
+            None => {
+                spent_by.extend(spent_inputs(tx).map(|input| (input, i)));
+            }
+        }
+    }
+
+    let ids = txs.iter().map(|tx| tx.id()).collect::<Vec<_>>();
+    let replaced = losers
+        .iter()
+        .filter(|(loser, winner)| senders(&txs[**loser]) == senders(&txs[**winner]))
+        .map(|(loser, _)| *loser)
+        .collect::<HashSet<_>>();
+    let mut kept = Vec::with_capacity(txs.len());
+    let mut conflicts = Vec::with_capacity(losers.len());
+    let mut replacements = Vec::with_capacity(replaced.len());
+    for (i, tx) in txs.into_iter().enumerate() {
+        match losers.remove(&i) {
+            Some(winner) if replaced.contains(&i) => replacements.push((tx, ids[winner])),
+            Some(winner) => conflicts.push((tx, ids[winner])),
+            None => kept.push(tx),
+        }
+    }
+    (kept, conflicts, replacements)
+}
+
+fn select_with_mode(
+    includable_txs: impl Iterator<Item = ArcPoolTx>,
+    max_gas: u64,
+    selection_mode: SelectionMode,
+    deadline: Option<Instant>,
+    limits: &mut BlockLimits,
+) -> Vec<ArcPoolTx> {
+    match selection_mode {
+        SelectionMode::Greedy => select_greedy(includable_txs, max_gas, limits),
+        SelectionMode::MaxFee => {
+            select_max_fee(includable_txs.collect(), max_gas, deadline, limits)
+        }
+        SelectionMode::MinDaCost { revenue_target } => {
+            select_min_da_cost(includable_txs.collect(), max_gas, revenue_target, limits)
+        }
+    }
+}
+
+/// Takes the transactions matching the `lane` into its reserved gas first, preferring
+/// the ones with higher gas price. The rest of the block, including the unused part of
+/// the reservation, is filled from the remaining transactions by the `selection_mode`.
+fn select_with_priority_lane(
+    includable_txs: impl Iterator<Item = ArcPoolTx>,
+    max_gas: u64,
+    lane: &PriorityLane,
+    selection_mode: SelectionMode,
+    deadline: Option<Instant>,
+    limits: &mut BlockLimits,
+) -> Vec<ArcPoolTx> {
+    let txs = includable_txs.collect::<Vec<_>>();
+    let prioritized_txs = txs
+        .iter()
+        .filter(|tx| (lane.predicate)(tx.as_ref()))
+        .cloned();
+    let mut selected =
+        select_greedy(prioritized_txs, lane.reserved_gas.min(max_gas), limits);
+
+    let selected_ids = selected.iter().map(|tx| tx.id()).collect::<HashSet<TxId>>();
+    let rest = txs
+        .into_iter()
+        .filter(|tx| !selected_ids.contains(&tx.id()));
+    selected.extend(select_with_mode(
+        rest,
+        max_gas.saturating_sub(total_gas(&selected)),
+        selection_mode,
+        deadline,
+        limits,
+    ));
+    selected.truncate(MAX_TRANSACTIONS);
+    selected
+}
+
+fn select_greedy(
+    includable_txs: impl Iterator<Item = ArcPoolTx>,
+    max_gas: u64,
+    limits: &mut BlockLimits,
 ) -> Vec<ArcPoolTx> {
     // Select all txs that fit into the block, preferring ones with higher gas price.
     //
     // Future improvements to this algorithm may take into account the parallel nature of
     // transactions to maximize throughput.
     let mut used_block_space: Word = 0;
-    // The type of the index for the transaction is `u16`, so we need to
-    // limit it to `MAX` value minus 1(because of the `Mint` transaction).
-    let takes_txs = u16::MAX - 1;
 
     // Pick as many transactions as we can fit into the block (greedy)
     includable_txs
         .filter(|tx| {
             let tx_block_space = tx.max_gas();
             if let Some(new_used_space) = used_block_space.checked_add(tx_block_space) {
-                if new_used_space <= max_gas {
+                if new_used_space <= max_gas && limits.take(tx) {
                     used_block_space = new_used_space;
                     true
                 } else {
@@ -35,10 +591,174 @@ pub fn select_transactions(
                 false
             }
         })
-        .take(takes_txs as usize)
+        .take(MAX_TRANSACTIONS)
         .collect()
 }
 
+/// Solves the 0/1 knapsack over the transactions, where the weight is the gas and
+/// the value is the fee. The gas is scaled down into [`MAX_FEE_GAS_RESOLUTION`] buckets,
+/// rounding up, so the selected set always fits into the `max_gas`.
+///
+/// The knapsack doesn't know the other `limits` of the block, so its choice passes
+/// through them in the order of the candidates, and the gas left by the skipped
+/// ones is refilled by the rest of the candidates.
+///
+/// If the `deadline` passes while solving the knapsack, the greedy selection is used.
+fn select_max_fee(
+    txs: Vec<ArcPoolTx>,
+    max_gas: u64,
+    deadline: Option<Instant>,
+    limits: &mut BlockLimits,
+) -> Vec<ArcPoolTx> {
+    let mut greedy_limits = limits.clone();
+    let greedy = select_greedy(txs.iter().cloned(), max_gas, &mut greedy_limits);
+    if max_gas == 0 || txs.is_empty() {
+        *limits = greedy_limits;
+        return greedy
+    }
+
+    let capacity = max_gas.min(MAX_FEE_GAS_RESOLUTION);
+    let weights = txs
+        .iter()
+        .map(|tx| scaled_gas(tx.max_gas(), max_gas, capacity))
+        .collect::<Vec<_>>();
+    let capacity = usize::try_from(capacity)
+        .expect("The capacity is limited by `MAX_FEE_GAS_RESOLUTION`");
+
+    // `best[c]` is the highest total fee of the transactions fitting into `c` buckets.
+    let mut best = vec![0u128; capacity.saturating_add(1)];
+    let mut taken = vec![vec![false; capacity.saturating_add(1)]; txs.len()];
+    for (i, (tx, weight)) in txs.iter().zip(weights.iter()).enumerate() {
+        if deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+            *limits = greedy_limits;
+            return greedy
+        }
+        let Some(weight) = *weight else { continue };
+        let fee = tx_fee(tx);
+        for c in (weight..=capacity).rev() {
+            let candidate = best[c.saturating_sub(weight)].saturating_add(fee);
+            if candidate > best[c] {
+                best[c] = candidate;
+                taken[i][c] = true;
+            }
+        }
+    }
+
+    let mut selected = vec![false; txs.len()];
+    let mut c = capacity;
+    for ((selected, taken), weight) in selected
+        .iter_mut()
+        .zip(taken.iter())
+        .zip(weights.iter())
+        .rev()
+    {
+        if taken[c] {
+            *selected = true;
+            c = c.saturating_sub(weight.unwrap_or_default());
+        }
+    }
+
+    let (chosen, rest): (Vec<_>, Vec<_>) = txs
+        .into_iter()
+        .zip(selected)
+        .partition(|(_, selected)| *selected);
+    let mut knapsack_limits = limits.clone();
+    let mut knapsack = select_greedy(
+        chosen.into_iter().map(|(tx, _)| tx),
+        max_gas,
+        &mut knapsack_limits,
+    );
+    let refill = select_greedy(
+        rest.into_iter().map(|(tx, _)| tx),
+        max_gas.saturating_sub(total_gas(&knapsack)),
+        &mut knapsack_limits,
+    );
+    knapsack.extend(refill);
+    knapsack.truncate(MAX_TRANSACTIONS);
+
+    if total_fee(&knapsack) > total_fee(&greedy) {
+        *limits = knapsack_limits;
+        knapsack
+    } else {
+        *limits = greedy_limits;
+        greedy
+    }
+}
+
+/// Takes the transactions in the order of the fee per byte, highest first,
+/// while they fit into the `max_gas`, until the `revenue_target` is reached.
+fn select_min_da_cost(
+    mut txs: Vec<ArcPoolTx>,
+    max_gas: u64,
+    revenue_target: Option<u128>,
+    limits: &mut BlockLimits,
+) -> Vec<ArcPoolTx> {
+    // Compare `fee_a / size_a` with `fee_b / size_b` without the division.
+    txs.sort_by(|a, b| {
+        let a_per_byte = tx_fee(a).saturating_mul(tx_size(b));
+        let b_per_byte = tx_fee(b).saturating_mul(tx_size(a));
+        b_per_byte.cmp(&a_per_byte)
+    });
+
+    let mut used_block_space: Word = 0;
+    let mut revenue = 0u128;
+    let mut selected = vec![];
+    for tx in txs {
+        if selected.len() >= MAX_TRANSACTIONS
+            || revenue_target.map_or(false, |target| revenue >= target)
+        {
+            break
+        }
+        match used_block_space.checked_add(tx.max_gas()) {
+            Some(new_used_space) if new_used_space <= max_gas && limits.take(&tx) => {
+                used_block_space = new_used_space;
+                revenue = revenue.saturating_add(tx_fee(&tx));
+                selected.push(tx);
+            }
+            _ => {}
+        }
+    }
+    selected
+}
+
+/// Scales the `gas` into the `capacity` buckets, rounding up.
+/// Returns `None` if the transaction doesn't fit into the block.
+fn scaled_gas(gas: u64, max_gas: u64, capacity: u64) -> Option<usize> {
+    if gas > max_gas {
+        return None
+    }
+    let scaled = u128::from(gas)
+        .saturating_mul(u128::from(capacity))
+        .saturating_add(u128::from(max_gas).saturating_sub(1))
+        .checked_div(u128::from(max_gas))?;
+    usize::try_from(scaled).ok()
+}
+
+fn tx_fee(tx: &ArcPoolTx) -> u128 {
+    u128::from(tx.price()).saturating_mul(u128::from(tx.max_gas()))
+}
+
+fn tx_size(tx: &ArcPoolTx) -> u128 {
+    tx.metered_bytes_size() as u128
+}
+
+/// The total fee of the `txs` in the block, computed the same way as by the selection.
+pub fn block_fee(txs: &[ArcPoolTx]) -> u64 {
+    u64::try_from(total_fee(txs)).unwrap_or(u64::MAX)
+}
+
+fn total_fee(txs: &[ArcPoolTx]) -> u128 {
+    txs.iter()
+        .map(tx_fee)
+        .fold(0u128, |total, fee| total.saturating_add(fee))
+}
+
+fn total_gas(txs: &[ArcPoolTx]) -> Word {
+    txs.iter()
+        .map(|tx| tx.max_gas())
+        .fold(0, Word::saturating_add)
+}
+
 #[cfg(test)]
 mod tests {
     use fuel_core_txpool as _;
@@ -52,18 +772,33 @@ mod tests {
             Rng,
         },
         fuel_tx::{
+            field::Witnesses,
             FeeParameters,
+            Finalizable,
             GasCosts,
             Output,
             TransactionBuilder,
+            Witness,
         },
         fuel_vm::{
-            checked_transaction::builder::TransactionBuilderExt,
+            checked_transaction::{
+                builder::TransactionBuilderExt,
+                IntoChecked,
+            },
             SecretKey,
         },
     };
     use itertools::Itertools;
-    use std::sync::Arc;
+    use std::{
+        sync::{
+            atomic::{
+                AtomicUsize,
+                Ordering,
+            },
+            Arc,
+        },
+        time::Duration,
+    };
 
     use super::*;
 
@@ -76,52 +811,239 @@ mod tests {
     /// A test helper that generates set of txs with given gas prices and limits and runs
     /// `select_transactions` against that, returning the list of selected gas price, limit pairs
     fn make_txs_and_select(txs: &[TxGas], block_gas_limit: Word) -> Vec<TxGas> {
+        make_txs_and_select_with_mode(txs, block_gas_limit, SelectionMode::Greedy)
+    }
+
+    /// The same as `make_txs_and_select`, but uses the given `SelectionMode`.
+    fn make_txs_and_select_with_mode(
+        txs: &[TxGas],
+        block_gas_limit: Word,
+        selection_mode: SelectionMode,
+    ) -> Vec<TxGas> {
+        let config = Config {
+            selection_mode,
+            ..Default::default()
+        };
+        select_transactions(
+            make_txs(txs).into_iter(),
+            block_gas_limit,
+            None,
+            &config,
+        )
+        .transactions
+            .into_iter()
+            .map(to_tx_gas)
+            .collect()
+    }
+
+    /// Generates the txs with given gas prices and limits, sorted by gas price.
+    fn make_txs(txs: &[TxGas]) -> Vec<ArcPoolTx> {
+        let txs = txs.iter().map(|tx_gas| (*tx_gas, 0)).collect::<Vec<_>>();
+        make_txs_with_script_data(&txs)
+    }
+
+    /// The same as `make_txs`, but pads each tx with the script data of the given size.
+    fn make_txs_with_script_data(txs: &[(TxGas, usize)]) -> Vec<ArcPoolTx> {
         let mut rng = thread_rng();
+        let mut txs = txs
+            .iter()
+            .map(|(tx_gas, script_data_size)| {
+                make_tx(*tx_gas, *script_data_size, rng.gen())
+            })
+            .collect::<Vec<ArcPoolTx>>();
+        txs.sort_by_key(|a| core::cmp::Reverse(a.price()));
+        txs
+    }
 
+    /// Generates the tx with given gas price and limit spending the coin `utxo_id`.
+    fn make_tx(tx_gas: TxGas, script_data_size: usize, utxo_id: UtxoId) -> ArcPoolTx {
+        let sender = SecretKey::random(&mut thread_rng());
+        make_tx_of(sender, tx_gas, script_data_size, utxo_id)
+    }
+
+    /// The same as `make_tx`, but the coin is owned by the `sender`.
+    fn make_tx_of(
+        sender: SecretKey,
+        tx_gas: TxGas,
+        script_data_size: usize,
+        utxo_id: UtxoId,
+    ) -> ArcPoolTx {
         let fee_params = FeeParameters {
             gas_price_factor: 1,
             gas_per_byte: 0,
         };
 
-        let mut txs = txs
-            .iter()
-            .map(|tx_gas| {
-                TransactionBuilder::script(
-                    vec![op::ret(RegId::ONE)].into_iter().collect(),
-                    vec![],
-                )
-                .gas_price(tx_gas.price)
-                .script_gas_limit(tx_gas.limit)
-                .add_unsigned_coin_input(
-                    SecretKey::random(&mut rng),
+        let tx = TransactionBuilder::script(
+            vec![op::ret(RegId::ONE)].into_iter().collect(),
+            vec![0; script_data_size],
+        )
+        .gas_price(tx_gas.price)
+        .script_gas_limit(tx_gas.limit)
+        .add_unsigned_coin_input(
+            sender,
+            utxo_id,
+            1_000_000,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .add_output(Output::Change {
+            to: Default::default(),
+            amount: 0,
+            asset_id: Default::default(),
+        })
+        .with_fee_params(fee_params)
+        .with_gas_costs(GasCosts::free())
+        // The block producer assumes transactions are already checked
+        // so it doesn't need to compute valid sigs for tests
+        .finalize_checked_basic(Default::default())
+        .into();
+        Arc::new(tx)
+    }
+
+    /// Generates the tx with given gas price and limit paying the fee in the `asset_id`.
+    /// It also spends a coin of the base asset to pass the checks of the protocol.
+    fn make_tx_paying_in(asset_id: AssetId, tx_gas: TxGas) -> ArcPoolTx {
+        let mut rng = thread_rng();
+        let sender = SecretKey::random(&mut rng);
+        let tx = TransactionBuilder::script(
+            vec![op::ret(RegId::ONE)].into_iter().collect(),
+            vec![],
+        )
+        .gas_price(tx_gas.price)
+        .script_gas_limit(tx_gas.limit)
+        .add_unsigned_coin_input(
+            sender,
+            rng.gen(),
+            1_000_000,
+            asset_id,
+            Default::default(),
+            Default::default(),
+        )
+        .add_unsigned_coin_input(
+            sender,
+            rng.gen(),
+            1_000_000,
+            AssetId::BASE,
+            Default::default(),
+            Default::default(),
+        )
+        .add_output(Output::Change {
+            to: Default::default(),
+            amount: 0,
+            asset_id,
+        })
+        .with_gas_costs(GasCosts::free())
+        .finalize_checked_basic(Default::default())
+        .into();
+        Arc::new(tx)
+    }
+
+    /// Generates the tx calling the `contracts`.
+    fn make_tx_calling(contracts: &[ContractId]) -> ArcPoolTx {
+        let mut rng = thread_rng();
+        let mut builder = TransactionBuilder::script(
+            vec![op::ret(RegId::ONE)].into_iter().collect(),
+            vec![],
+        );
+        builder.script_gas_limit(1000).add_unsigned_coin_input(
+            SecretKey::random(&mut rng),
+            rng.gen(),
+            1_000_000,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        );
+        for (i, contract_id) in contracts.iter().enumerate() {
+            let input_index = u8::try_from(i.saturating_add(1)).unwrap();
+            builder
+                .add_input(Input::contract(
                     rng.gen(),
-                    1_000_000,
                     Default::default(),
                     Default::default(),
                     Default::default(),
-                )
-                .add_output(Output::Change {
-                    to: Default::default(),
-                    amount: 0,
-                    asset_id: Default::default(),
-                })
-                .with_fee_params(fee_params)
-                .with_gas_costs(GasCosts::free())
-                // The block producer assumes transactions are already checked
-                // so it doesn't need to compute valid sigs for tests
-                .finalize_checked_basic(Default::default()).into()
-            })
-            .map(Arc::new)
-            .collect::<Vec<ArcPoolTx>>();
-        txs.sort_by_key(|a| core::cmp::Reverse(a.price()));
+                    *contract_id,
+                ))
+                .add_output(Output::contract(
+                    input_index,
+                    Default::default(),
+                    Default::default(),
+                ));
+        }
+        let tx = builder
+            .with_gas_costs(GasCosts::free())
+            .finalize_checked_basic(Default::default())
+            .into();
+        Arc::new(tx)
+    }
 
-        select_transactions(txs.into_iter(), block_gas_limit)
-            .into_iter()
-            .map(|tx| TxGas {
-                limit: tx.script_gas_limit().unwrap_or_default(),
-                price: tx.price(),
+    /// Generates the tx spending a predicate coin that uses the `predicate_gas`.
+    fn make_predicate_tx(predicate_gas: Word) -> ArcPoolTx {
+        let mut rng = thread_rng();
+        let predicate = vec![op::ret(RegId::ONE)].into_iter().collect::<Vec<u8>>();
+        let tx = TransactionBuilder::script(
+            vec![op::ret(RegId::ONE)].into_iter().collect(),
+            vec![],
+        )
+        .script_gas_limit(10)
+        .add_input(Input::coin_predicate(
+            rng.gen(),
+            Input::predicate_owner(&predicate),
+            1_000_000,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            predicate_gas,
+            predicate,
+            vec![],
+        ))
+        .add_output(Output::Change {
+            to: Default::default(),
+            amount: 0,
+            asset_id: Default::default(),
+        })
+        .with_gas_costs(GasCosts::free())
+        .finalize_checked_basic(Default::default())
+        .into();
+        Arc::new(tx)
+    }
+
+    /// Generates the tx with the `gas_limit` spending the `inputs` coins of one owner.
+    fn make_tx_with_inputs(inputs: usize, gas_limit: Word) -> ArcPoolTx {
+        let mut rng = thread_rng();
+        let sender = SecretKey::random(&mut rng);
+        let mut builder = TransactionBuilder::script(
+            vec![op::ret(RegId::ONE)].into_iter().collect(),
+            vec![],
+        );
+        builder.script_gas_limit(gas_limit);
+        for _ in 0..inputs {
+            builder.add_unsigned_coin_input(
+                sender,
+                rng.gen(),
+                1_000_000,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            );
+        }
+        let tx = builder
+            .add_output(Output::Change {
+                to: Default::default(),
+                amount: 0,
+                asset_id: Default::default(),
             })
-            .collect()
+            .with_gas_costs(GasCosts::free())
+            .finalize_checked_basic(Default::default())
+            .into();
+        Arc::new(tx)
+    }
+
+    fn to_tx_gas(tx: ArcPoolTx) -> TxGas {
+        TxGas {
+            limit: tx.script_gas_limit().unwrap_or_default(),
+            price: tx.price(),
+        }
     }
 
     #[test]
@@ -203,4 +1125,767 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn max_fee_selector_beats_greedy_when_greedy_wastes_space() {
+        #[rustfmt::skip]
+        let original = [
+            TxGas { price: 5, limit: 3000 },
+            TxGas { price: 4, limit: 2000 },
+            TxGas { price: 4, limit: 2000 },
+        ];
+        let fee = |txs: &[TxGas]| {
+            txs.iter()
+                .map(|tx| tx.price.saturating_mul(tx.limit))
+                .sum::<u64>()
+        };
+
+        let greedy =
+            make_txs_and_select_with_mode(&original, 4000, SelectionMode::Greedy);
+        let max_fee =
+            make_txs_and_select_with_mode(&original, 4000, SelectionMode::MaxFee);
+
+        assert_eq!(
+            greedy,
+            vec![TxGas {
+                price: 5,
+                limit: 3000
+            }]
+        );
+        assert_eq!(
+            max_fee,
+            vec![
+                TxGas {
+                    price: 4,
+                    limit: 2000
+                },
+                TxGas {
+                    price: 4,
+                    limit: 2000
+                }
+            ]
+        );
+        assert!(fee(&max_fee) > fee(&greedy));
+    }
+
+    #[test]
+    fn max_fee_selector_doesnt_exceed_max_gas_per_block() {
+        #[rustfmt::skip]
+        let original = [
+            TxGas { price: 3, limit: 2000 },
+            TxGas { price: 1, limit: 1000 },
+            TxGas { price: 4, limit: 3000 },
+            TxGas { price: 5, limit: 1000 },
+            TxGas { price: 2, limit: 1000 },
+        ];
+
+        for gas_limit in [999, 1000, 2000, 2500, 3000, 5000, 6000, 10_000] {
+            let selected = make_txs_and_select_with_mode(
+                &original,
+                gas_limit,
+                SelectionMode::MaxFee,
+            );
+            let total_gas: Word = selected.iter().map(|g| g.limit).sum();
+            assert!(total_gas <= gas_limit);
+        }
+    }
+
+    #[test]
+    fn min_da_cost_selector_posts_fewer_bytes_than_greedy() {
+        // given
+        #[rustfmt::skip]
+        let original = [
+            (TxGas { price: 5, limit: 1000 }, 4000),
+            (TxGas { price: 4, limit: 1000 }, 0),
+            (TxGas { price: 4, limit: 1000 }, 0),
+            (TxGas { price: 3, limit: 1000 }, 0),
+        ];
+        let txs = make_txs_with_script_data(&original);
+        let da_bytes = |txs: &[ArcPoolTx]| {
+            txs.iter().map(|tx| tx.metered_bytes_size()).sum::<usize>()
+        };
+        let select = |selection_mode| {
+            let config = Config {
+                selection_mode,
+                ..Default::default()
+            };
+            select_transactions(
+                txs.clone().into_iter(),
+                2000,
+                None,
+                &config,
+            )
+            .transactions
+        };
+
+        // when
+        let greedy = select(SelectionMode::Greedy);
+        let min_da_cost = select(SelectionMode::MinDaCost {
+            revenue_target: Some(8000),
+        });
+
+        // then
+        assert_eq!(min_da_cost.len(), 2);
+        assert!(total_fee(&min_da_cost) >= 8000);
+        assert!(da_bytes(&min_da_cost) < da_bytes(&greedy));
+    }
+
+    #[test]
+    fn min_da_cost_selector_stops_at_the_revenue_target() {
+        // given
+        let original = [TxGas {
+            price: 1,
+            limit: 1000,
+        }; 10];
+
+        // when
+        let selected = make_txs_and_select_with_mode(
+            &original,
+            u64::MAX,
+            SelectionMode::MinDaCost {
+                revenue_target: Some(3000),
+            },
+        );
+
+        // then
+        assert_eq!(selected.len(), 3);
+    }
+
+    #[test]
+    fn priority_lane_includes_outbid_txs_up_to_the_reserved_gas() {
+        #[rustfmt::skip]
+        let original = [
+            TxGas { price: 10, limit: 1000 },
+            TxGas { price: 9, limit: 1000 },
+            TxGas { price: 8, limit: 1000 },
+            TxGas { price: 1, limit: 1000 },
+            TxGas { price: 1, limit: 1000 },
+        ];
+        let config = Config {
+            priority_lane: Some(PriorityLane {
+                predicate: Arc::new(|tx: &PoolTransaction| tx.price() == 1),
+                reserved_gas: 1000,
+            }),
+            ..Default::default()
+        };
+
+        let selected = select_transactions(
+            make_txs(&original).into_iter(),
+            3000,
+            None,
+            &config,
+        )
+        .transactions
+            .into_iter()
+            .map(to_tx_gas)
+            .collect::<Vec<_>>();
+
+        #[rustfmt::skip]
+        let expected = vec![
+            TxGas { price: 1, limit: 1000 },
+            TxGas { price: 10, limit: 1000 },
+            TxGas { price: 9, limit: 1000 },
+        ];
+        assert_eq!(selected, expected);
+    }
+
+    #[test]
+    fn priority_lane_unused_reservation_falls_back_to_general_selection() {
+        #[rustfmt::skip]
+        let original = [
+            TxGas { price: 10, limit: 1000 },
+            TxGas { price: 9, limit: 1000 },
+            TxGas { price: 8, limit: 1000 },
+        ];
+        let config = Config {
+            priority_lane: Some(PriorityLane {
+                predicate: Arc::new(|tx: &PoolTransaction| tx.price() == 1),
+                reserved_gas: 1000,
+            }),
+            ..Default::default()
+        };
+
+        let selected = select_transactions(
+            make_txs(&original).into_iter(),
+            3000,
+            None,
+            &config,
+        )
+        .transactions
+            .into_iter()
+            .map(to_tx_gas)
+            .collect::<Vec<_>>();
+
+        assert_eq!(selected, original.to_vec());
+    }
+
+    #[test]
+    fn selector_keeps_only_the_higher_fee_tx_of_a_double_spend() {
+        // given
+        let utxo_id: UtxoId = thread_rng().gen();
+        let cheap = TxGas {
+            price: 2,
+            limit: 1000,
+        };
+        let expensive = TxGas {
+            price: 1,
+            limit: 5000,
+        };
+        let cheap_tx = make_tx(cheap, 0, utxo_id);
+        let expensive_tx = make_tx(expensive, 0, utxo_id);
+        let txs = vec![cheap_tx.clone(), expensive_tx.clone()];
+
+        // when
+        let selection = select_transactions(
+            txs.into_iter(),
+            u64::MAX,
+            None,
+            &Config::default(),
+        );
+
+        // then
+        let selected = selection
+            .transactions
+            .into_iter()
+            .map(to_tx_gas)
+            .collect::<Vec<_>>();
+        assert_eq!(selected, vec![expensive]);
+        assert_eq!(selection.conflicts.len(), 1);
+        let (loser, winner) = &selection.conflicts[0];
+        assert_eq!(loser.id(), cheap_tx.id());
+        assert_eq!(*winner, expensive_tx.id());
+    }
+
+    #[test]
+    fn selector_keeps_only_the_replacement_of_the_tx_of_the_same_sender() {
+        // given
+        let sender = SecretKey::random(&mut thread_rng());
+        let utxo_id: UtxoId = thread_rng().gen();
+        let original = TxGas {
+            price: 1,
+            limit: 1000,
+        };
+        let replacement = TxGas {
+            price: 2,
+            limit: 1000,
+        };
+        let original_tx = make_tx_of(sender, original, 0, utxo_id);
+        let replacement_tx = make_tx_of(sender, replacement, 0, utxo_id);
+        let txs = vec![replacement_tx.clone(), original_tx.clone()];
+
+        // when
+        let selection = select_transactions(
+            txs.into_iter(),
+            u64::MAX,
+            None,
+            &Config::default(),
+        );
+
+        // then
+        let selected = selection
+            .transactions
+            .into_iter()
+            .map(to_tx_gas)
+            .collect::<Vec<_>>();
+        assert_eq!(selected, vec![replacement]);
+        assert!(selection.conflicts.is_empty());
+        assert_eq!(selection.replaced.len(), 1);
+        let (replaced, by) = &selection.replaced[0];
+        assert_eq!(replaced.id(), original_tx.id());
+        assert_eq!(*by, replacement_tx.id());
+    }
+
+    #[test]
+    fn selector_excludes_the_tx_with_a_tampered_signature_if_verification_enabled() {
+        // given
+        let signed_tx = |tamper: bool| -> ArcPoolTx {
+            let mut rng = thread_rng();
+            let mut tx = TransactionBuilder::script(
+                vec![op::ret(RegId::ONE)].into_iter().collect(),
+                vec![],
+            )
+            .script_gas_limit(1000)
+            .add_unsigned_coin_input(
+                SecretKey::random(&mut rng),
+                rng.gen(),
+                1_000_000,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            )
+            .add_output(Output::Change {
+                to: Default::default(),
+                amount: 0,
+                asset_id: Default::default(),
+            })
+            .finalize();
+            if tamper {
+                tx.witnesses_mut()[0] = Witness::from(vec![0; 64]);
+            }
+            let tx = tx
+                .into_checked_basic(Default::default(), &ConsensusParameters::default())
+                .expect("The transaction is valid");
+            Arc::new(tx.into())
+        };
+        let valid_tx = signed_tx(false);
+        let tampered_tx = signed_tx(true);
+        let txs = vec![tampered_tx.clone(), valid_tx.clone()];
+        let config = Config {
+            verify_signatures: true,
+            ..Default::default()
+        };
+
+        // when
+        let selection = select_transactions(
+            txs.into_iter(),
+            u64::MAX,
+            None,
+            &config,
+        );
+
+        // then
+        let selected = selection
+            .transactions
+            .iter()
+            .map(|tx| tx.id())
+            .collect::<Vec<_>>();
+        assert_eq!(selected, vec![valid_tx.id()]);
+        let invalid = selection
+            .invalid
+            .iter()
+            .map(|tx| tx.id())
+            .collect::<Vec<_>>();
+        assert_eq!(invalid, vec![tampered_tx.id()]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn selector_stops_pulling_txs_after_deadline() {
+        // given
+        let original = [TxGas {
+            price: 1,
+            limit: 1000,
+        }; 10];
+        let txs = make_txs(&original);
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let slow_txs = tokio_stream::iter(txs).then(|tx| {
+            let pulled = pulled.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(30)).await;
+                pulled.fetch_add(1, Ordering::SeqCst);
+                tx
+            }
+        });
+        let deadline = Instant::now()
+            .checked_add(Duration::from_millis(100))
+            .unwrap();
+
+        // when
+        let selected = select_transactions_stream(
+            slow_txs,
+            u64::MAX,
+            deadline,
+            &Config::default(),
+        )
+        .await
+        .transactions;
+
+        // then
+        assert_eq!(pulled.load(Ordering::SeqCst), 3);
+        assert_eq!(selected.len(), 3);
+    }
+
+    #[derive(Debug)]
+    struct FixedRateOracle(HashMap<AssetId, u64>);
+
+    impl FeeOracle for FixedRateOracle {
+        fn to_base_asset(&self, asset_id: &AssetId, amount: u64) -> Option<u64> {
+            self.0
+                .get(asset_id)
+                .map(|rate| amount.saturating_mul(*rate))
+        }
+    }
+
+    #[test]
+    fn selector_orders_txs_by_fee_normalized_to_base_asset() {
+        // given
+        let cheap_asset = AssetId::new([1; 32]);
+        let unsupported_asset = AssetId::new([2; 32]);
+        let oracle = FixedRateOracle([(cheap_asset, 3)].into_iter().collect());
+        let limit = 1000;
+        let base_tx = make_tx_paying_in(AssetId::BASE, TxGas { price: 5, limit });
+        let expensive_tx = make_tx_paying_in(cheap_asset, TxGas { price: 2, limit });
+        let cheap_tx = make_tx_paying_in(cheap_asset, TxGas { price: 1, limit });
+        let unsupported_tx =
+            make_tx_paying_in(unsupported_asset, TxGas { price: 10, limit });
+        // Sorted by the gas price in the assets themselves.
+        let txs = vec![
+            unsupported_tx.clone(),
+            base_tx.clone(),
+            expensive_tx.clone(),
+            cheap_tx.clone(),
+        ];
+        let config = Config {
+            fee_oracle: Some(Arc::new(oracle)),
+            ..Default::default()
+        };
+
+        // when
+        let selection = select_transactions(
+            txs.into_iter(),
+            u64::MAX,
+            None,
+            &config,
+        );
+
+        // then
+        let selected = selection
+            .transactions
+            .iter()
+            .map(|tx| tx.id())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            selected,
+            vec![expensive_tx.id(), base_tx.id(), cheap_tx.id()]
+        );
+        let unsupported = selection
+            .unsupported_fee_asset
+            .iter()
+            .map(|(tx, asset_id)| (tx.id(), *asset_id))
+            .collect::<Vec<_>>();
+        assert_eq!(unsupported, vec![(unsupported_tx.id(), unsupported_asset)]);
+    }
+
+    #[test]
+    fn selector_skips_txs_calling_contracts_over_the_limit() {
+        // given
+        let [first, second, third] = [1, 2, 3].map(|i| ContractId::new([i; 32]));
+        let calls_first = make_tx_calling(&[first]);
+        let calls_second_and_third = make_tx_calling(&[second, third]);
+        let calls_second = make_tx_calling(&[second]);
+        let calls_third = make_tx_calling(&[third]);
+        let txs = vec![
+            calls_first.clone(),
+            calls_second_and_third.clone(),
+            calls_second.clone(),
+            calls_third.clone(),
+        ];
+        let config = Config {
+            max_contracts_per_block: Some(2),
+            ..Default::default()
+        };
+
+        // when
+        let selection = select_transactions(
+            txs.into_iter(),
+            u64::MAX,
+            None,
+            &config,
+        );
+
+        // then
+        let selected = selection
+            .transactions
+            .iter()
+            .map(|tx| tx.id())
+            .collect::<Vec<_>>();
+        assert_eq!(selected, vec![calls_first.id(), calls_second.id()]);
+        let contracts = selection
+            .transactions
+            .iter()
+            .flat_map(called_contracts)
+            .collect::<HashSet<_>>();
+        assert!(contracts.len() <= 2);
+        let skipped = selection
+            .skipped
+            .iter()
+            .map(|(tx, reason)| (tx.id(), *reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            skipped,
+            vec![
+                (calls_second_and_third.id(), SkipReason::ContractLimit),
+                (calls_third.id(), SkipReason::ContractLimit),
+            ]
+        );
+    }
+
+    #[test]
+    fn selector_caps_the_predicate_gas_separately_from_the_total_gas() {
+        // given
+        let heavy_txs = (0..3).map(|_| make_predicate_tx(60)).collect::<Vec<_>>();
+        let plain_tx = make_tx(
+            TxGas {
+                price: 0,
+                limit: 10,
+            },
+            0,
+            thread_rng().gen(),
+        );
+        let txs = heavy_txs
+            .iter()
+            .cloned()
+            .chain(core::iter::once(plain_tx.clone()))
+            .collect::<Vec<_>>();
+        let config = Config {
+            max_predicate_gas: Some(100),
+            ..Default::default()
+        };
+
+        // when
+        let selection = select_transactions(
+            txs.into_iter(),
+            u64::MAX,
+            None,
+            &config,
+        );
+
+        // then
+        let selected = selection
+            .transactions
+            .iter()
+            .map(|tx| tx.id())
+            .collect::<Vec<_>>();
+        assert_eq!(selected, vec![heavy_txs[0].id(), plain_tx.id()]);
+        let used_predicate_gas = selection
+            .transactions
+            .iter()
+            .map(predicate_gas)
+            .sum::<Word>();
+        assert!(used_predicate_gas <= 100);
+        let skipped = selection
+            .skipped
+            .iter()
+            .map(|(tx, reason)| (tx.id(), *reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            skipped,
+            vec![
+                (heavy_txs[1].id(), SkipReason::PredicateGasLimit),
+                (heavy_txs[2].id(), SkipReason::PredicateGasLimit),
+            ]
+        );
+    }
+
+    #[test]
+    fn selector_caps_the_total_inputs_even_with_the_gas_headroom() {
+        // given
+        let input_heavy_txs = (0..5)
+            .map(|_| make_tx_with_inputs(10, 1))
+            .collect::<Vec<_>>();
+        let config = Config {
+            max_total_inputs: Some(25),
+            ..Default::default()
+        };
+
+        // when
+        let selection = select_transactions(
+            input_heavy_txs.iter().cloned(),
+            1_000_000,
+            None,
+            &config,
+        );
+
+        // then
+        let total_inputs = selection
+            .transactions
+            .iter()
+            .map(|tx| tx.inputs().len())
+            .sum::<usize>();
+        assert_eq!(selection.transactions.len(), 2);
+        assert!(total_inputs <= 25);
+        assert_eq!(selection.skipped.len(), 3);
+        assert!(selection
+            .skipped
+            .iter()
+            .all(|(_, reason)| *reason == SkipReason::InputLimit));
+    }
+
+    #[test]
+    fn selector_caps_the_transactions_of_one_sender() {
+        // given
+        let mut rng = thread_rng();
+        let sender = SecretKey::random(&mut rng);
+        let tx_gas = TxGas {
+            price: 10,
+            limit: 1000,
+        };
+        let sender_txs = (0..5)
+            .map(|_| make_tx_of(sender, tx_gas, 0, rng.gen()))
+            .collect::<Vec<_>>();
+        let other_tx = make_tx(tx_gas, 0, rng.gen());
+        let txs = sender_txs
+            .iter()
+            .cloned()
+            .chain(std::iter::once(other_tx.clone()))
+            .collect::<Vec<_>>();
+        let config = Config {
+            max_txs_per_sender: Some(2),
+            ..Default::default()
+        };
+
+        // when
+        let selection = select_transactions(
+            txs.into_iter(),
+            u64::MAX,
+            None,
+            &config,
+        );
+
+        // then
+        let selected = selection
+            .transactions
+            .iter()
+            .map(|tx| tx.id())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            selected,
+            vec![sender_txs[0].id(), sender_txs[1].id(), other_tx.id()]
+        );
+        let skipped = selection
+            .skipped
+            .iter()
+            .map(|(tx, reason)| (tx.id(), *reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            skipped,
+            sender_txs[2..]
+                .iter()
+                .map(|tx| (tx.id(), SkipReason::SenderLimit))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// Generates the txs of one sender with a higher price followed by
+    /// the txs of the other senders, all with the same gas.
+    fn make_sender_and_other_txs(
+        sender_txs: usize,
+        other_txs: usize,
+    ) -> (Vec<ArcPoolTx>, Vec<ArcPoolTx>) {
+        let mut rng = thread_rng();
+        let sender = SecretKey::random(&mut rng);
+        let sender_txs = (0..sender_txs)
+            .map(|_| {
+                let tx_gas = TxGas {
+                    price: 10,
+                    limit: 1000,
+                };
+                make_tx_of(sender, tx_gas, 0, rng.gen())
+            })
+            .collect();
+        let other_txs = (0..other_txs)
+            .map(|_| {
+                let tx_gas = TxGas {
+                    price: 5,
+                    limit: 1000,
+                };
+                make_tx(tx_gas, 0, rng.gen())
+            })
+            .collect();
+        (sender_txs, other_txs)
+    }
+
+    #[test]
+    fn selector_refills_the_gas_left_by_the_txs_over_the_sender_limit() {
+        // given
+        let (sender_txs, other_txs) = make_sender_and_other_txs(3, 2);
+        let txs = sender_txs.iter().chain(other_txs.iter()).cloned();
+        let config = Config {
+            max_txs_per_sender: Some(1),
+            ..Default::default()
+        };
+
+        // when
+        let selection = select_transactions(txs, 3000, None, &config);
+
+        // then
+        let selected = selection
+            .transactions
+            .iter()
+            .map(|tx| tx.id())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            selected,
+            vec![sender_txs[0].id(), other_txs[0].id(), other_txs[1].id()]
+        );
+        let skipped = selection
+            .skipped
+            .iter()
+            .map(|(tx, reason)| (tx.id(), *reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            skipped,
+            vec![
+                (sender_txs[1].id(), SkipReason::SenderLimit),
+                (sender_txs[2].id(), SkipReason::SenderLimit),
+            ]
+        );
+    }
+
+    #[test]
+    fn max_fee_selection_respects_the_sender_limit_and_refills_the_gas() {
+        // given
+        let (sender_txs, other_txs) = make_sender_and_other_txs(3, 2);
+        let txs = sender_txs.iter().chain(other_txs.iter()).cloned();
+        let config = Config {
+            selection_mode: SelectionMode::MaxFee,
+            max_txs_per_sender: Some(1),
+            ..Default::default()
+        };
+
+        // when
+        let selection = select_transactions(txs, 3000, None, &config);
+
+        // then
+        let selected = selection
+            .transactions
+            .iter()
+            .map(|tx| tx.id())
+            .collect::<HashSet<_>>();
+        let expected = [&sender_txs[0], &other_txs[0], &other_txs[1]]
+            .into_iter()
+            .map(|tx| tx.id())
+            .collect::<HashSet<_>>();
+        assert_eq!(selected, expected);
+    }
+
+    #[test]
+    fn selector_refills_the_gas_left_by_the_txs_over_the_input_limit() {
+        // given
+        let input_heavy_txs = (0..3)
+            .map(|_| make_tx_with_inputs(10, 1000))
+            .collect::<Vec<_>>();
+        let plain_txs = (0..2)
+            .map(|_| make_tx_with_inputs(1, 1000))
+            .collect::<Vec<_>>();
+        // The gas fits the first input-heavy tx and the plain ones.
+        let max_gas = total_gas(&input_heavy_txs[..1]) + total_gas(&plain_txs);
+        let txs = input_heavy_txs.iter().chain(plain_txs.iter()).cloned();
+        let config = Config {
+            max_total_inputs: Some(15),
+            ..Default::default()
+        };
+
+        // when
+        let selection =
+            select_transactions(txs, max_gas, None, &config);
+
+        // then
+        let selected = selection
+            .transactions
+            .iter()
+            .map(|tx| tx.id())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            selected,
+            vec![
+                input_heavy_txs[0].id(),
+                plain_txs[0].id(),
+                plain_txs[1].id()
+            ]
+        );
+        assert!(selection
+            .skipped
+            .iter()
+            .all(|(_, reason)| *reason == SkipReason::InputLimit));
+    }
 }
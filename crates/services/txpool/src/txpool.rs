@@ -1,17 +1,20 @@
 use crate::{
     containers::{
         dependency::Dependency,
+        lru::LruCache,
         price_sort::PriceSort,
         time_sort::TimeSort,
     },
     ports::TxPoolDb,
     service::TxStatusChange,
+    transaction_selector::TieBreak,
     types::*,
     Config,
     Error,
     TxInfo,
 };
 use fuel_core_types::{
+    fuel_crypto::Hasher,
     fuel_tx::{
         Chargeable,
         Transaction,
@@ -49,6 +52,7 @@ use fuel_core_types::{
 use std::{
     cmp::Reverse,
     collections::HashMap,
+    iter,
     ops::Deref,
     sync::Arc,
 };
@@ -60,6 +64,7 @@ pub struct TxPool<ViewProvider> {
     by_gas_price: PriceSort,
     by_time: TimeSort,
     by_dependency: Dependency,
+    recently_included: LruCache<TxId>,
     config: Config,
     database: ViewProvider,
 }
@@ -73,6 +78,7 @@ impl<ViewProvider> TxPool<ViewProvider> {
             by_gas_price: PriceSort::default(),
             by_time: TimeSort::default(),
             by_dependency: Dependency::new(max_depth, config.utxo_validation),
+            recently_included: LruCache::new(config.recently_included_capacity),
             config,
             database,
         }
@@ -91,13 +97,81 @@ impl<ViewProvider> TxPool<ViewProvider> {
         &self.by_dependency
     }
 
-    /// Return all sorted transactions that are includable in next block.
+    /// Return all sorted transactions that are includable in next block, ordered
+    /// by the gas price, highest first, and then by the `tie_break`.
+    /// The transactions included into the recent blocks and the expired ones,
+    /// not pruned yet, are skipped.
+    ///
+    /// The ties are broken lazily, one group of the equal gas price at a time,
+    /// so taking a few transactions doesn't sort the whole pool.
     pub fn sorted_includable(&self) -> impl Iterator<Item = ArcPoolTx> + '_ {
-        self.by_gas_price
+        let mut by_price = self
+            .by_gas_price
             .sort
             .iter()
             .rev()
+            .filter(|(_, tx)| !self.recently_included.contains(&tx.id()))
+            .filter(|(_, tx)| !self.is_expired(&tx.id()))
             .map(|(_, tx)| tx.clone())
+            .peekable();
+        let tie_break = self.config.tie_break;
+        // The current group of the equal gas price, in the reversed order.
+        let mut group = Vec::new();
+        iter::from_fn(move || {
+            if group.is_empty() {
+                let tx = by_price.next()?;
+                let Some(tie_break) = tie_break else {
+                    return Some(tx)
+                };
+                let price = tx.price();
+                group.push(tx);
+                while let Some(tx) = by_price.next_if(|tx| tx.price() == price) {
+                    group.push(tx);
+                }
+                self.break_ties(&mut group, tie_break);
+                group.reverse();
+            }
+            group.pop()
+        })
+    }
+
+    /// Reorders the transactions with the same gas price according to the `tie_break`.
+    /// The `txs` must be sorted by the gas price, highest first.
+    fn break_ties(&self, txs: &mut [ArcPoolTx], tie_break: TieBreak) {
+        txs.sort_by_cached_key(|tx| {
+            let tx_id = tx.id();
+            let tie = match tie_break {
+                TieBreak::TxIdAsc => (None, tx_id),
+                TieBreak::FirstSeen => {
+                    (self.by_hash.get(&tx_id).map(TxInfo::created), tx_id)
+                }
+                TieBreak::Random { seed } => {
+                    let shuffled = Hasher::default()
+                        .chain(seed.to_be_bytes())
+                        .chain(tx_id)
+                        .finalize();
+                    (None, shuffled)
+                }
+            };
+            (Reverse(tx.price()), tie)
+        });
+    }
+
+    /// Returns `true` if the transaction is in the pool longer than the `transaction_ttl`.
+    fn is_expired(&self, tx_id: &TxId) -> bool {
+        let Some(deadline) =
+            tokio::time::Instant::now().checked_sub(self.config.transaction_ttl)
+        else {
+            return false
+        };
+        self.by_hash
+            .get(tx_id)
+            .map_or(false, |info| info.created() <= deadline)
+    }
+
+    /// Remembers the transaction as included into the committed block.
+    pub fn mark_included(&mut self, tx_id: TxId) {
+        self.recently_included.insert(tx_id);
     }
 
     pub fn remove_inner(&mut self, tx: &ArcPoolTx) -> Vec<ArcPoolTx> {
@@ -184,7 +258,7 @@ impl<ViewProvider> TxPool<ViewProvider> {
 
     /// Return all sorted transactions that are includable in next block.
     /// This is going to be heavy operation, use it only when needed.
-    pub fn includable(&mut self) -> impl Iterator<Item = ArcPoolTx> + '_ {
+    pub fn includable(&self) -> impl Iterator<Item = ArcPoolTx> + '_ {
         self.sorted_includable()
     }
 
@@ -205,6 +279,7 @@ impl<ViewProvider> TxPool<ViewProvider> {
                 height,
                 TxStatusMessage::Status(status),
             );
+            self.mark_included(tx_id);
             self.remove_committed_tx(&tx_id);
         }
     }
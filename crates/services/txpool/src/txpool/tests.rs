@@ -12,6 +12,7 @@ use crate::{
     },
     Config,
     Error,
+    TieBreak,
 };
 use fuel_core_types::{
     fuel_asm::{
@@ -31,12 +32,15 @@ use fuel_core_types::{
         UniqueIdentifier,
         UtxoId,
     },
+    fuel_crypto::Hasher,
+    fuel_tx::TxId,
     fuel_types::ChainId,
     fuel_vm::checked_transaction::Checked,
 };
 use std::{
     cmp::Reverse,
     collections::HashMap,
+    time::Duration,
     vec,
 };
 
@@ -662,6 +666,52 @@ async fn sorted_out_tx1_2_4() {
     assert_eq!(txs[2].id(), tx2_id, "Third should be tx2");
 }
 
+#[tokio::test]
+async fn recently_included_tx_is_not_includable() {
+    let mut context = TextContext::default();
+
+    let (_, gas_coin) = context.setup_coin();
+    let tx1 = TransactionBuilder::script(vec![], vec![])
+        .gas_price(20)
+        .script_gas_limit(GAS_LIMIT)
+        .add_input(gas_coin)
+        .finalize_as_transaction();
+
+    let (_, gas_coin) = context.setup_coin();
+    let tx2 = TransactionBuilder::script(vec![], vec![])
+        .gas_price(10)
+        .script_gas_limit(GAS_LIMIT)
+        .add_input(gas_coin)
+        .finalize_as_transaction();
+
+    let tx1_id = tx1.id(&ChainId::default());
+    let tx2_id = tx2.id(&ChainId::default());
+
+    let mut txpool = context.build();
+    let tx1 = check_unwrap_tx(tx1, &txpool.config).await;
+    let tx2 = check_unwrap_tx(tx2, &txpool.config).await;
+
+    txpool
+        .insert_single(tx1)
+        .expect("Tx1 should be Ok, got Err");
+    txpool
+        .insert_single(tx2)
+        .expect("Tx2 should be Ok, got Err");
+
+    // The block with tx1 is committed, but tx1 is not pruned from the pool yet.
+    txpool.mark_included(tx1_id);
+
+    let txs = txpool.sorted_includable().collect::<Vec<_>>();
+
+    assert_eq!(
+        txpool.pending_number(),
+        2,
+        "Tx1 should still be in the pool"
+    );
+    assert_eq!(txs.len(), 1, "Should have 1 tx");
+    assert_eq!(txs[0].id(), tx2_id, "Only tx2 should be includable");
+}
+
 #[tokio::test]
 async fn find_dependent_tx1_tx2() {
     let mut context = TextContext::default();
@@ -1062,3 +1112,72 @@ async fn predicate_that_returns_false_is_invalid() {
         "unexpected error: {err}",
     )
 }
+
+/// Inserts four transactions with the same gas price into the pool with the `tie_break`,
+/// one millisecond apart, in the order that is neither ascending nor descending by the id.
+/// Returns the ids in the order of the insertion and in the order of `sorted_includable`.
+async fn includable_with_tie_break(tie_break: TieBreak) -> (Vec<TxId>, Vec<TxId>) {
+    let mut context = TextContext::default().config(Config {
+        tie_break: Some(tie_break),
+        ..Default::default()
+    });
+    let mut txs = vec![];
+    for _ in 0..4 {
+        let (_, gas_coin) = context.setup_coin();
+        let tx = TransactionBuilder::script(vec![], vec![])
+            .gas_price(10)
+            .script_gas_limit(GAS_LIMIT)
+            .add_input(gas_coin)
+            .finalize_as_transaction();
+        txs.push(tx);
+    }
+    txs.sort_by_key(|tx| tx.id(&ChainId::default()));
+    let txs = [1, 3, 0, 2].map(|i| txs[i].clone());
+
+    let mut txpool = context.build();
+    let mut inserted = vec![];
+    for tx in txs {
+        inserted.push(tx.id(&ChainId::default()));
+        let tx = check_unwrap_tx(tx, &txpool.config).await;
+        txpool.insert_single(tx).expect("Tx should be Ok, got Err");
+        tokio::time::advance(Duration::from_millis(1)).await;
+    }
+
+    let includable = txpool.sorted_includable().map(|tx| tx.id()).collect();
+    (inserted, includable)
+}
+
+#[tokio::test(start_paused = true)]
+async fn tie_break_tx_id_asc_orders_equal_priced_txs_by_the_id() {
+    let (inserted, includable) = includable_with_tie_break(TieBreak::TxIdAsc).await;
+
+    let mut by_id = inserted;
+    by_id.sort();
+    assert_eq!(includable, by_id);
+}
+
+#[tokio::test(start_paused = true)]
+async fn tie_break_first_seen_orders_equal_priced_txs_by_the_insertion() {
+    let (inserted, includable) = includable_with_tie_break(TieBreak::FirstSeen).await;
+
+    assert_eq!(includable, inserted);
+}
+
+#[tokio::test(start_paused = true)]
+async fn tie_break_random_order_is_reproducible_for_the_seed() {
+    let seed = 7;
+    let (inserted, includable) =
+        includable_with_tie_break(TieBreak::Random { seed }).await;
+    let (_, includable_again) =
+        includable_with_tie_break(TieBreak::Random { seed }).await;
+
+    let mut shuffled = inserted;
+    shuffled.sort_by_key(|tx_id| {
+        Hasher::default()
+            .chain(seed.to_be_bytes())
+            .chain(tx_id)
+            .finalize()
+    });
+    assert_eq!(includable, shuffled);
+    assert_eq!(includable, includable_again);
+}
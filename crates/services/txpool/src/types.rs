@@ -8,3 +8,12 @@ pub use fuel_core_types::{
 };
 
 pub type GasPrice = Word;
+
+/// A bounded page of items, continued by the cursor of the last item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    /// The items of the page.
+    pub items: Vec<T>,
+    /// `true` if there are more items after the last item of the page.
+    pub has_next_page: bool,
+}
@@ -11,6 +11,7 @@ pub mod manual;
 pub mod postcard;
 pub mod primitive;
 pub mod raw;
+pub mod versioned;
 
 /// The trait is usually implemented by the encoder that stores serialized objects.
 pub trait Encoder {
@@ -0,0 +1,134 @@
+//! The module contains the implementation of the `Versioned` codec.
+//! The codec prefixes the encoded value with the version byte, so the readers
+//! can dispatch the value to the decoder of the version that wrote it.
+//! It allows changing the codec of the table between the versions of the node
+//! without making the values written by the previous version unreadable.
+
+use crate::codec::{
+    Decode,
+    Encode,
+    Encoder,
+};
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+};
+
+/// The codec encodes the value with the `Current` codec and prefixes it with the `VERSION` byte.
+/// The values with another version byte are passed to the `Previous` codec, which is usually
+/// the `Versioned` codec of the previous version. The chain ends with the [`NoPrevious`] codec.
+///
+/// For example, if the table used `Versioned<1, Postcard>` and switches to the `Primitive<8>`,
+/// the new codec is `Versioned<2, Primitive<8>, Versioned<1, Postcard>>`.
+pub struct Versioned<const VERSION: u8, Current, Previous = NoPrevious>(
+    PhantomData<(Current, Previous)>,
+);
+
+/// The end of the chain of the versions. It fails to decode any value.
+pub struct NoPrevious;
+
+impl<const VERSION: u8, Current, Previous, T> Encode<T>
+    for Versioned<VERSION, Current, Previous>
+where
+    T: ?Sized,
+    Current: Encode<T>,
+{
+    type Encoder<'a> = Cow<'a, [u8]> where T: 'a;
+
+    fn encode(t: &T) -> Self::Encoder<'_> {
+        let encoder = Current::encode(t);
+        let bytes = encoder.as_bytes();
+        let mut versioned = Vec::with_capacity(bytes.len().saturating_add(1));
+        versioned.push(VERSION);
+        versioned.extend_from_slice(bytes.as_ref());
+        Cow::Owned(versioned)
+    }
+}
+
+impl<const VERSION: u8, Current, Previous, T> Decode<T>
+    for Versioned<VERSION, Current, Previous>
+where
+    Current: Decode<T>,
+    Previous: Decode<T>,
+{
+    fn decode(bytes: &[u8]) -> anyhow::Result<T> {
+        match bytes.split_first() {
+            Some((version, value)) if *version == VERSION => Current::decode(value),
+            _ => Previous::decode(bytes),
+        }
+    }
+}
+
+impl<T> Decode<T> for NoPrevious {
+    fn decode(bytes: &[u8]) -> anyhow::Result<T> {
+        match bytes.first() {
+            Some(version) => Err(anyhow::anyhow!(
+                "The value has the unsupported version {version}"
+            )),
+            None => Err(anyhow::anyhow!("The value doesn't have the version byte")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::{
+        postcard::Postcard,
+        primitive::Primitive,
+    };
+
+    type OldCodec = Versioned<1, Postcard>;
+    type NewCodec = Versioned<2, Primitive<8>, OldCodec>;
+
+    #[test]
+    fn decode_new_codec_reads_the_value_written_with_the_old_envelope() {
+        // Given
+        let value = 1_000_000u64;
+        let bytes = OldCodec::encode(&value).into_owned();
+
+        // When
+        let decoded: u64 = NewCodec::decode(&bytes).unwrap();
+
+        // Then
+        assert_eq!(bytes[0], 1);
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn decode_old_codec_rejects_the_value_written_with_the_new_envelope() {
+        // Given
+        let value = 1_000_000u64;
+        let bytes = NewCodec::encode(&value).into_owned();
+
+        // When
+        let result: anyhow::Result<u64> = OldCodec::decode(&bytes);
+
+        // Then
+        assert_eq!(bytes[0], 2);
+        let err = result.expect_err("The old codec doesn't know the new version");
+        assert!(err.to_string().contains("unsupported version 2"));
+    }
+
+    #[test]
+    fn decode_new_codec_reads_the_value_written_with_the_new_envelope() {
+        // Given
+        let value = 1_000_000u64;
+        let bytes = NewCodec::encode(&value).into_owned();
+
+        // When
+        let decoded: u64 = NewCodec::decode(&bytes).unwrap();
+
+        // Then
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn decode_empty_value_is_rejected() {
+        // When
+        let result: anyhow::Result<u64> = NewCodec::decode(&[]);
+
+        // Then
+        assert!(result.is_err());
+    }
+}
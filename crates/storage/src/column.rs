@@ -72,6 +72,10 @@ pub enum Column {
     OwnedCoins = 20,
     /// The column of the table that stores `true` if `owner` owns `Message` with `message_id`
     OwnedMessageIds = 21,
+    /// See `FuelBlockIds`
+    FuelBlockIds = 22,
+    /// See `SealedBlockHeaders`
+    SealedBlockHeaders = 23,
 }
 
 impl Column {
@@ -32,6 +32,7 @@ pub mod tables;
 pub mod test_helpers;
 pub mod transactional;
 pub mod vm_storage;
+pub mod write_batch;
 
 pub use fuel_vm_private::storage::{
     ContractsAssetKey,
@@ -83,6 +84,21 @@ impl From<Error> for fuel_vm_private::prelude::InterpreterError<Error> {
     }
 }
 
+impl TryFrom<fuel_vm_private::prelude::InterpreterError<Error>> for Error {
+    type Error = fuel_vm_private::prelude::InterpreterError<Error>;
+
+    /// Recovers the storage error wrapped by the interpreter. Any other variant
+    /// is returned back unchanged.
+    fn try_from(
+        e: fuel_vm_private::prelude::InterpreterError<Error>,
+    ) -> core::result::Result<Self, Self::Error> {
+        match e {
+            fuel_vm_private::prelude::InterpreterError::Storage(e) => Ok(e),
+            e => Err(e),
+        }
+    }
+}
+
 impl From<Error> for fuel_vm_private::prelude::RuntimeError<Error> {
     fn from(e: Error) -> Self {
         fuel_vm_private::prelude::RuntimeError::Storage(e)
@@ -145,6 +161,21 @@ pub trait StorageBatchMutate<Type: Mappable>: StorageMutate<Type> {
         Type::Key: 'a;
 }
 
+/// The trait allows swapping the value in the storage with a new one in a single operation.
+/// Each backend implements it with its own swap primitive.
+pub trait StorageReplace<Type: Mappable>: StorageMutate<Type> {
+    /// Puts the `value` under the `key` and returns the value that was stored before.
+    /// Returns `None` if there was no value under the `key`.
+    ///
+    /// Prefer this method over a `get` followed by an `insert`, because the backend
+    /// performs the swap in one step.
+    fn replace(
+        &mut self,
+        key: &Type::Key,
+        value: &Type::Value,
+    ) -> Result<Option<Type::OwnedValue>>;
+}
+
 /// Creates `StorageError::NotFound` error with file and line information inside.
 ///
 /// # Examples
@@ -172,7 +203,11 @@ macro_rules! not_found {
 
 #[cfg(test)]
 mod test {
-    use crate::tables::Coins;
+    use crate::{
+        tables::Coins,
+        Error,
+    };
+    use fuel_vm_private::prelude::InterpreterError;
 
     #[test]
     fn not_found_output() {
@@ -187,4 +222,32 @@ mod test {
             format!("resource of type `fuel_core_types::entities::coins::coin::CompressedCoin` was not found at the: {}:{}", file!(), line!() - 1)
         );
     }
+
+    #[test]
+    fn codec_error_round_trips_through_interpreter_error() {
+        // Given
+        let error = Error::Codec(anyhow::anyhow!("corrupted value"));
+        let expected = format!("{error}");
+
+        // When
+        let interpreter_error: InterpreterError<Error> = error.into();
+        let error =
+            Error::try_from(interpreter_error).expect("Should recover the storage error");
+
+        // Then
+        assert!(matches!(error, Error::Codec(_)));
+        assert_eq!(format!("{error}"), expected);
+    }
+
+    #[test]
+    fn non_storage_interpreter_error_is_returned_back() {
+        let interpreter_error = InterpreterError::<Error>::DebugStateNotInitialized;
+
+        let result = Error::try_from(interpreter_error);
+
+        assert!(matches!(
+            result,
+            Err(InterpreterError::DebugStateNotInitialized)
+        ));
+    }
 }
@@ -16,6 +16,7 @@ use crate::{
     StorageBatchMutate,
     StorageInspect,
     StorageMutate,
+    StorageReplace,
     StorageSize,
 };
 use std::borrow::Cow;
@@ -111,6 +112,26 @@ where
     }
 }
 
+impl<Column, S, M> StorageReplace<M> for StructuredStorage<S>
+where
+    S: KeyValueStore<Column = Column>,
+    M: Mappable + TableWithBlueprint<Column = Column>,
+    M::Blueprint: Blueprint<M, S>,
+{
+    fn replace(
+        &mut self,
+        key: &M::Key,
+        value: &M::Value,
+    ) -> Result<Option<M::OwnedValue>, Self::Error> {
+        <M as TableWithBlueprint>::Blueprint::replace(
+            &mut self.storage,
+            key,
+            M::column(),
+            value,
+        )
+    }
+}
+
 impl<Column, S, M> StorageSize<M> for StructuredStorage<S>
 where
     S: KeyValueStore<Column = Column>,
@@ -678,3 +699,246 @@ pub mod test {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        test::InMemoryStorage,
+        StructuredStorage,
+        TableWithBlueprint,
+    };
+    use crate::{
+        blueprint::plain::Plain,
+        codec::{
+            postcard::Postcard,
+            raw::Raw,
+            Decode,
+            Encode,
+        },
+        column::Column,
+        kv_store::KeyValueStore,
+        tables::ContractsLatestUtxo,
+        Mappable,
+        StorageAsMut,
+        StorageAsRef,
+        StorageReplace,
+    };
+    use fuel_core_types::{
+        entities::contract::ContractUtxoInfo,
+        fuel_tx::{
+            ContractId,
+            TxPointer,
+            UtxoId,
+        },
+    };
+
+    /// The codec encodes values with [`Postcard`], but panics on any attempt to decode them.
+    struct PanicOnDecode;
+
+    impl Encode<u64> for PanicOnDecode {
+        type Encoder<'a> = <Postcard as Encode<u64>>::Encoder<'a>;
+
+        fn encode(t: &u64) -> Self::Encoder<'_> {
+            <Postcard as Encode<u64>>::encode(t)
+        }
+    }
+
+    impl Decode<u64> for PanicOnDecode {
+        fn decode(_: &[u8]) -> anyhow::Result<u64> {
+            panic!("The value should not be decoded")
+        }
+    }
+
+    struct NoDecodeTable;
+
+    impl Mappable for NoDecodeTable {
+        type Key = Self::OwnedKey;
+        type OwnedKey = [u8; 32];
+        type Value = Self::OwnedValue;
+        type OwnedValue = u64;
+    }
+
+    impl TableWithBlueprint for NoDecodeTable {
+        type Blueprint = Plain<Raw, PanicOnDecode>;
+        type Column = Column;
+
+        fn column() -> Column {
+            Column::ContractsRawCode
+        }
+    }
+
+    #[test]
+    fn contains_key_does_not_decode_value() {
+        let mut storage = InMemoryStorage::<Column>::default();
+        let mut structured_storage = StructuredStorage::new(&mut storage);
+        let existing_key = [1; 32];
+        let missing_key = [2; 32];
+
+        // Given
+        structured_storage
+            .storage_as_mut::<NoDecodeTable>()
+            .insert(&existing_key, &42)
+            .expect("Insertion of a new value doesn't require decoding");
+
+        // When
+        let existing = structured_storage
+            .storage_as_ref::<NoDecodeTable>()
+            .contains_key(&existing_key)
+            .unwrap();
+        let missing = structured_storage
+            .storage_as_ref::<NoDecodeTable>()
+            .contains_key(&missing_key)
+            .unwrap();
+
+        // Then
+        assert!(existing);
+        assert!(!missing);
+    }
+
+    #[test]
+    fn replace_returns_the_old_value() {
+        let mut storage = InMemoryStorage::<Column>::default();
+        let mut structured_storage = StructuredStorage::new(&mut storage);
+        let contract_id = ContractId::from([1; 32]);
+        let old_info = ContractUtxoInfo {
+            utxo_id: UtxoId::new([2; 32].into(), 0),
+            tx_pointer: TxPointer::new(1.into(), 0),
+        };
+        let new_info = ContractUtxoInfo {
+            utxo_id: UtxoId::new([3; 32].into(), 1),
+            tx_pointer: TxPointer::new(2.into(), 1),
+        };
+
+        // Given
+        structured_storage
+            .storage_as_mut::<ContractsLatestUtxo>()
+            .insert(&contract_id, &old_info)
+            .unwrap();
+
+        // When
+        let replaced = StorageReplace::<ContractsLatestUtxo>::replace(
+            &mut structured_storage,
+            &contract_id,
+            &new_info,
+        )
+        .unwrap();
+
+        // Then
+        assert_eq!(replaced, Some(old_info));
+        let stored = structured_storage
+            .storage_as_ref::<ContractsLatestUtxo>()
+            .get(&contract_id)
+            .unwrap()
+            .unwrap()
+            .into_owned();
+        assert_eq!(stored, new_info);
+    }
+
+    #[test]
+    fn replace_returns_none_when_value_is_absent() {
+        let mut storage = InMemoryStorage::<Column>::default();
+        let mut structured_storage = StructuredStorage::new(&mut storage);
+        let contract_id = ContractId::from([1; 32]);
+        let info = ContractUtxoInfo {
+            utxo_id: UtxoId::new([2; 32].into(), 0),
+            tx_pointer: TxPointer::new(1.into(), 0),
+        };
+
+        // When
+        let replaced = StorageReplace::<ContractsLatestUtxo>::replace(
+            &mut structured_storage,
+            &contract_id,
+            &info,
+        )
+        .unwrap();
+
+        // Then
+        assert_eq!(replaced, None);
+        assert!(structured_storage
+            .storage_as_ref::<ContractsLatestUtxo>()
+            .contains_key(&contract_id)
+            .unwrap());
+    }
+
+    /// The custom codec that stores values as big-endian bytes.
+    struct BigEndian;
+
+    impl Encode<u64> for BigEndian {
+        type Encoder<'a> = [u8; 8];
+
+        fn encode(t: &u64) -> Self::Encoder<'_> {
+            t.to_be_bytes()
+        }
+    }
+
+    impl Decode<u64> for BigEndian {
+        fn decode(bytes: &[u8]) -> anyhow::Result<u64> {
+            Ok(u64::from_be_bytes(bytes.try_into()?))
+        }
+    }
+
+    struct CustomCodecTable;
+
+    impl Mappable for CustomCodecTable {
+        type Key = Self::OwnedKey;
+        type OwnedKey = [u8; 32];
+        type Value = Self::OwnedValue;
+        type OwnedValue = u64;
+    }
+
+    impl TableWithBlueprint for CustomCodecTable {
+        type Blueprint = Plain<Raw, BigEndian>;
+        type Column = Column;
+
+        fn column() -> Column {
+            Column::ContractsInfo
+        }
+    }
+
+    #[test]
+    fn tables_use_their_own_value_codecs() {
+        let mut storage = InMemoryStorage::<Column>::default();
+        let mut structured_storage = StructuredStorage::new(&mut storage);
+        let key = [1; 32];
+        let value = 0x0102_0304_0506_0708u64;
+        let contract_id = ContractId::from(key);
+        let info = ContractUtxoInfo {
+            utxo_id: UtxoId::new([2; 32].into(), 0),
+            tx_pointer: TxPointer::new(1.into(), 0),
+        };
+
+        // When
+        structured_storage
+            .storage_as_mut::<CustomCodecTable>()
+            .insert(&key, &value)
+            .unwrap();
+        structured_storage
+            .storage_as_mut::<ContractsLatestUtxo>()
+            .insert(&contract_id, &info)
+            .unwrap();
+
+        // Then
+        let custom_bytes = storage
+            .get(&key, Column::ContractsInfo)
+            .unwrap()
+            .expect("The value should be stored");
+        assert_eq!(custom_bytes.as_slice(), value.to_be_bytes());
+        let default_bytes = storage
+            .get(contract_id.as_ref(), Column::ContractsLatestUtxo)
+            .unwrap()
+            .expect("The value should be stored");
+        assert_eq!(
+            default_bytes.as_slice(),
+            postcard::to_allocvec(&info).unwrap()
+        );
+
+        let structured_storage = StructuredStorage::new(&mut storage);
+        let decoded = structured_storage
+            .storage_as_ref::<CustomCodecTable>()
+            .get(&key)
+            .unwrap()
+            .unwrap()
+            .into_owned();
+        assert_eq!(decoded, value);
+    }
+}
@@ -0,0 +1,177 @@
+//! The builder of the atomic writes across several tables.
+
+use crate::{
+    transactional::Transactional,
+    Error as StorageError,
+    Mappable,
+    Result as StorageResult,
+    StorageMutate,
+};
+use core::borrow::Borrow;
+
+type Operation<Storage> = Box<dyn FnOnce(&mut Storage) -> StorageResult<()>>;
+
+/// Accumulates the typed `put` and `delete` operations over any [`Mappable`] tables
+/// and applies all of them within one storage transaction.
+pub struct WriteBatch<Storage: ?Sized> {
+    operations: Vec<Operation<Storage>>,
+}
+
+impl<Storage: ?Sized> Default for WriteBatch<Storage> {
+    fn default() -> Self {
+        Self {
+            operations: Vec::new(),
+        }
+    }
+}
+
+impl<Storage: ?Sized> core::fmt::Debug for WriteBatch<Storage> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("WriteBatch")
+            .field("operations", &self.operations.len())
+            .finish()
+    }
+}
+
+impl<Storage: ?Sized> WriteBatch<Storage> {
+    /// Creates an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of the accumulated operations.
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Returns `true` if the batch has no operations.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Schedules the insertion of the `value` under the `key` into the `M` table.
+    pub fn put<M>(mut self, key: &M::Key, value: &M::Value) -> Self
+    where
+        M: Mappable + 'static,
+        <M::Key as ToOwned>::Owned: 'static,
+        <M::Value as ToOwned>::Owned: 'static,
+        Storage: StorageMutate<M, Error = StorageError>,
+    {
+        let key = key.to_owned();
+        let value = value.to_owned();
+        self.operations.push(Box::new(move |storage: &mut Storage| {
+            storage.insert(key.borrow(), value.borrow())?;
+            Ok(())
+        }));
+        self
+    }
+
+    /// Schedules the removal of the `key` from the `M` table.
+    pub fn delete<M>(mut self, key: &M::Key) -> Self
+    where
+        M: Mappable + 'static,
+        <M::Key as ToOwned>::Owned: 'static,
+        Storage: StorageMutate<M, Error = StorageError>,
+    {
+        let key = key.to_owned();
+        self.operations.push(Box::new(move |storage: &mut Storage| {
+            storage.remove(key.borrow())?;
+            Ok(())
+        }));
+        self
+    }
+
+    /// Applies all operations in the order they were added and commits them atomically.
+    /// If any operation fails, nothing is committed.
+    pub fn commit<T>(self, storage: &T) -> StorageResult<()>
+    where
+        T: Transactional<Storage = Storage> + ?Sized,
+    {
+        let mut transaction = storage.transaction();
+        for operation in self.operations {
+            operation(transaction.as_mut())?;
+        }
+        transaction.commit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tables::{
+            Coins,
+            ContractsRawCode,
+            Messages,
+        },
+        test_helpers::MockStorage,
+    };
+    use fuel_core_types::{
+        entities::{
+            coins::coin::CompressedCoin,
+            message::Message,
+        },
+        fuel_tx::{
+            ContractId,
+            UtxoId,
+        },
+        fuel_types::Nonce,
+    };
+
+    #[test]
+    fn commit_applies_all_operations_across_tables() {
+        // Given
+        let mut inner = MockStorage::default();
+        inner
+            .expect_insert::<Coins>()
+            .times(1)
+            .returning(|_, _| Ok(None));
+        inner
+            .expect_insert::<Messages>()
+            .times(1)
+            .returning(|_, _| Ok(None));
+        inner
+            .expect_remove::<ContractsRawCode>()
+            .times(1)
+            .returning(|_| Ok(None));
+        inner.expect_commit().times(1).returning(|| Ok(()));
+        let storage = inner.into_transactional();
+        let batch = WriteBatch::new()
+            .put::<Coins>(&UtxoId::default(), &CompressedCoin::default())
+            .put::<Messages>(&Nonce::default(), &Message::default())
+            .delete::<ContractsRawCode>(&ContractId::default());
+
+        // When
+        let result = batch.commit(&storage);
+
+        // Then
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn commit_failed_operation_commits_nothing() {
+        // Given
+        let mut inner = MockStorage::default();
+        inner
+            .expect_insert::<Coins>()
+            .times(1)
+            .returning(|_, _| Ok(None));
+        inner
+            .expect_insert::<Messages>()
+            .times(1)
+            .returning(|_, _| Err(StorageError::Other(anyhow::anyhow!("forced"))));
+        inner.expect_remove::<ContractsRawCode>().never();
+        inner.expect_commit().never();
+        let storage = inner.into_transactional();
+        let batch = WriteBatch::new()
+            .put::<Coins>(&UtxoId::default(), &CompressedCoin::default())
+            .put::<Messages>(&Nonce::default(), &Message::default())
+            .delete::<ContractsRawCode>(&ContractId::default());
+
+        // When
+        let result = batch.commit(&storage);
+
+        // Then
+        assert!(result.is_err());
+    }
+}
@@ -9,8 +9,10 @@ use crate::{
     fuel_tx::{
         field::{
             Inputs,
+            Maturity,
             Outputs,
             ScriptGasLimit,
+            Witnesses,
         },
         Cacheable,
         Chargeable,
@@ -21,8 +23,11 @@ use crate::{
         Transaction,
         TxId,
         UtxoId,
+        Witness,
     },
     fuel_types::{
+        AssetId,
+        BlockHeight,
         ContractId,
         Nonce,
     },
@@ -106,6 +111,14 @@ impl PoolTransaction {
         }
     }
 
+    /// Returns the height the transaction can be included at the earliest.
+    pub fn maturity(&self) -> BlockHeight {
+        match self {
+            PoolTransaction::Script(script) => script.transaction().maturity(),
+            PoolTransaction::Create(create) => create.transaction().maturity(),
+        }
+    }
+
     pub fn inputs(&self) -> &Vec<Input> {
         match self {
             PoolTransaction::Script(script) => script.transaction().inputs(),
@@ -119,6 +132,13 @@ impl PoolTransaction {
             PoolTransaction::Create(create) => create.transaction().outputs(),
         }
     }
+
+    pub fn witnesses(&self) -> &Vec<Witness> {
+        match self {
+            PoolTransaction::Script(script) => script.transaction().witnesses(),
+            PoolTransaction::Create(create) => create.transaction().witnesses(),
+        }
+    }
 }
 
 impl From<&PoolTransaction> for Transaction {
@@ -300,6 +320,14 @@ pub enum Error {
     TTLReason,
     #[error("Transaction squeezed out because {0}")]
     SqueezedOut(String),
+    #[error("Transaction removed. A higher fee tx {0:#x} spends the same input")]
+    Conflict(TxId),
+    #[error("Transaction replaced by tx {0:#x} of the same sender with a higher fee")]
+    Replaced(TxId),
+    #[error("Transaction removed. Its signatures or predicates are invalid")]
+    InvalidSignature,
+    #[error("Transaction removed. The fee can't be paid in the asset {0:#x}")]
+    UnsupportedFeeAsset(AssetId),
     // TODO: We need it for now until channels are removed from TxPool.
     #[error("Got some unexpected error: {0}")]
     Other(String),